@@ -1,17 +1,46 @@
+#[derive(Debug)]
 pub enum Action {
     AppNavigation(AppNavigationAction),
     MainView(MainViewActions),
     Editing(EditingAction),
     Searching(SearchingAction),
+    Command(CommandAction),
+    Problems(ProblemsAction),
+    Preview(PreviewAction),
+    Table(TableAction),
+    Confirm(ConfirmAction),
+    Tutorial(TutorialAction),
     App(SystemAction),
 }
 
+impl Action {
+    /// Whether the action changes the document, or starts changing it.
+    pub fn modifies_document(&self) -> bool {
+        return matches!(
+            self,
+            Action::AppNavigation(AppNavigationAction::ToEditingScreen)
+                | Action::Editing(EditingAction::EditExisting)
+                | Action::Preview(PreviewAction::Replace)
+                | Action::Table(TableAction::EditCell)
+                | Action::MainView(MainViewActions::Delete { .. })
+                | Action::MainView(MainViewActions::Paste)
+                | Action::MainView(MainViewActions::Exchange)
+                | Action::MainView(MainViewActions::MoveInto)
+                | Action::MainView(MainViewActions::Duplicate)
+        );
+    }
+}
+
+#[derive(Debug)]
 pub enum AppNavigationAction {
     ToViewingScreen,
     ToEditingScreen,
     ToSearchingWidget,
+    ToCommandWidget,
+    ToProblemsPanel,
 }
 
+#[derive(Debug)]
 pub enum MainViewActions {
     MoveDown,
     MoveUp,
@@ -19,25 +48,108 @@ pub enum MainViewActions {
     MoveToBottom,
     MoveHalfPageDown,
     MoveHalfPageUp,
+    /// Shows or hides the readable dates next to timestamps.
+    ToggleTimestamps,
+    /// Shows or hides what the environment variable placeholders resolve to.
+    ToggleEnvValues,
+    /// Copies the value under the cursor to the clipboard, or to the selected register.
+    Yank,
+    /// Copies the value under the cursor as an escaped, quoted string.
+    YankEscaped,
+    /// Deletes the node under the cursor. Unless `confirmed`, deleting one holding other values asks first.
+    Delete { confirmed: bool },
+    /// Names the register of the next yank, delete or paste.
+    SelectRegister(char),
+    /// Inserts the node of the selected register after the cursor.
+    Paste,
+    /// Marks the node under the cursor, or swaps it with the marked sibling.
+    Exchange,
+    /// Marks the node under the cursor, or moves the marked one into the container under the cursor.
+    MoveInto,
+    /// Starts or stops selecting a range of the elements of an array.
+    ToggleVisual,
+    /// Inserts a copy of the node under the cursor after it and edits the copy.
+    Duplicate,
 }
 
+#[allow(dead_code)]
+#[derive(Debug)]
 pub enum EditingAction {
     SwitchToKey,
     SwitchToValue,
     AppendChar(char),
     AppendToKey(char),
     AppendToValue(char),
-    MoveCursor(CursorDirection),
+    /// Moves the cursor of the focused input, extending its selection when `selecting`.
+    MoveCursor { direction: CursorDirection, selecting: bool },
+    /// Deletes the character before the cursor in the focused input.
+    PopChar,
     PopFromKey,
     PopFromValue,
+    /// Deletes the word before the cursor in the focused input.
+    PopWord,
+    /// Deletes the character under the cursor in the focused input.
+    DeleteForward,
+    /// Deletes from the cursor of the focused input to where it would move, like to the start.
+    DeleteTo(CursorDirection),
+    /// Swaps the characters around the cursor of the focused input.
+    TransposeChars,
+    /// Undoes the last edit of the focused input.
+    Undo,
+    /// Shows or hides the value behind `•`, like the values of secrets are.
+    ToggleHidden,
+    ClearInput,
+    /// Copies the selection of the focused input to the clipboard, and deletes it when `cut`.
+    CopySelection { cut: bool },
     Submit,
+    /// Submits after the replacement of the sibling with the same key was confirmed.
+    SubmitReplacing,
     EditExisting,
+    /// Selects the next of the values the schema allows, or of the suggested keys.
+    SelectNext,
+    SelectPrevious,
+    /// Fills in the selected suggestion and moves on to the next input.
+    AcceptSuggestion,
+    /// Fills the focused input with the entry entered before (`older`) or after the one it holds.
+    RecallHistory { older: bool },
+}
+
+impl EditingAction {
+    pub fn from_input_edit(edit: InputEdit) -> EditingAction {
+        return match edit {
+            InputEdit::AppendChar(c) => EditingAction::AppendChar(c),
+            InputEdit::MoveCursor { direction, selecting } => EditingAction::MoveCursor { direction, selecting },
+            InputEdit::PopChar => EditingAction::PopChar,
+            InputEdit::PopWord => EditingAction::PopWord,
+            InputEdit::DeleteForward => EditingAction::DeleteForward,
+            InputEdit::DeleteTo(direction) => EditingAction::DeleteTo(direction),
+            InputEdit::TransposeChars => EditingAction::TransposeChars,
+            InputEdit::Undo => EditingAction::Undo,
+            InputEdit::Clear => EditingAction::ClearInput,
+            InputEdit::ToggleHidden => EditingAction::ToggleHidden,
+            InputEdit::CopySelection { cut } => EditingAction::CopySelection { cut },
+        };
+    }
 }
 
+#[derive(Debug)]
 pub enum SearchingAction {
     AppendChar(char),
-    MoveCursor(CursorDirection),
+    /// Moves the cursor, extending the selection when `selecting`.
+    MoveCursor { direction: CursorDirection, selecting: bool },
     PopChar,
+    /// Deletes the word before the cursor.
+    PopWord,
+    /// Deletes the character under the cursor.
+    DeleteForward,
+    /// Deletes from the cursor to where it would move, like to the start.
+    DeleteTo(CursorDirection),
+    /// Swaps the characters around the cursor.
+    TransposeChars,
+    /// Undoes the last edit.
+    Undo,
+    /// Copies the selection to the clipboard, and deletes it when `cut`.
+    CopySelection { cut: bool },
     ClearSearch,
     GoToPrevMatch,
     GoToNextMatch,
@@ -45,13 +157,169 @@ pub enum SearchingAction {
     ReportResults,
 }
 
+impl SearchingAction {
+    /// The search has nothing hidden, so `None` for showing it.
+    pub fn from_input_edit(edit: InputEdit) -> Option<SearchingAction> {
+        return match edit {
+            InputEdit::AppendChar(c) => Some(SearchingAction::AppendChar(c)),
+            InputEdit::MoveCursor { direction, selecting } => Some(SearchingAction::MoveCursor { direction, selecting }),
+            InputEdit::PopChar => Some(SearchingAction::PopChar),
+            InputEdit::PopWord => Some(SearchingAction::PopWord),
+            InputEdit::DeleteForward => Some(SearchingAction::DeleteForward),
+            InputEdit::DeleteTo(direction) => Some(SearchingAction::DeleteTo(direction)),
+            InputEdit::TransposeChars => Some(SearchingAction::TransposeChars),
+            InputEdit::Undo => Some(SearchingAction::Undo),
+            InputEdit::Clear => Some(SearchingAction::ClearSearch),
+            InputEdit::ToggleHidden => None,
+            InputEdit::CopySelection { cut } => Some(SearchingAction::CopySelection { cut }),
+        };
+    }
+}
+
+#[derive(Debug)]
+pub enum CommandAction {
+    AppendChar(char),
+    /// Moves the cursor, extending the selection when `selecting`.
+    MoveCursor { direction: CursorDirection, selecting: bool },
+    PopChar,
+    /// Deletes the word before the cursor.
+    PopWord,
+    /// Deletes the character under the cursor.
+    DeleteForward,
+    /// Deletes from the cursor to where it would move, like to the start.
+    DeleteTo(CursorDirection),
+    /// Swaps the characters around the cursor.
+    TransposeChars,
+    /// Undoes the last edit.
+    Undo,
+    /// Copies the selection to the clipboard, and deletes it when `cut`.
+    CopySelection { cut: bool },
+    ClearInput,
+    /// Completes the word at the end of the command line.
+    Complete,
+    Submit,
+}
+
+impl CommandAction {
+    /// The command line has nothing hidden, so `None` for showing it.
+    pub fn from_input_edit(edit: InputEdit) -> Option<CommandAction> {
+        return match edit {
+            InputEdit::AppendChar(c) => Some(CommandAction::AppendChar(c)),
+            InputEdit::MoveCursor { direction, selecting } => Some(CommandAction::MoveCursor { direction, selecting }),
+            InputEdit::PopChar => Some(CommandAction::PopChar),
+            InputEdit::PopWord => Some(CommandAction::PopWord),
+            InputEdit::DeleteForward => Some(CommandAction::DeleteForward),
+            InputEdit::DeleteTo(direction) => Some(CommandAction::DeleteTo(direction)),
+            InputEdit::TransposeChars => Some(CommandAction::TransposeChars),
+            InputEdit::Undo => Some(CommandAction::Undo),
+            InputEdit::Clear => Some(CommandAction::ClearInput),
+            InputEdit::ToggleHidden => None,
+            InputEdit::CopySelection { cut } => Some(CommandAction::CopySelection { cut }),
+        };
+    }
+}
+
+#[derive(Debug)]
+pub enum TableAction {
+    MoveDown,
+    MoveUp,
+    MoveLeft,
+    MoveRight,
+    /// Opens the value input on the selected cell.
+    EditCell,
+    SubmitCell,
+    CancelEditing,
+    /// Edits the text of the cell being edited.
+    EditInput(InputEdit),
+    /// Shows or hides the summary of each column under the rows.
+    ToggleSummaries,
+    /// Goes back to the tree, on the selected row.
+    Close,
+}
+
+#[derive(Debug)]
+pub enum ProblemsAction {
+    SelectNext,
+    SelectPrevious,
+    JumpToSelected,
+}
+
+#[derive(Debug)]
+pub enum PreviewAction {
+    DecodeBase64,
+    DecodeJwt,
+    DecodeUrl,
+    /// Shows details about the node under the cursor.
+    Inspect,
+    /// Lists the keys of every screen.
+    Help,
+    ScrollDown,
+    ScrollUp,
+    /// Replaces the value under the cursor with what the preview offers.
+    Replace,
+}
+
+#[derive(Debug)]
+pub enum ConfirmAction {
+    /// Picks the choice bound to the key, if one is.
+    Choose(char),
+    Cancel,
+}
+
+#[derive(Debug)]
+pub enum TutorialAction {
+    Next,
+    Previous,
+    Close,
+}
+
+/// An edit of the text of an input, made with the same keys in every input. Each screen maps it
+/// onto its own action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEdit {
+    AppendChar(char),
+    /// Moves the cursor, extending the selection when `selecting`.
+    MoveCursor { direction: CursorDirection, selecting: bool },
+    PopChar,
+    /// Deletes the word before the cursor.
+    PopWord,
+    /// Deletes the character under the cursor.
+    DeleteForward,
+    /// Deletes from the cursor to where it would move, like to the start.
+    DeleteTo(CursorDirection),
+    /// Swaps the characters around the cursor.
+    TransposeChars,
+    Undo,
+    Clear,
+    /// Shows or hides the text behind `•`.
+    ToggleHidden,
+    /// Copies the selection to the clipboard, and deletes it when `cut`.
+    CopySelection { cut: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CursorDirection {
     Left,
     // Up,
     Right,
     // Down,
+    /// To the start of the word before the cursor.
+    WordLeft,
+    /// To the end of the word after the cursor.
+    WordRight,
+    Start,
+    End,
 }
 
+#[derive(Debug)]
 pub enum SystemAction {
     Quit,
+    Save,
+    /// Saves even if the file was deleted or emptied outside of todd.
+    ForceSave,
+    /// Saves, and quits if that worked.
+    SaveAndQuit,
+    ForceSaveAndQuit,
+    /// Quits and drops the unsaved changes, recovery journal included.
+    DiscardAndQuit,
 }
@@ -1,17 +1,28 @@
-use std::{cell::RefCell, fs::{File, Metadata}, io::Seek, time::{Duration, Instant}};
+use std::{cell::RefCell, fs::{self, Metadata}, io, path::PathBuf, time::{Duration, Instant}};
 
 use color_eyre::{eyre::bail, Result};
-use ratatui::{layout::Size, widgets::ScrollbarState, DefaultTerminal}
+use ratatui::{layout::{Rect, Size}, widgets::ScrollbarState, DefaultTerminal}
 ;
-use serde_json::Value;
+use serde_json::{Number, Value};
 
-use crate::{actions::{Action, AppNavigationAction, CursorDirection, EditingAction, MainViewActions, SearchingAction, SystemAction}, utils::json::{get_nested_object_to_insert_into, get_current_value_at_position}, widgets::text_input::TextInput};
+use crate::{actions::{Action, AppNavigationAction, CommandAction, CursorDirection, EditingAction, MainViewActions, ProblemsAction, SearchingAction, SystemAction}, clipboard::copy_selection, commands::Command, config::Config, confirm::Confirm, i18n::{tr, translate}, formats::FileFormat, input_history::InputHistory, keymap::KeyChord, mouse::Drag, preview::Preview, registers::Registers, schema::{Schema, SchemaOnSave}, table::Table, tutorial::Tutorial, journal::{remove_journal, write_journal}, utils::{edit::insert_after, json::{get_line_at_path, get_nested_object_to_insert_into, get_current_value_at_position, path_to_pointer, PathSegment}, jsonc::Comments, stats::descendants_count}, widgets::text_input::TextInput};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum CurrentScreen {
     ViewingFile,
     Editing,
     Searching,
+    Command,
+    /// The panel listing the problems found in the document.
+    Problems,
+    /// A popup previewing what the value under the cursor holds.
+    Preview,
+    /// An array of objects shown as a table.
+    Table,
+    /// A popup asking to confirm something.
+    Confirm,
+    /// The guided tour of the basics.
+    Tutorial,
 }
 
 #[derive(Debug, PartialEq)]
@@ -26,6 +37,18 @@ pub enum EditingMode {
     Editing,
 }
 
+/// Whether the opened file is still on disk the way todd left it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileStatus {
+    Present,
+    /// The file was removed by something other than todd.
+    Deleted,
+    /// The file was emptied by something other than todd.
+    Truncated,
+    /// The file doesn't exist yet. It's created on the first save.
+    New,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ValuePair {
     pub indentation: usize,
@@ -43,6 +66,23 @@ pub struct ReportedMessage {
     pub kind: ReportedMessageKinds,
 }
 
+/// Something wrong with the document, listed in the problems panel.
+#[derive(Debug)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    /// Where in the document the problem is.
+    pub path: Vec<PathSegment>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProblemKind {
+    /// A key that appears twice in the same object of the file.
+    DuplicateKey,
+    /// A value that doesn't match the schema.
+    Schema,
+}
+
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum ReportedMessageKinds {
@@ -56,17 +96,23 @@ pub enum ReportedMessageKinds {
 
 /// Represents the parent app state.
 #[derive(Debug)]
-pub struct App<'a> {
+pub struct App {
     /// The key element in the popup for inserting new pairs.
     pub key_input: TextInput,
     /// The currently being edited json value.
     pub value_input: TextInput,
     /// The search input widget.
     pub search_widget: TextInput,
+    /// The command line input (`:`).
+    pub command_input: TextInput,
     /// If there is an active search, this data structure saves all matches found by their (rendered) line numbers.
     pub search_matches: Vec<usize>,
     /// The representation of the json file data. It could be an array or an object at the top level.
     pub json: Value,
+    /// The document while the tree shows a sorted copy of it in `json`. It's what gets saved.
+    pub unsorted_json: Option<Value>,
+    /// The JSON data as it was when it was last loaded or saved. Unsaved changes are compared against it.
+    pub saved_json: Value,
     /// Holds all the pairs serialized out of the JSON. Has empty pairs to represent a line separator 
     /// for the beginning of an array value.
     pub json_pairs: Vec<ValuePair>,
@@ -91,28 +137,91 @@ pub struct App<'a> {
     // @Fix: It's an Option because of tests and maintaining the default() method. Change all the tests to read
     // from a file to make this non-optional.
     pub file_metadata: Option<Metadata>,
+    /// The path of the opened file. Changes are saved to it.
+    pub file_path: Option<PathBuf>,
+    pub file_status: FileStatus,
+    /// How the opened file is stored on disk. Saving writes it back in the same format.
+    pub file_format: FileFormat,
+    /// The comments of a JSONC file, shown in the tree and written back on save.
+    pub comments: Comments,
+    /// Whether the JSON data has changes that weren't saved to the file yet.
+    pub is_dirty: bool,
+    /// The problems found in the document, listed in the problems panel.
+    pub problems: Vec<Problem>,
+    /// The index of the problem selected in the problems panel.
+    pub selected_problem: usize,
+    /// The content of the preview popup, while it's open.
+    pub preview: Option<Preview>,
+    /// The array shown in the table mode, while it's open.
+    pub table: Option<Table>,
+    /// The confirmation popup, while it's open.
+    pub confirm: Option<Confirm>,
+    /// The step of the tutorial shown, while it's open.
+    pub tutorial: Option<Tutorial>,
+    /// A key that collides with an existing one and was warned about. Submitting it again replaces the existing value.
+    pub confirmed_key_collision: Option<String>,
+    /// The JSON Schema the document is validated against, if any.
+    pub schema: Option<Schema>,
+    /// The values the schema allows for the value being edited, when it restricts them to a list.
+    pub value_choices: Vec<Value>,
+    /// The index of the allowed value selected in the edit popup.
+    pub selected_choice: usize,
+    /// Whether timestamps are annotated with the date they stand for.
+    pub show_timestamps: bool,
+    /// Whether `${VARIABLE}` placeholders are annotated with the environment's values.
+    pub show_env_values: bool,
+    /// The index of the suggestion selected under the key input.
+    pub selected_suggestion: usize,
+    /// The keys entered in the edit popup during the session.
+    pub key_history: InputHistory,
+    /// The values entered in the edit popup during the session.
+    pub value_history: InputHistory,
+    pub config: Config,
+    /// The keys pressed so far of a sequence bound to an action, like the first `Z` of `ZZ`.
+    pub pending_keys: Vec<KeyChord>,
+    /// Whether the key after `"` names a register.
+    pub is_choosing_register: bool,
+    /// The register named for the next yank, delete or paste.
+    pub selected_register: Option<char>,
+    pub registers: Registers,
+    /// The node marked to be swapped with a sibling.
+    pub exchange_mark: Option<Vec<PathSegment>>,
+    /// The node marked to be moved into another object or array, and how many of its siblings
+    /// after it go with it, when elements are marked in visual mode.
+    pub move_mark: Option<(Vec<PathSegment>, usize)>,
+    /// Where visual mode started, selecting the elements from it to the cursor.
+    pub visual_anchor: Option<Vec<PathSegment>>,
+    /// The element of an array dragged with the mouse.
+    pub drag: Option<Drag>,
+    /// Where the tree was last drawn, and the line shown at each of its rows.
+    pub tree_area: Rect,
+    pub row_lines: Vec<usize>,
+    /// The title todd gave the terminal, once it did.
+    pub shown_title: Option<String>,
+    /// Whether to draw without colors (`NO_COLOR` or `--no-color`).
+    pub no_color: bool,
     pub size: Size,
-    file: Option<&'a mut File>,
     running: bool,
 }
 
-impl<'a> App<'a> {
+impl App {
     /// Construct the app and sets the JSON data.
     pub fn new(
         json_content: &str, 
         file_metadata: Option<Metadata>, 
-        file: Option<&'a mut File>,
+        file_path: Option<PathBuf>,
         size: Size,
     ) -> Result<Self> {
         let mut app = Self::default();
 
-        let json = match serde_json::from_str(json_content) {
+        let json: Value = match serde_json::from_str(json_content) {
             Ok(value) => value,
             Err(err) => bail!("Failed to parse JSON: {}", err)
         };
+        app.saved_json = json.clone();
         app.json = json;
         app.file_metadata = file_metadata;
-        app.file = file;
+        app.file_path = file_path;
         app.size = size;
 
         return Ok(app);
@@ -121,24 +230,42 @@ impl<'a> App<'a> {
     /// Run the application's main loop.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         self.running = true;
+        self.capture_mouse()?;
 
         while self.running {
             terminal.draw(|frame| {
                 self.draw(frame);
             })?;
+            self.update_terminal_title()?;
 
             self.handle_crossterm_events()?;
+            self.check_file_status();
         }
 
+        self.restore_terminal_title()?;
+        self.release_mouse()?;
+
+        self.save_session();
+
         return Ok(());
     }
 
     pub fn update(&mut self, action: Action) {
+        if action.modifies_document() && !self.check_editable() {
+            return;
+        }
+
         match action {
             Action::AppNavigation(action) => self.handle_app_navigation_actions(action),
             Action::MainView(action) => self.handle_main_view_messages(action),
             Action::Editing(action) => self.handle_editing_actions(action),
             Action::Searching(action) => self.handle_searching_actions(action),
+            Action::Command(action) => self.handle_command_actions(action),
+            Action::Problems(action) => self.handle_problems_actions(action),
+            Action::Preview(action) => self.handle_preview_actions(action),
+            Action::Table(action) => self.handle_table_actions(action),
+            Action::Confirm(action) => self.handle_confirm_actions(action),
+            Action::Tutorial(action) => self.handle_tutorial_actions(action),
             Action::App(action) => self.handle_app_actions(action),
         }
     }
@@ -159,92 +286,221 @@ impl<'a> App<'a> {
         };
         
         
-        let value = serde_json::to_value(self.value_input.content()).unwrap();
-        
-        // Serialize/Parse the value to its correct type by trying to parse it.
-        // The input text component it comes from treats & makes it a string
-        // because that's how it views and modify it. But it may be a number
-        // so we insert it as such.
-        // The result is still a value, but it's now constructed with information
-        // about what it really is instead of saying a Value::String for everything.
-        let value: Value = match value {
-            Value::String(s) => {
-                if s.parse::<f64>().is_ok() {
-                    Value::Number(s.parse().unwrap())
-                } else if s.parse::<bool>().is_ok() {
-                    Value::Bool(s.parse().unwrap())
-                } else {
-                    Value::String(s)
-                }
-            },
-            _ => value, // The value is an object or an array. Not even a string.
-        };
-        
-        let (object_to_insert_into, index) = get_nested_object_to_insert_into(self.line_at_cursor_without_empty_lines(), &mut self.json);
-        if let Some(object_to_insert_into) = object_to_insert_into {
-            
-            match object_to_insert_into {
-                Value::Object(map) => {
-                    // Check if we're at the last index. If yes, just insert, otherwise, insert safely
-                    // at `index + 1`.
-                    if index > map.len() - 2 {
-                        map.insert(
-                            self.key_input.content().to_string(),
-                            value,
-                        );
-                    } else {
-                        map.shift_insert(
-                            index + 1,
-                            self.key_input.content().to_string(),
-                            value,
-                        );
-                    }
-                },
-                Value::Array(values) => {
-                    values.insert(index + 1, value);
-                },
-                _ => {}
-            }
+        let key = self.key_input.content().to_string();
+        if !self.is_inside_array() && !self.confirm_key_collision(&key) {
+            return;
         }
+
+        let value = self.chosen_value().unwrap_or_else(|| value_from_input(self.value_input.content()));
+        
+        self.insert_value_after_cursor(self.key_input.content().to_string(), value);
         
         self.report(
-            format!("Inserted new key-value pair: {} -> {}", self.key_input.content(), self.value_input.content()),
+            tr!("Inserted new key-value pair: {} -> {}", self.key_input.content(), self.value_input.content()),
             ReportedMessageKinds::Success,
             Duration::from_secs(3)
         );
 
-        if let Some(file) = self.file.as_mut() {
-            // Clear the file by truncating it to 0 bytes
-            if let Err(err) = file.set_len(0) {
+        self.mark_dirty();
+        self.remember_inputs();
+
+        self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+    }
+    
+    /// The value the object the cursor is in already has for the key, other than at the cursor's
+    /// own pair when editing it.
+    fn existing_value_of_key(&mut self, key: &str) -> Option<&Value> {
+        if self.is_root_empty() {
+            return None;
+        }
+
+        let is_editing = self.editing_mode == EditingMode::Editing;
+        let (parent, index) = get_nested_object_to_insert_into(self.line_at_cursor_without_empty_lines(), &mut self.json);
+
+        return match parent {
+            Some(Value::Object(map)) => map.keys().position(|existing| existing == key).filter(|existing| !is_editing || *existing != index).and_then(|_| map.get(key)),
+            _ => None,
+        };
+    }
+
+    /// Asks for the key to be confirmed when it would replace an existing one: the first submit
+    /// warns, submitting the same key again goes through. Replacing a value holding others asks
+    /// in a popup instead. Returns whether to go on.
+    fn confirm_key_collision(&mut self, key: &str) -> bool {
+        let replaced_count = self.existing_value_of_key(key).map(descendants_count);
+        let Some(replaced_count) = replaced_count.filter(|_| self.confirmed_key_collision.as_deref() != Some(key)) else {
+            self.confirmed_key_collision = None;
+            return true;
+        };
+
+        if replaced_count > 0 && self.config.confirm_destructive {
+            self.open_confirm(Confirm::yes_no(
+                translate("Replace"),
+                tr!("Replace '{}' and the {} values inside it?", key, replaced_count),
+                Action::Editing(EditingAction::SubmitReplacing),
+            ));
+            return false;
+        }
+
+        self.confirmed_key_collision = Some(key.to_string());
+        self.report(
+            tr!("The key \"{}\" already exists. Press enter again to replace its value, or change the key", key),
+            ReportedMessageKinds::Warning,
+            Duration::from_secs(5)
+        );
+
+        return false;
+    }
+
+    /// Inserts a value right after the line at the cursor, into the same parent. The key
+    /// is ignored if the parent is an array. Returns `false` if there was nowhere to insert.
+    pub fn insert_value_after_cursor(&mut self, key: String, value: Value) -> bool {
+        // An empty root has no line to insert after, so the new data goes directly into it.
+        let (object_to_insert_into, index) = if self.is_root_empty() {
+            (Some(&mut self.json), 0)
+        } else {
+            get_nested_object_to_insert_into(self.line_at_cursor_without_empty_lines(), &mut self.json)
+        };
+        return object_to_insert_into.is_some_and(|container| insert_after(container, index, key, value));
+    }
+    
+    /// Flags the JSON data as having unsaved changes and keeps a copy of it in the recovery
+    /// journal until it's saved.
+    pub fn mark_dirty(&mut self) {
+        self.is_dirty = true;
+        // The marked paths may point to other nodes now.
+        self.exchange_mark = None;
+        self.move_mark = None;
+        self.visual_anchor = None;
+        self.drag = None;
+        self.validate_against_schema();
+
+        if let Some(file_path) = &self.file_path {
+            if let Err(err) = write_journal(file_path, &self.json) {
                 self.report(
-                    format!("Failed to clear file: {}", err), 
-                    ReportedMessageKinds::Error, 
+                    tr!("Failed to write the recovery journal: {}", err),
+                    ReportedMessageKinds::Warning,
                     Duration::from_secs(3)
                 );
-                return;
             }
-            
-            // Reset file position to the beginning
-            if let Err(err) = file.seek(std::io::SeekFrom::Start(0)) {
+        }
+    }
+    
+    /// Writes the JSON data back to the opened file, if there is one. Failures are reported
+    /// to the user and `false` is returned.
+    ///
+    /// If the file was deleted or emptied outside of todd, nothing is written unless `force`
+    /// is set, so that it doesn't get silently re-created or overwritten.
+    pub fn save_to_file(&mut self, force: bool) -> bool {
+        let Some(file_path) = self.file_path.clone() else {
+            return true;
+        };
+
+        if !force {
+            let problem = match self.file_status {
+                FileStatus::Present | FileStatus::New => None,
+                FileStatus::Deleted => Some(tr!("{} was deleted outside of todd. Use :w! to write it anyway or :saveas <path> to save elsewhere", file_path.display())),
+                FileStatus::Truncated => Some(tr!("{} was emptied outside of todd. Use :w! to write it anyway or :saveas <path> to save elsewhere", file_path.display())),
+            };
+
+            if let Some(problem) = problem {
                 self.report(
-                    format!("Failed to reset file position: {}", err), 
-                    ReportedMessageKinds::Error, 
-                    Duration::from_secs(3)
+                    problem,
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(5)
                 );
-                return;
+                return false;
             }
-            
-            // Write the new JSON content directly to the file
-            if let Err(err) = serde_json::to_writer_pretty(file, &self.json) {
+
+            let errors_count = self.schema_errors_count();
+            if errors_count > 0 && self.config.schema_on_save == SchemaOnSave::Block {
+                self.report(
+                    tr!("The document has {} schema error(s). Fix them or use :w! to save anyway", errors_count),
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(5)
+                );
+                return false;
+            }
+        }
+
+        let content = match self.file_format.encode(self.document(), &self.comments) {
+            Ok(content) => content,
+            Err(err) => {
                 self.report(
-                    format!("Failed to save changes: {}", err), 
+                    tr!("Failed to save changes: {}", err), 
                     ReportedMessageKinds::Error, 
                     Duration::from_secs(3)
                 );
+                return false;
             }
+        };
+
+        if let Err(err) = fs::write(&file_path, content) {
+            self.report(
+                tr!("Failed to save changes: {}", err), 
+                ReportedMessageKinds::Error, 
+                Duration::from_secs(3)
+            );
+            return false;
+        }
+        
+        self.is_dirty = false;
+        self.saved_json = self.document().clone();
+        self.file_status = FileStatus::Present;
+        self.file_metadata = fs::metadata(&file_path).ok();
+        remove_journal(&file_path);
+        
+        return true;
+    }
+    
+    /// Saves the JSON data to another path, which becomes the opened file from now on.
+    pub fn save_as(&mut self, path: PathBuf) -> bool {
+        let previous_path = self.file_path.replace(path);
+
+        if !self.save_to_file(true) {
+            self.file_path = previous_path;
+            return false;
         }
 
-        self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+        // The changes the previous journal was holding are saved now.
+        if let Some(previous_path) = previous_path {
+            remove_journal(&previous_path);
+        }
+
+        return true;
+    }
+    
+    /// Checks whether the opened file was deleted or emptied by something else since todd last
+    /// saw it, and warns about it when that happens.
+    pub fn check_file_status(&mut self) {
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+
+        let had_content = self.file_metadata.as_ref().is_some_and(|metadata| metadata.len() > 0);
+        let status = match fs::metadata(file_path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound && self.file_status == FileStatus::New => FileStatus::New,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => FileStatus::Deleted,
+            Ok(metadata) if metadata.len() == 0 && had_content => FileStatus::Truncated,
+            _ => FileStatus::Present,
+        };
+
+        if status == self.file_status {
+            return;
+        }
+
+        self.file_status = status;
+
+        let problem = match status {
+            FileStatus::Present | FileStatus::New => return,
+            FileStatus::Deleted => tr!("{} was deleted outside of todd. Use :w! to write it again or :saveas <path> to save elsewhere", file_path.display()),
+            FileStatus::Truncated => tr!("{} was emptied outside of todd. Use :w! to write it again or :saveas <path> to save elsewhere", file_path.display()),
+        };
+        self.report(
+            problem,
+            ReportedMessageKinds::Warning,
+            Duration::from_secs(5)
+        );
     }
 
     /// Starts editing an existing value at the current cursor position.
@@ -252,7 +508,7 @@ impl<'a> App<'a> {
         // Check if we're on a line that has an actual value (not an object/array header)
         if self.line_at_cursor >= self.json_pairs.len() {
             self.report(
-                "No value to edit at current position".to_string(),
+                tr!("No value to edit at current position"),
                 ReportedMessageKinds::Error,
                 Duration::from_secs(2)
             );
@@ -264,7 +520,7 @@ impl<'a> App<'a> {
         // Can't edit object/array headers, only actual values
         if current_pair.value.is_none() {
             self.report(
-                "Cannot edit object or array headers".to_string(),
+                tr!("Cannot edit object or array headers"),
                 ReportedMessageKinds::Error,
                 Duration::from_secs(2)
             );
@@ -306,15 +562,18 @@ impl<'a> App<'a> {
                 _ => serde_json::to_string(value).unwrap_or_default()
             };
             self.value_input.set_content(&value_str);
+            self.value_input.set_hidden(is_secret_key(self.key_input.content()));
+            self.refresh_value_choices();
+            self.refresh_input_masks();
 
             self.report(
-                "Editing existing value".to_string(),
+                tr!("Editing existing value"),
                 ReportedMessageKinds::Info,
                 Duration::from_secs(2)
             );
         } else {
             self.report(
-                "Could not find value to edit".to_string(),
+                tr!("Could not find value to edit"),
                 ReportedMessageKinds::Error,
                 Duration::from_secs(2)
             );
@@ -336,22 +595,13 @@ impl<'a> App<'a> {
             }
         }
 
-        // Parse the new value
-        let new_value = serde_json::to_value(self.value_input.content()).unwrap();
-        let new_value: Value = match new_value {
-            Value::String(s) => {
-                if s.parse::<f64>().is_ok() {
-                    Value::Number(s.parse().unwrap())
-                } else if s.parse::<bool>().is_ok() {
-                    Value::Bool(s.parse().unwrap())
-                } else if s == "null" {
-                    Value::Null
-                } else {
-                    Value::String(s)
-                }
-            },
-            _ => new_value,
-        };
+        // Renaming the key to one of its siblings would replace the sibling.
+        let key = self.key_input.content().to_string();
+        if !is_array_item && !self.confirm_key_collision(&key) {
+            return;
+        }
+
+        let new_value = self.chosen_value().unwrap_or_else(|| value_from_input(self.value_input.content()));
 
         // Get the parent object and update the value
         let (object_to_update, index) = get_nested_object_to_insert_into(
@@ -368,11 +618,15 @@ impl<'a> App<'a> {
                         
                         // If key changed, we need to remove old and insert new
                         if old_key != new_key {
+                            // The sibling with the new key was confirmed to be replaced. Removing
+                            // it first shifts the pairs after it, the old key's included.
+                            map.shift_remove(&new_key);
+                            let position = map.keys().position(|existing| *existing == old_key).unwrap_or(map.len());
                             map.shift_remove(&old_key);
-                            map.shift_insert(index, new_key.clone(), new_value.clone());
+                            map.shift_insert(position.min(map.len()), new_key.clone(), new_value.clone());
                             
                             self.report(
-                                format!("Updated key-value pair: {} -> {}", new_key, self.value_input.content()),
+                                tr!("Updated key-value pair: {} -> {}", new_key, self.value_input.content()),
                                 ReportedMessageKinds::Success,
                                 Duration::from_secs(3)
                             );
@@ -382,7 +636,7 @@ impl<'a> App<'a> {
                             map.insert(old_key, new_value.clone());
                             
                             self.report(
-                                format!("Updated value: {} -> {}", old_key_clone, self.value_input.content()),
+                                tr!("Updated value: {} -> {}", old_key_clone, self.value_input.content()),
                                 ReportedMessageKinds::Success,
                                 Duration::from_secs(3)
                             );
@@ -394,7 +648,7 @@ impl<'a> App<'a> {
                         values[index] = new_value.clone();
                         
                         self.report(
-                            format!("Updated array value: {}", self.value_input.content()),
+                            tr!("Updated array value: {}", self.value_input.content()),
                             ReportedMessageKinds::Success,
                             Duration::from_secs(3)
                         );
@@ -402,7 +656,7 @@ impl<'a> App<'a> {
                 },
                 _ => {
                     self.report(
-                        "Cannot update this value".to_string(),
+                        tr!("Cannot update this value"),
                         ReportedMessageKinds::Error,
                         Duration::from_secs(3)
                     );
@@ -411,44 +665,15 @@ impl<'a> App<'a> {
             }
         } else {
             self.report(
-                "Could not find parent to update value".to_string(),
+                tr!("Could not find parent to update value"),
                 ReportedMessageKinds::Error,
                 Duration::from_secs(3)
             );
             return;
         }
 
-        // Save to file if available
-        if let Some(file) = self.file.as_mut() {
-            // Clear the file by truncating it to 0 bytes
-            if let Err(err) = file.set_len(0) {
-                self.report(
-                    format!("Failed to clear file: {}", err), 
-                    ReportedMessageKinds::Error, 
-                    Duration::from_secs(3)
-                );
-                return;
-            }
-            
-            // Reset file position to the beginning
-            if let Err(err) = file.seek(std::io::SeekFrom::Start(0)) {
-                self.report(
-                    format!("Failed to reset file position: {}", err), 
-                    ReportedMessageKinds::Error, 
-                    Duration::from_secs(3)
-                );
-                return;
-            }
-            
-            // Write the new JSON content directly to the file
-            if let Err(err) = serde_json::to_writer_pretty(file, &self.json) {
-                self.report(
-                    format!("Failed to save changes: {}", err), 
-                    ReportedMessageKinds::Error, 
-                    Duration::from_secs(3)
-                );
-            }
-        }
+        self.mark_dirty();
+        self.remember_inputs();
 
         // Reset editing mode and return to viewing
         self.editing_mode = EditingMode::Inserting;
@@ -489,6 +714,12 @@ impl<'a> App<'a> {
                 }
             }
         };
+
+        self.refresh_value_choices();
+        self.refresh_input_masks();
+        if self.currently_editing == Some(CurrentlyEditing::Value) {
+            self.value_input.set_hidden(is_secret_key(self.key_input.content()));
+        }
     }
     
     /// Set running to false to quit the application.
@@ -528,16 +759,24 @@ impl<'a> App<'a> {
         
         return match object_to_insert_into {
             Some(val) => val.is_array(),
-            None => false,
+            None => self.is_root_empty() && self.json.is_array(),
+        };
+    }
+    
+    /// Tells us if the root object/array of the document has no children.
+    pub fn is_root_empty(&self) -> bool {
+        return match &self.json {
+            Value::Object(map) => map.is_empty(),
+            Value::Array(values) => values.is_empty(),
+            _ => false,
         };
     }
     
     /// Returns another version of line_at_cursor that doesn't count empty representation lines.
     /// Useful for example when we want to step into the json with actual steps count.
-    fn line_at_cursor_without_empty_lines(&self) -> usize {
-        let mut i = 0;
+    pub fn line_at_cursor_without_empty_lines(&self) -> usize {
         let mut empty_lines = 0;
-        for pair in self.json_pairs.iter() {
+        for (i, pair) in self.json_pairs.iter().enumerate() {
             if i == self.line_at_cursor {
                 break;
             }
@@ -546,8 +785,6 @@ impl<'a> App<'a> {
             if pair.key == "" && pair.value.is_none() {
                 empty_lines += 1;
             }
-            
-            i += 1;
         }
         
         return self.line_at_cursor.saturating_sub(empty_lines);
@@ -586,7 +823,7 @@ impl<'a> App<'a> {
                 
                 *lines_count += self.insert_data_to_tree(
                     pairs,
-                    &value,
+                    value,
                     indentation_counter
                 );
             }
@@ -613,7 +850,7 @@ impl<'a> App<'a> {
                     indentation: indentation_counter, 
                     key: key.to_owned(), 
                     value: Some(serde_json::from_value(value.clone()).unwrap()),
-                    is_array_value: if key == "" { true } else { false }, // We're in an array.
+                    is_array_value: key == "", // We're in an array.
                 }
             );
         }
@@ -628,6 +865,16 @@ impl<'a> App<'a> {
                 self.editing_mode = EditingMode::Inserting;
                 self.key_input.clear();
                 self.value_input.clear();
+                self.key_input.set_mask(None);
+                self.value_input.set_mask(None);
+                self.value_input.set_hidden(false);
+                self.command_input.clear();
+                self.preview = None;
+                self.value_choices.clear();
+                self.confirmed_key_collision = None;
+                self.selected_suggestion = 0;
+                self.key_history.reset();
+                self.value_history.reset();
                 self.current_screen = CurrentScreen::ViewingFile;
             },
             AppNavigationAction::ToEditingScreen => {
@@ -636,6 +883,30 @@ impl<'a> App<'a> {
             AppNavigationAction::ToSearchingWidget => {
                 self.current_screen = CurrentScreen::Searching;
             },
+            AppNavigationAction::ToCommandWidget => {
+                self.command_input.clear();
+                self.current_screen = CurrentScreen::Command;
+            },
+            AppNavigationAction::ToProblemsPanel => {
+                if self.problems.is_empty() {
+                    self.report(tr!("No problems found"), ReportedMessageKinds::Info, Duration::from_secs(2));
+                    return;
+                }
+
+                self.selected_problem = self.selected_problem.min(self.problems.len() - 1);
+                self.current_screen = CurrentScreen::Problems;
+            },
+        }
+    }
+
+    /// Moves the cursor to the given line, scrolling it to the middle of the view if it's out of it.
+    pub fn move_cursor_to_line(&mut self, line: usize) {
+        self.line_at_cursor = line;
+
+        if line < self.vertical_scroll || line >= self.vertical_scroll + self.viewport_lines_count {
+            self.vertical_scroll = line.saturating_sub(self.viewport_lines_count / 2);
+            App::set_vertical_scroll_state(&mut self.vertical_scroll_state, self.vertical_scroll, self.viewport_lines_count);
+            self.scrolled_so_far = self.vertical_scroll;
         }
     }
     
@@ -645,7 +916,8 @@ impl<'a> App<'a> {
     }
     
     fn handle_main_view_messages(&mut self, action: MainViewActions) {
-        let scroll_offset = 5;
+        // Like in vim, the offset can't keep the cursor further than the middle of the view.
+        let scroll_offset = self.config.scrolloff.min(self.viewport_lines_count / 2);
 
         match action {
             MainViewActions::MoveDown => {
@@ -719,6 +991,31 @@ impl<'a> App<'a> {
                     self.update(Action::MainView(MainViewActions::MoveToTop));
                 }
             },
+            MainViewActions::ToggleTimestamps => {
+                self.show_timestamps = !self.show_timestamps;
+                self.report(
+                    if self.show_timestamps { tr!("Timestamp dates shown") } else { tr!("Timestamp dates hidden") },
+                    ReportedMessageKinds::Info,
+                    Duration::from_secs(2)
+                );
+            },
+            MainViewActions::Yank => self.yank_value_at_cursor(false),
+            MainViewActions::SelectRegister(register) => self.select_register(register),
+            MainViewActions::Paste => self.paste_at_cursor(),
+            MainViewActions::Exchange => self.exchange_at_cursor(),
+            MainViewActions::MoveInto => self.move_into_at_cursor(),
+            MainViewActions::ToggleVisual => self.toggle_visual(),
+            MainViewActions::Duplicate => self.duplicate_at_cursor(),
+            MainViewActions::YankEscaped => self.yank_value_at_cursor(true),
+            MainViewActions::Delete { confirmed } => self.delete_at_cursor(confirmed),
+            MainViewActions::ToggleEnvValues => {
+                self.show_env_values = !self.show_env_values;
+                self.report(
+                    if self.show_env_values { tr!("Environment values shown") } else { tr!("Environment values hidden") },
+                    ReportedMessageKinds::Info,
+                    Duration::from_secs(2)
+                );
+            },
         }
     }
     
@@ -750,42 +1047,126 @@ impl<'a> App<'a> {
                     }
                 }
             }
-            EditingAction::MoveCursor(direction) => {
-                match direction {
-                    CursorDirection::Left => {
-                        if let Some(focused_text_input) = self.get_focused_text_input() {
-                            focused_text_input.move_cursor_left();
-                        }
-                    },
-                    CursorDirection::Right => {
-                        if let Some(focused_text_input) = self.get_focused_text_input() {
-                            focused_text_input.move_cursor_right();
-                        }
-                    },
+            EditingAction::MoveCursor { direction, selecting } => {
+                if let Some(focused_text_input) = self.get_focused_text_input() {
+                    move_input_cursor(focused_text_input, direction, selecting);
+                }
+            }
+            EditingAction::PopChar => {
+                match self.currently_editing {
+                    Some(CurrentlyEditing::Key) => self.update(Action::Editing(EditingAction::PopFromKey)),
+                    Some(CurrentlyEditing::Value) => self.update(Action::Editing(EditingAction::PopFromValue)),
+                    None => { }
                 }
             }
             EditingAction::AppendToKey(c) => {
                 self.key_input.append_char(c);
+                self.selected_suggestion = 0;
             },
             EditingAction::AppendToValue(c) => {
-                self.value_input.append_char(c);
+                // Values restricted by the schema are picked, not typed.
+                if !self.is_choosing_value() {
+                    self.value_input.append_char(c);
+                    self.selected_suggestion = 0;
+                }
             },
             EditingAction::PopFromKey => {
                 if self.key_input.is_focused {
                     self.key_input.delete_char();
+                    self.selected_suggestion = 0;
                 }
             },
             EditingAction::PopFromValue => {
-                if self.value_input.is_focused {
+                if self.value_input.is_focused && !self.is_choosing_value() {
                     self.value_input.delete_char();
+                    self.selected_suggestion = 0;
+                }
+            },
+            EditingAction::PopWord => {
+                // Values restricted by the schema are picked, not typed.
+                let is_picked = self.value_input.is_focused && self.is_choosing_value();
+                if !is_picked && let Some(focused_text_input) = self.get_focused_text_input() {
+                    focused_text_input.delete_word();
+                    self.selected_suggestion = 0;
+                }
+            },
+            EditingAction::DeleteForward => {
+                let is_picked = self.value_input.is_focused && self.is_choosing_value();
+                if !is_picked && let Some(focused_text_input) = self.get_focused_text_input() {
+                    focused_text_input.delete_next_char();
+                    self.selected_suggestion = 0;
+                }
+            },
+            EditingAction::DeleteTo(direction) => {
+                let is_picked = self.value_input.is_focused && self.is_choosing_value();
+                if !is_picked && let Some(focused_text_input) = self.get_focused_text_input() {
+                    delete_input_to(focused_text_input, direction);
+                    self.selected_suggestion = 0;
                 }
             },
+            EditingAction::TransposeChars => {
+                let is_picked = self.value_input.is_focused && self.is_choosing_value();
+                if !is_picked && let Some(focused_text_input) = self.get_focused_text_input() {
+                    focused_text_input.transpose_chars();
+                    self.selected_suggestion = 0;
+                }
+            },
+            EditingAction::ToggleHidden => {
+                self.value_input.set_hidden(!self.value_input.is_hidden());
+            },
+            EditingAction::Undo => {
+                if let Some(focused_text_input) = self.get_focused_text_input() && focused_text_input.undo() {
+                    self.selected_suggestion = 0;
+                }
+            },
+            EditingAction::ClearInput => {
+                let is_picked = self.value_input.is_focused && self.is_choosing_value();
+                if !is_picked && let Some(focused_text_input) = self.get_focused_text_input() {
+                    focused_text_input.delete_all();
+                    self.selected_suggestion = 0;
+                }
+            },
+            EditingAction::CopySelection { cut } => {
+                let is_picked = self.value_input.is_focused && self.is_choosing_value();
+                if let Some(focused_text_input) = self.get_focused_text_input() {
+                    let (message, kind) = copy_selection(focused_text_input, cut && !is_picked);
+                    self.report(message, kind, Duration::from_secs(2));
+                }
+            },
+            EditingAction::SelectNext => {
+                if self.is_choosing_value() {
+                    self.select_value_choice(true);
+                } else if self.get_focused_text_input().is_some_and(|input| input.move_cursor_vertically(true)) {
+                    // Moved down a row of the wrapped content.
+                } else if !self.suggestions().is_empty() {
+                    self.select_suggestion(true);
+                } else {
+                    self.recall_input_history(false);
+                }
+            },
+            EditingAction::SelectPrevious => {
+                if self.is_choosing_value() {
+                    self.select_value_choice(false);
+                } else if self.get_focused_text_input().is_some_and(|input| input.move_cursor_vertically(false)) {
+                    // Moved up a row of the wrapped content.
+                } else if !self.suggestions().is_empty() {
+                    self.select_suggestion(false);
+                } else {
+                    self.recall_input_history(true);
+                }
+            },
+            EditingAction::RecallHistory { older } => self.recall_input_history(older),
+            EditingAction::AcceptSuggestion => self.accept_suggestion(),
             EditingAction::Submit => {
                 match self.editing_mode {
                     EditingMode::Inserting => self.insert_new_data_from_user_input(),
                     EditingMode::Editing => self.update_existing_data_from_user_input(),
                 }
             },
+            EditingAction::SubmitReplacing => {
+                self.confirmed_key_collision = Some(self.key_input.content().to_string());
+                self.update(Action::Editing(EditingAction::Submit));
+            },
         }
     }
     
@@ -795,21 +1176,34 @@ impl<'a> App<'a> {
                 self.update(Action::Searching(SearchingAction::ClearMatches)); // Clear previous matches before the new ones with the new character.
                 self.search_widget.append_char(c);
             }
-            SearchingAction::MoveCursor(direction) => {
-                match direction {
-                    CursorDirection::Left => {
-                        self.search_widget.move_cursor_left();
-                    },
-                    CursorDirection::Right => {
-                        self.search_widget.move_cursor_right();
-                    },
-                }
+            SearchingAction::MoveCursor { direction, selecting } => {
+                move_input_cursor(&mut self.search_widget, direction, selecting);
             }
             SearchingAction::PopChar => {
                 self.search_widget.delete_char();
             },
+            SearchingAction::PopWord => {
+                self.search_widget.delete_word();
+            },
+            SearchingAction::DeleteForward => {
+                self.search_widget.delete_next_char();
+            },
+            SearchingAction::DeleteTo(direction) => {
+                delete_input_to(&mut self.search_widget, direction);
+            },
+            SearchingAction::TransposeChars => {
+                self.search_widget.transpose_chars();
+            },
+            SearchingAction::Undo => {
+                self.update(Action::Searching(SearchingAction::ClearMatches));
+                self.search_widget.undo();
+            },
+            SearchingAction::CopySelection { cut } => {
+                let (message, kind) = copy_selection(&mut self.search_widget, cut);
+                self.report(message, kind, Duration::from_secs(2));
+            },
             SearchingAction::ClearSearch => {
-                self.search_widget.clear();
+                self.search_widget.delete_all();
                 self.search_matches = vec![];
             }
             SearchingAction::GoToNextMatch => {
@@ -832,7 +1226,7 @@ impl<'a> App<'a> {
                         self.line_at_cursor = *line_match;
 
                         self.report(
-                            format!("Match {} out of {}", i + 1, self.search_matches.len()),
+                            tr!("Match {} out of {}", i + 1, self.search_matches.len()),
                             ReportedMessageKinds::Info,
                             Duration::from_secs(1)
                         );
@@ -843,7 +1237,7 @@ impl<'a> App<'a> {
                 
                 if !found {
                     self.report(
-                        format!("No more matches"),
+                        tr!("No more matches"),
                         ReportedMessageKinds::Error,
                         Duration::from_secs(1)
                     );
@@ -867,7 +1261,7 @@ impl<'a> App<'a> {
                         self.line_at_cursor = *line_match;
 
                         self.report(
-                            format!("Match {} out of {}", self.search_matches.len() - i, self.search_matches.len()),
+                            tr!("Match {} out of {}", self.search_matches.len() - i, self.search_matches.len()),
                             ReportedMessageKinds::Info,
                             Duration::from_secs(1)
                         );
@@ -878,7 +1272,7 @@ impl<'a> App<'a> {
                 
                 if !found {
                     self.report(
-                        format!("No previous matches"),
+                        tr!("No previous matches"),
                         ReportedMessageKinds::Error,
                         Duration::from_secs(1)
                     );
@@ -889,7 +1283,7 @@ impl<'a> App<'a> {
             }
             SearchingAction::ReportResults => {
                 self.report(
-                    format!("Found {} matches", self.search_matches.len()),
+                    tr!("Found {} matches", self.search_matches.len()),
                     ReportedMessageKinds::Info,
                     Duration::from_secs(1)
                 );
@@ -897,11 +1291,149 @@ impl<'a> App<'a> {
         }
     }
 
+    fn handle_command_actions(&mut self, action: CommandAction) {
+        match action {
+            CommandAction::AppendChar(c) => {
+                self.command_input.append_char(c);
+            }
+            CommandAction::MoveCursor { direction, selecting } => {
+                move_input_cursor(&mut self.command_input, direction, selecting);
+            }
+            CommandAction::PopWord => {
+                self.command_input.delete_word();
+            }
+            CommandAction::DeleteForward => {
+                self.command_input.delete_next_char();
+            }
+            CommandAction::DeleteTo(direction) => {
+                delete_input_to(&mut self.command_input, direction);
+            }
+            CommandAction::TransposeChars => {
+                self.command_input.transpose_chars();
+            }
+            CommandAction::Undo => {
+                self.command_input.undo();
+            }
+            CommandAction::ClearInput => {
+                self.command_input.delete_all();
+            }
+            CommandAction::CopySelection { cut } => {
+                let (message, kind) = copy_selection(&mut self.command_input, cut);
+                self.report(message, kind, Duration::from_secs(2));
+            }
+            CommandAction::Complete => {
+                self.complete_command();
+            }
+            CommandAction::PopChar => {
+                // Deleting past the start of an empty command line leaves it, like in vim.
+                if self.command_input.content().is_empty() {
+                    self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+                    return;
+                }
+
+                self.command_input.delete_char();
+            }
+            CommandAction::Submit => {
+                let input = self.command_input.content().to_string();
+                self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+
+                match Command::parse(&input) {
+                    Ok(command) => self.execute_command(command),
+                    Err(message) => {
+                        self.report(
+                            message,
+                            ReportedMessageKinds::Error,
+                            Duration::from_secs(3)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_problems_actions(&mut self, action: ProblemsAction) {
+        match action {
+            ProblemsAction::SelectNext => {
+                if self.selected_problem + 1 < self.problems.len() {
+                    self.selected_problem += 1;
+                }
+            }
+            ProblemsAction::SelectPrevious => {
+                self.selected_problem = self.selected_problem.saturating_sub(1);
+            }
+            ProblemsAction::JumpToSelected => {
+                let Some(problem) = self.problems.get(self.selected_problem) else {
+                    return;
+                };
+
+                match get_line_at_path(&problem.path, &self.json) {
+                    Some(line) => {
+                        self.move_cursor_to_line(line);
+                        self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+                    }
+                    None => {
+                        self.report(
+                            tr!("{} isn't in the document anymore", path_to_pointer(&problem.path)),
+                            ReportedMessageKinds::Warning,
+                            Duration::from_secs(3)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     fn handle_app_actions(&mut self, sys_msg: SystemAction) {
         match sys_msg {
             SystemAction::Quit => {
+                self.quit_or_confirm();
+            },
+            SystemAction::DiscardAndQuit => {
+                if let Some(file_path) = &self.file_path {
+                    remove_journal(file_path);
+                }
+
                 self.quit();
             },
+            SystemAction::SaveAndQuit | SystemAction::ForceSaveAndQuit => {
+                // Without a file, there's only something to save if the document was changed.
+                if self.file_path.is_some() || self.is_dirty {
+                    let force = matches!(sys_msg, SystemAction::ForceSaveAndQuit);
+                    self.update(Action::App(if force { SystemAction::ForceSave } else { SystemAction::Save }));
+                }
+
+                if !self.is_dirty {
+                    self.quit();
+                }
+            },
+            SystemAction::Save | SystemAction::ForceSave => {
+                if self.file_path.is_none() {
+                    self.report(
+                        tr!("There is no file to save to. Use :saveas <path> to save to one"),
+                        ReportedMessageKinds::Error,
+                        Duration::from_secs(3)
+                    );
+                    return;
+                }
+
+                if self.save_to_file(matches!(sys_msg, SystemAction::ForceSave)) {
+                    let errors_count = self.schema_errors_count();
+                    if errors_count > 0 {
+                        self.report(
+                            tr!("Saved with {} schema error(s)", errors_count),
+                            ReportedMessageKinds::Warning,
+                            Duration::from_secs(3)
+                        );
+                        return;
+                    }
+
+                    self.report(
+                        tr!("Saved"),
+                        ReportedMessageKinds::Success,
+                        Duration::from_secs(2)
+                    );
+                }
+            },
         }
     }
     
@@ -962,15 +1494,105 @@ impl<'a> App<'a> {
     }
 }
 
-impl Default for App<'_> {
+/// Gives the text typed into the value input its type. The input treats everything as text, but
+/// it may be a number, a boolean or null. Numbers are kept exactly as typed (no rounding or
+/// reformatting of big or precise numbers).
+pub fn value_from_input(text: &str) -> Value {
+    if let Ok(number) = text.parse::<Number>() {
+        return Value::Number(number);
+    }
+
+    return match text {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "null" => Value::Null,
+        _ => Value::String(text.to_string()),
+    };
+}
+
+/// Whether the key looks like it holds a secret, like a password or a token, whose value is
+/// hidden while it's typed.
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase().replace(['_', '-', ' '], "");
+
+    return ["password", "passwd", "passphrase", "secret", "token", "apikey", "privatekey", "credential"]
+        .iter()
+        .any(|word| key.contains(word));
+}
+
+/// Deletes the text between the cursor and where it would move in the direction.
+pub fn delete_input_to(input: &mut TextInput, direction: CursorDirection) {
+    input.set_selecting(false);
+    move_input_cursor(input, direction, true);
+    input.delete_selection();
+}
+
+/// Moves the cursor of an input, selecting the text it moves over when `selecting`.
+pub fn move_input_cursor(input: &mut TextInput, direction: CursorDirection, selecting: bool) {
+    input.set_selecting(selecting);
+    match direction {
+        CursorDirection::Left => input.move_cursor_left(),
+        CursorDirection::Right => input.move_cursor_right(),
+        CursorDirection::WordLeft => input.move_cursor_word_left(),
+        CursorDirection::WordRight => input.move_cursor_word_right(),
+        CursorDirection::Start => input.move_cursor_to_start(),
+        CursorDirection::End => input.move_cursor_to_end(),
+    }
+}
+
+/// What a value typed in the edit popup will be stored as, with what looks wrong about it.
+#[derive(Debug, PartialEq)]
+pub struct InputFeedback {
+    pub stored_as: &'static str,
+    pub warning: Option<String>,
+}
+
+/// Tells what `value_from_input` will make of the text, and flags text that was likely meant
+/// to be something else, like a number JSON can't hold or a JSON snippet that doesn't parse.
+pub fn input_feedback(text: &str) -> InputFeedback {
+    let stored_as = match value_from_input(text) {
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+        _ => "string",
+    };
+
+    let unescaped_quotes = text
+        .char_indices()
+        .filter(|(index, c)| *c == '"' && !text[..*index].ends_with('\\'))
+        .count();
+
+    let warning = if stored_as != "string" {
+        None
+    } else if matches!(text.to_lowercase().trim_start_matches(['+', '-']), "nan" | "inf" | "infinity") {
+        Some(tr!("NaN and Infinity aren't JSON numbers"))
+    } else if text.parse::<f64>().is_ok() {
+        Some(tr!("Not a valid JSON number (like 01, +1 or .5)"))
+    } else if unescaped_quotes % 2 == 1 {
+        Some(tr!("Unterminated quote"))
+    } else if text.starts_with(['{', '[']) && let Err(err) = serde_json::from_str::<Value>(text) {
+        Some(tr!("Not valid JSON: {}", err))
+    } else if text.trim() != text {
+        Some(tr!("Has leading or trailing spaces"))
+    } else {
+        None
+    };
+
+    return InputFeedback { stored_as, warning };
+}
+
+impl Default for App {
     fn default() -> Self {
         Self {
             running: false,
-            key_input: TextInput::new(Some("Key")),
-            value_input: TextInput::new(Some("Value")),
-            search_widget: TextInput::new(Some("Look For")),
+            key_input: TextInput::new(Some(&tr!("Key"))).with_placeholder(translate("new key…")),
+            value_input: TextInput::new(Some(&tr!("Value"))).with_placeholder(translate("text, 42, true, null…")),
+            search_widget: TextInput::new(Some(&tr!("Look For"))).with_placeholder(translate("search keys and values…")),
+            command_input: TextInput::new(Some(&tr!("Command"))).with_placeholder(translate("w, q, set wrap, tutorial…")),
             search_matches: vec![],
             json: Value::default(),
+            saved_json: Value::default(),
+            unsorted_json: None,
             lines_count: 0,
             viewport_lines_count: 0,
             message_to_report: RefCell::new(ReportedMessage {
@@ -985,7 +1607,39 @@ impl Default for App<'_> {
             line_at_cursor: 0,
             json_pairs: vec![],
             file_metadata: None,
-            file: None,
+            file_path: None,
+            file_status: FileStatus::Present,
+            file_format: FileFormat::default(),
+            comments: Comments::default(),
+            is_dirty: false,
+            problems: vec![],
+            selected_problem: 0,
+            preview: None,
+            table: None,
+            confirm: None,
+            tutorial: None,
+            confirmed_key_collision: None,
+            schema: None,
+            value_choices: vec![],
+            selected_choice: 0,
+            selected_suggestion: 0,
+            key_history: InputHistory::default(),
+            value_history: InputHistory::default(),
+            show_timestamps: true,
+            show_env_values: false,
+            config: Config::default(),
+            pending_keys: vec![],
+            is_choosing_register: false,
+            selected_register: None,
+            registers: Registers::default(),
+            exchange_mark: None,
+            move_mark: None,
+            visual_anchor: None,
+            drag: None,
+            tree_area: Rect::default(),
+            row_lines: vec![],
+            shown_title: None,
+            no_color: false,
             vertical_scroll_state: ScrollbarState::default(),
             vertical_scroll: 0,
             scrolled_so_far: 0,
@@ -999,6 +1653,34 @@ impl Default for App<'_> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_secret_keys() {
+        assert!(is_secret_key("password"));
+        assert!(is_secret_key("DB_PASSWORD"));
+        assert!(is_secret_key("api-key"));
+        assert!(is_secret_key("refreshToken"));
+        assert!(!is_secret_key("name"));
+        assert!(!is_secret_key("keyboard"));
+    }
+
+    #[test]
+    fn test_input_feedback() {
+        let feedback = |stored_as, warning: Option<&str>| InputFeedback { stored_as, warning: warning.map(str::to_string) };
+
+        assert_eq!(input_feedback("42"), feedback("number", None));
+        assert_eq!(input_feedback("-1.5e3"), feedback("number", None));
+        assert_eq!(input_feedback("true"), feedback("boolean", None));
+        assert_eq!(input_feedback("null"), feedback("null", None));
+        assert_eq!(input_feedback("hello"), feedback("string", None));
+        assert_eq!(input_feedback("NaN"), feedback("string", Some("NaN and Infinity aren't JSON numbers")));
+        assert_eq!(input_feedback("-Infinity"), feedback("string", Some("NaN and Infinity aren't JSON numbers")));
+        assert_eq!(input_feedback("01"), feedback("string", Some("Not a valid JSON number (like 01, +1 or .5)")));
+        assert_eq!(input_feedback(r#"{"a": "b}"#), feedback("string", Some("Unterminated quote")));
+        assert_eq!(input_feedback(r#"say \"hi\""#), feedback("string", None));
+        assert!(input_feedback(r#"{"a": 1"#).warning.unwrap().starts_with("Not valid JSON"));
+        assert_eq!(input_feedback(" padded"), feedback("string", Some("Has leading or trailing spaces")));
+    }
+
     #[test]
     fn test_insert_data_to_tree_for_object() {
         let app = App::default();
@@ -1259,6 +1941,19 @@ mod tests {
                 (&"Currency".to_string(), &serde_json::to_value("USD").unwrap()),
             );
         }
+
+        // Test case: Inserting an existing key needs to be confirmed by submitting it again.
+        {
+            app.key_input.set_content("age");
+            app.value_input.set_content("10");
+            app.line_at_cursor = 0;
+
+            app.insert_new_data_from_user_input();
+            assert_eq!(app.json["age"], serde_json::json!(9));
+
+            app.insert_new_data_from_user_input();
+            assert_eq!(app.json["age"], serde_json::json!(10));
+        }
         
         // Test case: Insert after the third line. Into the object below it.
         // @NotImplemented: Inserting inside of an object instead of after it because the index
@@ -1338,6 +2033,112 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_insert_into_empty_root() {
+        // Object root, as created by `todd --create`.
+        {
+            let mut app = App::new("{}", None, None, Size::default()).unwrap();
+            app.update(Action::AppNavigation(AppNavigationAction::ToEditingScreen));
+            assert_eq!(app.currently_editing, Some(CurrentlyEditing::Key));
+
+            app.key_input.set_content("name");
+            app.value_input.set_content("Omar");
+            app.insert_new_data_from_user_input();
+
+            app.key_input.set_content("age");
+            app.value_input.set_content("24");
+            app.insert_new_data_from_user_input();
+
+            assert_eq!(app.json, serde_json::json!({ "name": "Omar", "age": 24 }));
+        }
+
+        // Array root.
+        {
+            let mut app = App::new("[]", None, None, Size::default()).unwrap();
+            assert!(app.is_inside_array());
+
+            app.value_input.set_content("true");
+            app.insert_new_data_from_user_input();
+
+            assert_eq!(app.json, serde_json::json!([true]));
+        }
+    }
+
+    #[test]
+    fn test_saving_after_the_file_is_deleted() {
+        let file_path = std::env::temp_dir().join(format!("todd_deleted_test_{}.json", std::process::id()));
+        fs::write(&file_path, r#"{ "name": "Omar" }"#).unwrap();
+
+        let mut app = App::new(
+            r#"{ "name": "Omar" }"#,
+            fs::metadata(&file_path).ok(),
+            Some(file_path.clone()),
+            Size::default(),
+        ).unwrap();
+
+        fs::remove_file(&file_path).unwrap();
+        app.check_file_status();
+        assert_eq!(app.file_status, FileStatus::Deleted);
+
+        // A normal save must not silently re-create the file.
+        assert!(!app.save_to_file(false));
+        assert!(!file_path.exists());
+
+        assert!(app.save_to_file(true));
+        assert!(file_path.exists());
+        assert_eq!(app.file_status, FileStatus::Present);
+
+        fs::remove_file(&file_path).unwrap();
+    }
+
+    #[test]
+    fn test_saving_a_new_file() {
+        let file_path = std::env::temp_dir().join(format!("todd_new_file_test_{}.json", std::process::id()));
+        let _ = fs::remove_file(&file_path);
+
+        let mut app = App::new("{}", None, Some(file_path.clone()), Size::default()).unwrap();
+        app.file_status = FileStatus::New;
+
+        // Nothing is on disk until the first save, which isn't a deleted file.
+        app.check_file_status();
+        assert_eq!(app.file_status, FileStatus::New);
+        assert!(!file_path.exists());
+
+        assert!(app.save_to_file(false));
+        assert_eq!(fs::read_to_string(&file_path).unwrap().trim(), "{}");
+        assert_eq!(app.file_status, FileStatus::Present);
+
+        fs::remove_file(&file_path).unwrap();
+    }
+
+    #[test]
+    fn test_quitting_with_unsaved_changes() {
+        let file_path = std::env::temp_dir().join(format!("todd_quit_test_{}.json", std::process::id()));
+        fs::write(&file_path, r#"{ "name": "Omar" }"#).unwrap();
+
+        let mut app = App::new(r#"{ "name": "Omar" }"#, fs::metadata(&file_path).ok(), Some(file_path.clone()), Size::default()).unwrap();
+        app.running = true;
+        app.json = serde_json::json!({ "name": "Jane" });
+        app.mark_dirty();
+
+        app.execute_command(Command::Quit { force: false });
+        assert!(app.running);
+
+        app.execute_command(Command::WriteQuit { force: false });
+        assert!(!app.running);
+        assert!(!app.is_dirty);
+        assert!(fs::read_to_string(&file_path).unwrap().contains("Jane"));
+
+        // Dropping the changes drops their recovery journal too.
+        app.json = serde_json::json!({ "name": "John" });
+        app.mark_dirty();
+        assert!(crate::journal::read_journal(&file_path).is_some());
+        app.execute_command(Command::Quit { force: true });
+        assert!(crate::journal::read_journal(&file_path).is_none());
+
+        fs::remove_file(&file_path).unwrap();
+    }
+
     #[test]
     fn test_editing_existing_values() {
         let data = r#"
@@ -1409,4 +2210,67 @@ mod tests {
             assert_eq!(hobbies[0].as_str().unwrap(), "writing");
         }
     }
+
+    #[test]
+    fn test_replacing_a_key_holding_values() {
+        let mut app = App::new(r#"{ "name": "Omar", "address": { "city": "Cairo" } }"#, None, None, Size::default()).unwrap();
+        let mut pairs = vec![];
+        app.lines_count = app.insert_data_to_tree(&mut pairs, &app.json, 0);
+        app.json_pairs = pairs;
+
+        app.line_at_cursor = 0;
+        app.start_editing_existing_value();
+        app.key_input.set_content("address");
+        app.update(Action::Editing(EditingAction::Submit));
+        assert!(matches!(app.current_screen, CurrentScreen::Confirm));
+
+        // Saying no goes back to the edit popup.
+        app.update(Action::Confirm(crate::actions::ConfirmAction::Choose('n')));
+        assert!(matches!(app.current_screen, CurrentScreen::Editing));
+        assert_eq!(app.json["address"]["city"], "Cairo");
+
+        app.update(Action::Editing(EditingAction::Submit));
+        app.update(Action::Confirm(crate::actions::ConfirmAction::Choose('y')));
+        assert_eq!(app.json, serde_json::json!({ "address": "Omar" }));
+    }
+
+    #[test]
+    fn test_key_collisions() {
+        // Renames the key at the line, submitting twice to confirm replacing the sibling.
+        let rename = |json: &str, line: usize, key: &str| {
+            let mut app = App::new(json, None, None, Size::default()).unwrap();
+            let mut pairs = vec![];
+            app.lines_count = app.insert_data_to_tree(&mut pairs, &app.json, 0);
+            app.json_pairs = pairs;
+
+            app.line_at_cursor = line;
+            app.start_editing_existing_value();
+            app.key_input.set_content(key);
+            app.update_existing_data_from_user_input();
+            app.update_existing_data_from_user_input();
+            return serde_json::to_string(&app.json).unwrap();
+        };
+
+        // To an earlier sibling, last or not, and to a later one.
+        assert_eq!(rename(r#"{"a":1,"b":2}"#, 1, "a"), r#"{"a":2}"#);
+        assert_eq!(rename(r#"{"a":1,"b":2,"c":3}"#, 1, "a"), r#"{"a":2,"c":3}"#);
+        assert_eq!(rename(r#"{"a":1,"b":2,"c":3}"#, 0, "c"), r#"{"c":1,"b":2}"#);
+
+        // An inserted key the object already has replaces it, after the cursor.
+        let mut app = App::new(r#"{"a":1,"b":2,"c":3}"#, None, None, Size::default()).unwrap();
+        app.line_at_cursor = 1;
+        app.key_input.set_content("a");
+        app.value_input.set_content("9");
+        app.insert_new_data_from_user_input();
+        assert_eq!(serde_json::to_string(&app.json).unwrap(), r#"{"a":1,"b":2,"c":3}"#);
+        app.insert_new_data_from_user_input();
+        assert_eq!(serde_json::to_string(&app.json).unwrap(), r#"{"b":2,"a":9,"c":3}"#);
+
+        app.line_at_cursor = 2;
+        app.key_input.set_content("c");
+        app.value_input.set_content("4");
+        app.insert_new_data_from_user_input();
+        app.insert_new_data_from_user_input();
+        assert_eq!(serde_json::to_string(&app.json).unwrap(), r#"{"b":2,"a":9,"c":4}"#);
+    }
 }
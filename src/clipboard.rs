@@ -0,0 +1,109 @@
+//
+// Copying to the system clipboard through the terminal (OSC 52). It needs no display server
+// and works over SSH, as long as the terminal supports it (most do).
+//
+
+use std::{io::{self, Write}, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+
+use crate::{app::{App, ReportedMessageKinds}, i18n::tr, registers::Yanked, utils::json::{get_path_at_line, get_value_at_line, PathSegment}, widgets::text_input::TextInput};
+
+/// Asks the terminal to put the text in the clipboard.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+
+    return stdout.flush();
+}
+
+/// How a value is copied: strings as they are, objects and arrays pretty-printed, the rest as JSON.
+pub fn yanked_text(value: &Value) -> String {
+    return match value {
+        Value::String(string) => string.clone(),
+        Value::Array(_) | Value::Object(_) => serde_json::to_string_pretty(value).unwrap_or_default(),
+        _ => value.to_string(),
+    };
+}
+
+/// The copied text as a double-quoted string literal, with quotes, backslashes and control
+/// characters escaped, to paste it in code or in a shell command.
+pub fn escaped_text(value: &Value) -> String {
+    return Value::String(yanked_text(value)).to_string();
+}
+
+/// Copies the text selected in the input, and deletes it from the input when `cut`. The message
+/// telling how it went.
+pub fn copy_selection(input: &mut TextInput, cut: bool) -> (String, ReportedMessageKinds) {
+    let Some(text) = input.selected_text() else {
+        return (tr!("Nothing is selected, select with <S-Left> and <S-Right>"), ReportedMessageKinds::Error);
+    };
+    if let Err(err) = copy_to_clipboard(&text) {
+        return (tr!("Failed to copy to the clipboard: {}", err), ReportedMessageKinds::Error);
+    }
+
+    if cut {
+        input.delete_selection();
+        return (tr!("Cut the selection to the clipboard"), ReportedMessageKinds::Success);
+    }
+
+    return (tr!("Copied the selection to the clipboard"), ReportedMessageKinds::Success);
+}
+
+impl App {
+    /// Copies the value under the cursor to the clipboard, as it is or escaped. Unescaped, the
+    /// node goes to the registers too, and only to the named one when there's one.
+    pub fn yank_value_at_cursor(&mut self, escaped: bool) {
+        if !escaped && self.visual_anchor.is_some() {
+            self.yank_visual_range();
+            return;
+        }
+
+        let register = self.selected_register.take();
+        let (Some(path), Some(value)) = (get_path_at_line(self.line_at_cursor, &self.json), get_value_at_line(self.line_at_cursor, &self.json)) else {
+            self.report(tr!("Nothing to copy at the current line"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        if !escaped {
+            let key = match path.last() {
+                Some(PathSegment::Key(key)) => Some(key.clone()),
+                _ => None,
+            };
+            self.registers.store(register, Yanked { key, value: value.clone(), is_range: false });
+
+            if let Some(register) = register {
+                self.report(tr!("Yanked the value into register \"{}", register), ReportedMessageKinds::Success, Duration::from_secs(2));
+                return;
+            }
+        }
+
+        let text = if escaped { escaped_text(value) } else { yanked_text(value) };
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.report(
+                if escaped { tr!("Copied the value as an escaped string to the clipboard") } else { tr!("Copied the value to the clipboard") },
+                ReportedMessageKinds::Success,
+                Duration::from_secs(2),
+            ),
+            Err(err) => self.report(tr!("Failed to copy to the clipboard: {}", err), ReportedMessageKinds::Error, Duration::from_secs(3)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_yanked_text() {
+        assert_eq!(yanked_text(&json!("say \"hi\"")), "say \"hi\"");
+        assert_eq!(yanked_text(&json!(4.5)), "4.5");
+        assert_eq!(yanked_text(&json!({ "a": 1 })), "{\n  \"a\": 1\n}");
+
+        assert_eq!(escaped_text(&json!("C:\\dir \"x\"")), r#""C:\\dir \"x\"""#);
+        assert_eq!(escaped_text(&json!("line\nbreak")), r#""line\nbreak""#);
+        assert_eq!(escaped_text(&json!({ "a": "b" })), r#""{\n  \"a\": \"b\"\n}""#);
+    }
+}
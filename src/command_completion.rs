@@ -0,0 +1,126 @@
+//
+// Tab completion in the command line: the names of the commands, the options of `:set`, and the
+// paths of the commands taking a file.
+//
+
+use std::{fs, path::Path, time::Duration};
+
+use crate::{app::{App, ReportedMessageKinds}, options::{BOOLEAN_OPTION_NAMES, OPTION_NAMES}};
+
+/// The names of the commands, as completed. Short aliases like `:w` are left out.
+pub const COMMAND_NAMES: [&str; 27] = [
+    "agg", "delete", "export", "exportmerge", "format", "goto", "groupby", "importcsv", "mergepatch", "move", "parse", "patch", "problems",
+    "quit", "read", "rust", "saveas", "schema", "set", "sort", "stats", "stringify", "table", "ts", "tutorial", "unsort", "write",
+];
+
+/// The commands whose argument is the path of a file.
+const PATH_COMMANDS: [&str; 11] = ["export", "exportmerge", "importcsv", "mergepatch", "patch", "r", "read", "rust", "saveas", "schema", "ts"];
+
+/// The candidates for the word being typed at the end of the command line, and where that word starts.
+pub fn completions(input: &str, list_directory: impl Fn(&Path) -> Vec<(String, bool)>) -> (usize, Vec<String>) {
+    let Some((name, argument)) = input.split_once(' ') else {
+        let names = COMMAND_NAMES.iter().filter(|name| name.starts_with(input)).map(|name| name.to_string()).collect();
+        return (0, names);
+    };
+    let start = name.len() + 1 + (argument.len() - argument.trim_start().len());
+    let argument = argument.trim_start();
+
+    let name = name.trim_end_matches('!');
+    if name == "set" {
+        let options = OPTION_NAMES.iter().flat_map(|option| match BOOLEAN_OPTION_NAMES.contains(option) {
+            true => vec![option.to_string(), format!("no{}", option)],
+            false => vec![format!("{}=", option)],
+        });
+        return (start, options.filter(|option| option.starts_with(argument)).collect());
+    }
+    if !PATH_COMMANDS.contains(&name) {
+        return (start, vec![]);
+    }
+
+    // Only the file name after the last `/` is completed, among the files of its directory.
+    let (directory, prefix) = match argument.rfind('/') {
+        Some(index) => (&argument[..=index], &argument[index + 1..]),
+        None => ("", argument),
+    };
+    let mut paths: Vec<String> = list_directory(Path::new(if directory.is_empty() { "." } else { directory }))
+        .into_iter()
+        .filter(|(file_name, _)| file_name.starts_with(prefix) && (prefix.starts_with('.') || !file_name.starts_with('.')))
+        .map(|(file_name, is_directory)| format!("{}{}{}", directory, file_name, if is_directory { "/" } else { "" }))
+        .collect();
+    paths.sort();
+
+    return (start, paths);
+}
+
+/// The names of the entries of a directory, and whether each is a directory.
+fn read_directory(path: &Path) -> Vec<(String, bool)> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return vec![];
+    };
+
+    return entries
+        .filter_map(Result::ok)
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path().is_dir()))
+        .collect();
+}
+
+fn common_prefix(candidates: &[String]) -> String {
+    let mut prefix = candidates[0].as_str();
+    for candidate in &candidates[1..] {
+        let length = prefix.chars().zip(candidate.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a.len_utf8()).sum();
+        prefix = &prefix[..length];
+    }
+
+    return prefix.to_string();
+}
+
+impl App {
+    /// Completes the word at the end of the command line as far as all its candidates agree, and
+    /// lists them when there are several.
+    pub fn complete_command(&mut self) {
+        let input = self.command_input.content().to_string();
+        let (start, candidates) = completions(&input, read_directory);
+        if candidates.is_empty() {
+            return;
+        }
+
+        let mut completed = format!("{}{}", &input[..start], common_prefix(&candidates));
+        // A lone command name is followed by its argument.
+        if candidates.len() == 1 && start == 0 {
+            completed.push(' ');
+        }
+        if completed.len() > input.len() {
+            self.command_input.replace_content(completed);
+            self.command_input.move_cursor_to_end();
+        }
+
+        if candidates.len() > 1 {
+            self.report(candidates.join("  "), ReportedMessageKinds::Info, Duration::from_secs(5));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completions() {
+        let list_directory = |path: &Path| match path.to_str() {
+            Some(".") => vec![("data".to_string(), true), ("data.json".to_string(), false), (".git".to_string(), true)],
+            Some("data/") => vec![("users.csv".to_string(), false)],
+            _ => vec![],
+        };
+
+        assert_eq!(completions("gr", list_directory), (0, vec!["groupby".to_string()]));
+        assert_eq!(completions("s", list_directory).1, ["saveas", "schema", "set", "sort", "stats", "stringify"]);
+        assert_eq!(completions("set no", list_directory).1, ["notimestamps", "noenvvalues", "nowrap", "nonumber"]);
+        assert_eq!(completions("set sc", list_directory), (4, vec!["scrolloff=".to_string()]));
+        assert_eq!(completions("read d", list_directory), (5, vec!["data.json".to_string(), "data/".to_string()]));
+        assert_eq!(completions("saveas!  data/u", list_directory), (9, vec!["data/users.csv".to_string()]));
+        assert_eq!(completions("read .", list_directory), (5, vec![".git/".to_string()]));
+        assert_eq!(completions("sort i", list_directory), (5, vec![]));
+
+        assert_eq!(common_prefix(&["data.json".to_string(), "data/".to_string()]), "data");
+    }
+}
@@ -0,0 +1,945 @@
+//
+// Commands typed into the command line (`:`).
+//
+
+use std::{fs, path::{Path, PathBuf}, time::Duration};
+
+use serde_json::Value;
+
+use crate::{actions::{Action, AppNavigationAction, SystemAction}, app::{App, ReportedMessageKinds}, clipboard::copy_to_clipboard, formats::Syntax, i18n::{tr, translate}, options::OptionAssignment, preview::Preview, registers::Yanked, utils::{csv::csv_to_json, edit::{free_key, remove_elements}, json::{get_line_at_path, get_nested_object_to_insert_into, get_path_at_line, get_value_at_line, get_value_at_path_mut, path_to_pointer, pointer_to_path, PathSegment}, patch::{apply_merge_patch, apply_patch, create_merge_patch, PatchError}, shape::{json_schema, type_name}, rust_types::rust_types, stats::{document_stats, group_by, Aggregate}, typescript::typescript_types}};
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Writes the value under the cursor to a new file. `force` allows overwriting an existing file.
+    Export { path: String, force: bool },
+    /// Parses a JSON file and inserts its content after the cursor.
+    Read { path: String },
+    /// Parses a CSV file into an array of objects and inserts it after the cursor.
+    ImportCsv { path: String },
+    /// Applies a JSON Patch (RFC 6902) file to the document.
+    Patch { path: String },
+    /// Applies a JSON Merge Patch (RFC 7386) file to the document.
+    MergePatch { path: String },
+    /// Writes the merge patch that turns the last saved document into the current one. `force` allows
+    /// overwriting an existing file.
+    ExportMergePatch { path: String, force: bool },
+    /// Changes the syntax the document is saved with.
+    Format { syntax: Syntax },
+    /// Saves the changes to the opened file. `force` writes it even if it was deleted or emptied outside of todd.
+    Write { force: bool },
+    /// Saves the changes to another file, which becomes the opened one. `force` allows overwriting an existing file.
+    SaveAs { path: String, force: bool },
+    /// Opens the panel listing the problems found in the document.
+    Problems,
+    /// Infers a JSON Schema from the document and writes it to a new file, or copies it to the
+    /// clipboard without a path. `force` allows overwriting an existing file.
+    Schema { path: Option<String>, force: bool },
+    /// Generates TypeScript interfaces for the document and writes them to a new file, or copies
+    /// them to the clipboard without a path. `force` allows overwriting an existing file.
+    TypeScript { path: Option<String>, force: bool },
+    /// Generates Rust structs with serde derives for the document and writes them to a new file, or
+    /// copies them to the clipboard without a path. `force` allows overwriting an existing file.
+    Rust { path: Option<String>, force: bool },
+    /// Parses the JSON held by the string under the cursor and replaces the string with it.
+    ParseString,
+    /// Replaces the object or array under the cursor with a string holding it as compact JSON.
+    Stringify,
+    /// Opens a summary of the whole document.
+    Stats,
+    /// Aggregates the numbers of the array under the cursor, or the values of a key across the
+    /// objects of an array: the key under the cursor, or the given one on the array itself.
+    Aggregate { key: Option<String> },
+    /// Counts the objects of the array under the cursor by their value at a key. `restructure`
+    /// replaces the array with an object of the groups.
+    GroupBy { key: String, restructure: bool },
+    /// Shows the tree with the keys sorted and, given a field, the arrays of objects sorted by it.
+    /// The document isn't changed.
+    Sort { field: Option<String> },
+    /// Shows the tree in the document's own order again.
+    Unsort,
+    /// Shows the array of objects under the cursor as a table.
+    Table,
+    /// Quits, unless there are unsaved changes. Forced, it drops them.
+    Quit { force: bool },
+    /// Saves, then quits if that worked.
+    WriteQuit { force: bool },
+    /// Moves the cursor to a line (counted from 1) or to the value at a JSON Pointer.
+    Goto { target: GotoTarget },
+    /// Changes an option of the view.
+    Set { option: String, assignment: OptionAssignment },
+    /// Opens the guided tour of the basics.
+    Tutorial,
+    /// Moves the node under the cursor into the object or array at a JSON Pointer.
+    Move { pointer: String },
+    /// Removes the elements of the array under the cursor from `start` up to `end` (excluded), or
+    /// to its end without one.
+    DeleteRange { start: usize, end: Option<usize> },
+}
+
+/// Parses a range of indexes written like in Rust: `3..10` (10 excluded), `3..=10`, `3..` or `..10`.
+fn parse_index_range(argument: &str) -> Option<(usize, Option<usize>)> {
+    let (start, end) = argument.split_once("..")?;
+    let start = if start.trim().is_empty() { 0 } else { start.trim().parse().ok()? };
+    let end = match end.trim().strip_prefix('=') {
+        Some(last) => Some(last.trim().parse::<usize>().ok()?.checked_add(1)?),
+        None if end.trim().is_empty() => None,
+        None => Some(end.trim().parse().ok()?),
+    };
+
+    return end.is_none_or(|end| start < end).then_some((start, end));
+}
+
+/// Where `:goto` moves the cursor to.
+#[derive(Debug, PartialEq)]
+pub enum GotoTarget {
+    Line(usize),
+    Pointer(String),
+}
+
+impl GotoTarget {
+    fn parse(argument: &str) -> Option<GotoTarget> {
+        if argument.starts_with('/') {
+            return Some(GotoTarget::Pointer(argument.to_string()));
+        }
+
+        return argument.parse().ok().filter(|line| *line > 0).map(GotoTarget::Line);
+    }
+}
+
+impl Command {
+    /// Parses the text of the command line (without the leading `:`) into a command.
+    pub fn parse(input: &str) -> Result<Command, String> {
+        let input = input.trim();
+        let (name, argument) = match input.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (input, ""),
+        };
+
+        return match name {
+            "export" | "export!" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :{} <path>", name));
+                }
+
+                Ok(Command::Export { path: argument.to_string(), force: name.ends_with('!') })
+            }
+            "read" | "r" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :read <path>"));
+                }
+
+                Ok(Command::Read { path: argument.to_string() })
+            }
+            "importcsv" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :importcsv <path>"));
+                }
+
+                Ok(Command::ImportCsv { path: argument.to_string() })
+            }
+            "patch" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :patch <path>"));
+                }
+
+                Ok(Command::Patch { path: argument.to_string() })
+            }
+            "mergepatch" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :mergepatch <path>"));
+                }
+
+                Ok(Command::MergePatch { path: argument.to_string() })
+            }
+            "exportmerge" | "exportmerge!" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :{} <path>", name));
+                }
+
+                Ok(Command::ExportMergePatch { path: argument.to_string(), force: name.ends_with('!') })
+            }
+            "format" => match Syntax::parse(argument) {
+                Some(syntax) => Ok(Command::Format { syntax }),
+                None => Err(tr!("Usage: :format <json | json5 | jsonc | hjson | yaml | msgpack>")),
+            },
+            "write" | "w" | "write!" | "w!" => Ok(Command::Write { force: name.ends_with('!') }),
+            "saveas" | "saveas!" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :{} <path>", name));
+                }
+
+                Ok(Command::SaveAs { path: argument.to_string(), force: name.ends_with('!') })
+            }
+            "problems" => Ok(Command::Problems),
+            "schema" | "schema!" => {
+                let path = (!argument.is_empty()).then(|| argument.to_string());
+                Ok(Command::Schema { path, force: name.ends_with('!') })
+            }
+            "ts" | "ts!" => {
+                let path = (!argument.is_empty()).then(|| argument.to_string());
+                Ok(Command::TypeScript { path, force: name.ends_with('!') })
+            }
+            "rust" | "rust!" => {
+                let path = (!argument.is_empty()).then(|| argument.to_string());
+                Ok(Command::Rust { path, force: name.ends_with('!') })
+            }
+            "parse" => Ok(Command::ParseString),
+            "stringify" => Ok(Command::Stringify),
+            "stats" => Ok(Command::Stats),
+            "groupby" | "groupby!" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :{} <key>", name));
+                }
+
+                Ok(Command::GroupBy { key: argument.to_string(), restructure: name.ends_with('!') })
+            }
+            "sort" => Ok(Command::Sort { field: (!argument.is_empty()).then(|| argument.to_string()) }),
+            "unsort" => Ok(Command::Unsort),
+            "table" => Ok(Command::Table),
+            "tutorial" => Ok(Command::Tutorial),
+            "delete" | "d" => match parse_index_range(argument) {
+                Some((start, end)) => Ok(Command::DeleteRange { start, end }),
+                None => Err(tr!("Usage: :delete <start>..<end>, like :delete 3..10 (10 excluded) or :delete 3..=10")),
+            },
+            "move" | "mv" => {
+                if argument.is_empty() {
+                    return Err(tr!("Usage: :move </json/pointer>"));
+                }
+
+                Ok(Command::Move { pointer: argument.to_string() })
+            }
+            "quit" | "q" | "quit!" | "q!" => Ok(Command::Quit { force: name.ends_with('!') }),
+            "wq" | "wq!" | "x" | "x!" => Ok(Command::WriteQuit { force: name.ends_with('!') }),
+            "goto" => match GotoTarget::parse(argument) {
+                Some(target) => Ok(Command::Goto { target }),
+                None => Err(tr!("Usage: :goto <line | /json/pointer>")),
+            },
+            "set" => {
+                let (option, assignment) = OptionAssignment::parse(argument)?;
+                Ok(Command::Set { option, assignment })
+            }
+            // `:42` goes to a line, like in vim.
+            _ if let Some(target) = GotoTarget::parse(name).filter(|_| argument.is_empty()) => Ok(Command::Goto { target }),
+            "agg" => Ok(Command::Aggregate { key: (!argument.is_empty()).then(|| argument.to_string()) }),
+            "" => Err(tr!("No command given")),
+            _ => Err(tr!("Unknown command: {}", name)),
+        };
+    }
+}
+
+impl Command {
+    /// Whether the command changes the document.
+    pub fn modifies_document(&self) -> bool {
+        return matches!(
+            self,
+            Command::Read { .. }
+                | Command::ImportCsv { .. }
+                | Command::Patch { .. }
+                | Command::MergePatch { .. }
+                | Command::ParseString
+                | Command::Stringify
+                | Command::GroupBy { restructure: true, .. }
+                | Command::Move { .. }
+                | Command::DeleteRange { .. }
+        );
+    }
+}
+
+impl App {
+    pub fn execute_command(&mut self, command: Command) {
+        if command.modifies_document() && !self.check_editable() {
+            return;
+        }
+
+        match command {
+            Command::Export { path, force } => self.export_value_at_cursor(&path, force),
+            Command::Read { path } => self.read_file_at_cursor(&path),
+            Command::ImportCsv { path } => self.import_csv_at_cursor(&path),
+            Command::Patch { path } => self.apply_patch_file(&path),
+            Command::MergePatch { path } => self.apply_merge_patch_file(&path),
+            Command::ExportMergePatch { path, force } => self.export_merge_patch(&path, force),
+            Command::Format { syntax } => self.set_syntax(syntax),
+            Command::Write { force: false } => self.update(Action::App(SystemAction::Save)),
+            Command::Write { force: true } => self.update(Action::App(SystemAction::ForceSave)),
+            Command::SaveAs { path, force } => self.save_as_path(&path, force),
+            Command::Problems => self.update(Action::AppNavigation(AppNavigationAction::ToProblemsPanel)),
+            Command::Schema { path, force } => {
+                let content = serde_json::to_string_pretty(&json_schema(self.document())).unwrap_or_default();
+                self.write_or_copy(&content, path.as_deref(), force, translate("JSON Schema"), "schema");
+            }
+            Command::TypeScript { path, force } => {
+                let content = typescript_types(self.document(), &self.root_type_name());
+                self.write_or_copy(&content, path.as_deref(), force, translate("TypeScript types"), "ts");
+            }
+            Command::Rust { path, force } => {
+                let content = rust_types(self.document(), &self.root_type_name());
+                self.write_or_copy(&content, path.as_deref(), force, translate("Rust structs"), "rust");
+            }
+            Command::ParseString => self.parse_string_at_cursor(),
+            Command::Stringify => self.stringify_value_at_cursor(),
+            Command::Aggregate { key } => self.aggregate_at_cursor(key.as_deref()),
+            Command::Sort { field } => self.sort_view(field.as_deref()),
+            Command::Unsort => self.unsort_view(),
+            Command::Table => self.open_table(),
+            Command::Quit { force: true } => self.update(Action::App(SystemAction::DiscardAndQuit)),
+            Command::Quit { force: false } => {
+                if self.is_dirty {
+                    self.report(
+                        tr!("There are unsaved changes. Save them with :wq, or drop them with :q!"),
+                        ReportedMessageKinds::Error,
+                        Duration::from_secs(3)
+                    );
+                    return;
+                }
+
+                self.update(Action::App(SystemAction::Quit));
+            }
+            Command::WriteQuit { force: false } => self.update(Action::App(SystemAction::SaveAndQuit)),
+            Command::WriteQuit { force: true } => self.update(Action::App(SystemAction::ForceSaveAndQuit)),
+            Command::Goto { target } => self.go_to(target),
+            Command::Set { option, assignment } => self.set_option(&option, assignment),
+            Command::Tutorial => self.open_tutorial(),
+            Command::Move { pointer } => self.move_at_cursor_to_pointer(&pointer),
+            Command::DeleteRange { start, end } => self.delete_range_at_cursor(start, end),
+            Command::GroupBy { key, restructure } => self.group_array_at_cursor(&key, restructure),
+            Command::Stats => self.open_preview(Preview {
+                title: tr!("Document statistics"),
+                lines: document_stats(&self.json).to_lines(),
+                scroll: 0,
+                replacement: None,
+            }),
+        }
+    }
+
+    fn go_to(&mut self, target: GotoTarget) {
+        let line = match target {
+            GotoTarget::Line(line) if line <= self.lines_count => Some(line - 1),
+            GotoTarget::Line(_) => None,
+            GotoTarget::Pointer(pointer) => pointer_to_path(&pointer, &self.json).and_then(|path| get_line_at_path(&path, &self.json)),
+        };
+
+        match line {
+            Some(line) => self.move_cursor_to_line(line),
+            None => self.report(tr!("There is no such line or value in the document"), ReportedMessageKinds::Error, Duration::from_secs(3)),
+        }
+    }
+
+    fn aggregate_at_cursor(&self, key: Option<&str>) {
+        let Some(path) = get_path_at_line(self.line_at_cursor, &self.json) else {
+            self.report(tr!("There is no value under the cursor"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+        let value_at = |path: &[PathSegment]| self.json.pointer(&path_to_pointer(path));
+        let name = |path: &[PathSegment]| if path.is_empty() { tr!("the document") } else { path_to_pointer(path) };
+
+        // What to aggregate, with a description of it for the report.
+        let (values, description): (Vec<&Value>, String) = match (value_at(&path), key) {
+            (Some(Value::Array(elements)), Some(key)) => (
+                elements.iter().filter_map(|element| element.get(key)).collect(),
+                tr!("\"{}\" of {}", key, name(&path)),
+            ),
+            (Some(Value::Array(elements)), None) => (elements.iter().collect(), name(&path)),
+            // A field of an object in an array: the same field across the objects.
+            (Some(_), None) if path.len() >= 2
+                && let Some(PathSegment::Key(field)) = path.last()
+                && let Some(Value::Array(elements)) = value_at(&path[..path.len() - 2]) => (
+                elements.iter().filter_map(|element| element.get(field)).collect(),
+                tr!("\"{}\" of {}", field, name(&path[..path.len() - 2])),
+            ),
+            // An element of an array: the whole array.
+            (Some(_), None) if let Some(PathSegment::Index(_)) = path.last()
+                && let Some(Value::Array(elements)) = value_at(&path[..path.len() - 1]) => (
+                elements.iter().collect(),
+                name(&path[..path.len() - 1]),
+            ),
+            _ => (vec![], String::new()),
+        };
+
+        match Aggregate::of(values) {
+            Some(aggregate) => self.report(format!("{}: {}", description, aggregate.summary()), ReportedMessageKinds::Info, Duration::from_secs(10)),
+            None => self.report(
+                tr!("No numbers to aggregate. Put the cursor on an array of numbers or on a field of an object in an array, or use :agg <key> on an array of objects"),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(5)
+            ),
+        }
+    }
+
+    /// The path of the array under the cursor, or of the array holding the element under it.
+    pub fn array_path_at_cursor(&self) -> Option<Vec<PathSegment>> {
+        let mut path = get_path_at_line(self.line_at_cursor, &self.json)?;
+        if !self.json.pointer(&path_to_pointer(&path))?.is_array() {
+            path.pop()?;
+        }
+
+        return self.json.pointer(&path_to_pointer(&path))?.is_array().then_some(path);
+    }
+
+    fn group_array_at_cursor(&mut self, key: &str, restructure: bool) {
+        let Some(path) = self.array_path_at_cursor() else {
+            self.report(tr!("Put the cursor on an array of objects, or on one of its elements"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+        let Some(Value::Array(elements)) = get_value_at_path_mut(&path, &mut self.json) else {
+            return;
+        };
+
+        let groups = group_by(elements, key);
+
+        if restructure {
+            let grouped = groups.into_iter().map(|(name, elements)| (name, Value::Array(elements))).collect();
+            if let Some(array) = get_value_at_path_mut(&path, &mut self.json) {
+                *array = Value::Object(grouped);
+            }
+            self.mark_dirty();
+            self.report(tr!("Grouped the array by \"{}\"", key), ReportedMessageKinds::Success, Duration::from_secs(3));
+            return;
+        }
+
+        let mut counts: Vec<(String, usize)> = groups.into_iter().map(|(name, elements)| (name, elements.len())).collect();
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let name_width = counts.iter().map(|(name, _)| name.chars().count()).max().unwrap_or_default();
+
+        let mut lines: Vec<String> = counts.iter().map(|(name, count)| format!("{:<width$}  {}", name, count, width = name_width)).collect();
+        lines.push(String::new());
+        lines.push(tr!("Use :groupby! {} to turn the array into an object of the groups", key));
+
+        self.open_preview(Preview {
+            title: tr!("{} groups by \"{}\"", counts.len(), key),
+            lines,
+            scroll: 0,
+            replacement: None,
+        });
+    }
+
+    /// Removes a slice of the elements of the array under the cursor, into the registers like `dd`.
+    fn delete_range_at_cursor(&mut self, start: usize, end: Option<usize>) {
+        let Some(path) = self.array_path_at_cursor() else {
+            self.report(tr!("Put the cursor on an array, or on one of its elements"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+        let Some(Value::Array(elements)) = get_value_at_path_mut(&path, &mut self.json) else {
+            return;
+        };
+        let length = elements.len();
+        let Some(removed) = remove_elements(elements, start, end) else {
+            self.report(tr!("The array has {} elements, there's none at index {}", length, start), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+        let count = removed.len();
+
+        self.registers.store(None, Yanked { key: None, value: Value::Array(removed), is_range: true });
+        self.mark_dirty();
+        self.report(tr!("Deleted {} elements", count), ReportedMessageKinds::Success, Duration::from_secs(3));
+    }
+
+    /// Replaces the string under the cursor with the object or array it holds as JSON text.
+    fn parse_string_at_cursor(&mut self) {
+        let Some(Value::String(text)) = get_value_at_line(self.line_at_cursor, &self.json) else {
+            self.report(tr!("The value under the cursor isn't a string"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let parsed = match serde_json::from_str::<Value>(text) {
+            Ok(parsed) if parsed.is_object() || parsed.is_array() => parsed,
+            Ok(_) => {
+                self.report(tr!("The string doesn't hold a JSON object or array"), ReportedMessageKinds::Error, Duration::from_secs(3));
+                return;
+            }
+            Err(err) => {
+                self.report(tr!("The string isn't valid JSON: {}", err), ReportedMessageKinds::Error, Duration::from_secs(5));
+                return;
+            }
+        };
+
+        let Some(value) = get_path_at_line(self.line_at_cursor, &self.json).and_then(|path| get_value_at_path_mut(&path, &mut self.json)) else {
+            return;
+        };
+
+        *value = parsed;
+        self.mark_dirty();
+        self.report(tr!("Parsed the string into JSON. Use :stringify to turn it back"), ReportedMessageKinds::Success, Duration::from_secs(3));
+    }
+
+    /// Replaces the object or array under the cursor with its compact JSON text, as a string.
+    fn stringify_value_at_cursor(&mut self) {
+        let text = match get_value_at_line(self.line_at_cursor, &self.json) {
+            Some(value) if value.is_object() || value.is_array() => serde_json::to_string(value).unwrap_or_default(),
+            _ => {
+                self.report(tr!("The value under the cursor isn't an object or an array"), ReportedMessageKinds::Error, Duration::from_secs(3));
+                return;
+            }
+        };
+
+        let Some(value) = get_path_at_line(self.line_at_cursor, &self.json).and_then(|path| get_value_at_path_mut(&path, &mut self.json)) else {
+            return;
+        };
+
+        *value = Value::String(text);
+        self.mark_dirty();
+        self.report(tr!("Turned the value into a JSON string. Use :parse to turn it back"), ReportedMessageKinds::Success, Duration::from_secs(3));
+    }
+
+    /// The name of the type generated for the whole document, after the file (`package.json`
+    /// gives `Package`).
+    fn root_type_name(&self) -> String {
+        let stem = self.file_path
+            .as_deref()
+            .and_then(|path| path.file_name())
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split('.').next())
+            .unwrap_or_default();
+
+        return if stem.is_empty() { "Root".to_string() } else { type_name(stem) };
+    }
+
+    /// Writes generated content to a new file, or copies it to the clipboard when there's no path.
+    /// `command` is the name of the command that generated it, for the hint to overwrite a file.
+    fn write_or_copy(&self, content: &str, path: Option<&str>, force: bool, what: &str, command: &str) {
+        let Some(path) = path else {
+            match copy_to_clipboard(content) {
+                Ok(()) => self.report(tr!("Copied the {} to the clipboard", what), ReportedMessageKinds::Success, Duration::from_secs(3)),
+                Err(err) => self.report(tr!("Failed to copy to the clipboard: {}", err), ReportedMessageKinds::Error, Duration::from_secs(3)),
+            }
+            return;
+        };
+
+        if !force && Path::new(path).exists() {
+            self.report(
+                tr!("{} already exists. Use :{}! to overwrite it", path, command),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        if let Err(err) = fs::write(path, content) {
+            self.report(
+                tr!("Failed to write {}: {}", path, err),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        self.report(
+            tr!("Wrote the {} to {}", what, path),
+            ReportedMessageKinds::Success,
+            Duration::from_secs(3)
+        );
+    }
+
+    /// Writes the focused object/array/value, pretty-printed, to the given path.
+    fn export_value_at_cursor(&self, path: &str, force: bool) {
+        let Some(value) = get_value_at_line(self.line_at_cursor, &self.json) else {
+            self.report(
+                tr!("Nothing to export at the current line"),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        };
+
+        if !force && Path::new(path).exists() {
+            self.report(
+                tr!("{} already exists. Use :export! to overwrite it", path),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        let content = match serde_json::to_string_pretty(value) {
+            Ok(content) => content,
+            Err(err) => {
+                self.report(
+                    tr!("Failed to serialize value: {}", err),
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(3)
+                );
+                return;
+            }
+        };
+
+        if let Err(err) = fs::write(path, content) {
+            self.report(
+                tr!("Failed to write {}: {}", path, err),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        self.report(
+            tr!("Exported to {}", path),
+            ReportedMessageKinds::Success,
+            Duration::from_secs(3)
+        );
+    }
+
+    fn save_as_path(&mut self, path: &str, force: bool) {
+        if !force && Path::new(path).exists() {
+            self.report(
+                tr!("{} already exists. Use :saveas! to overwrite it", path),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        if self.save_as(PathBuf::from(path)) {
+            self.report(
+                tr!("Saved to {}", path),
+                ReportedMessageKinds::Success,
+                Duration::from_secs(3)
+            );
+        }
+    }
+
+    /// Applies the operations of a JSON Patch file to the document. The result is left unsaved
+    /// so it can be reviewed first.
+    fn apply_patch_file(&mut self, path: &str) {
+        let Some(patch) = self.read_json_file(path) else {
+            return;
+        };
+
+        match apply_patch(&mut self.json, &patch) {
+            Ok(count) => {
+                self.mark_dirty();
+                self.report(
+                    tr!("Applied all {} patch operations. Review and save with :w", count),
+                    ReportedMessageKinds::Success,
+                    Duration::from_secs(5)
+                );
+            }
+            Err(PatchError::Failed { index, description, error }) => {
+                self.report(
+                    tr!("The patch wasn't applied, operation #{} {} failed: {}", index + 1, description, error),
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(10)
+                );
+            }
+            Err(error) => self.report(error.to_string(), ReportedMessageKinds::Error, Duration::from_secs(3)),
+        }
+    }
+
+    /// Merges a JSON Merge Patch file into the document. The result is left unsaved so it can be
+    /// reviewed first.
+    fn apply_merge_patch_file(&mut self, path: &str) {
+        let Some(patch) = self.read_json_file(path) else {
+            return;
+        };
+
+        let before = self.json.clone();
+        apply_merge_patch(&mut self.json, &patch);
+
+        if self.json == before {
+            self.report(
+                tr!("The merge patch didn't change anything"),
+                ReportedMessageKinds::Info,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        self.mark_dirty();
+
+        self.report(
+            tr!("Applied the merge patch. Review and save with :w"),
+            ReportedMessageKinds::Success,
+            Duration::from_secs(5)
+        );
+    }
+
+    /// Writes the merge patch of the unsaved changes (from the last saved document to the current one).
+    fn export_merge_patch(&self, path: &str, force: bool) {
+        if !force && Path::new(path).exists() {
+            self.report(
+                tr!("{} already exists. Use :exportmerge! to overwrite it", path),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        let (patch, has_lost_nulls) = create_merge_patch(&self.saved_json, self.document());
+
+        let content = match serde_json::to_string_pretty(&patch) {
+            Ok(content) => content,
+            Err(err) => {
+                self.report(
+                    tr!("Failed to serialize the merge patch: {}", err),
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(3)
+                );
+                return;
+            }
+        };
+
+        if let Err(err) = fs::write(path, content) {
+            self.report(
+                tr!("Failed to write {}: {}", path, err),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        if has_lost_nulls {
+            self.report(
+                tr!("Exported the merge patch to {}. Merge patches can't set values to null, so those are removed instead", path),
+                ReportedMessageKinds::Warning,
+                Duration::from_secs(5)
+            );
+        } else {
+            self.report(
+                tr!("Exported the merge patch to {}", path),
+                ReportedMessageKinds::Success,
+                Duration::from_secs(3)
+            );
+        }
+    }
+
+    fn set_syntax(&mut self, syntax: Syntax) {
+        if self.file_format.syntax == syntax {
+            return;
+        }
+
+        self.file_format.syntax = syntax;
+        self.mark_dirty();
+
+        let name = match syntax {
+            Syntax::Json => translate("strict JSON"),
+            Syntax::Json5 => "JSON5",
+            Syntax::Jsonc => "JSONC",
+            Syntax::Hjson => "Hjson",
+            Syntax::Yaml => "YAML",
+            Syntax::MessagePack => "MessagePack",
+            Syntax::Xml => "XML",
+        };
+        if syntax != Syntax::Jsonc && !self.comments.is_empty() {
+            self.report(
+                tr!("The file will be saved as {}, without its comments", name),
+                ReportedMessageKinds::Warning,
+                Duration::from_secs(5)
+            );
+            return;
+        }
+
+        self.report(
+            tr!("The file will be saved as {}", name),
+            ReportedMessageKinds::Info,
+            Duration::from_secs(3)
+        );
+    }
+
+    /// Reads and parses a JSON file, reporting to the user what went wrong if it couldn't.
+    fn read_json_file(&self, path: &str) -> Option<Value> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                self.report(
+                    tr!("Failed to read {}: {}", path, err),
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(3)
+                );
+                return None;
+            }
+        };
+
+        return match serde_json::from_str(&content) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.report(
+                    tr!("Failed to parse {}: {}", path, err),
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(3)
+                );
+                None
+            }
+        };
+    }
+
+    /// Inserts the content of another JSON file after the cursor.
+    fn read_file_at_cursor(&mut self, path: &str) {
+        let Some(value) = self.read_json_file(path) else {
+            return;
+        };
+
+        self.insert_file_content_at_cursor(path, value);
+    }
+
+    /// Inserts the rows of a CSV file, as an array of objects, after the cursor.
+    fn import_csv_at_cursor(&mut self, path: &str) {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                self.report(
+                    tr!("Failed to read {}: {}", path, err),
+                    ReportedMessageKinds::Error,
+                    Duration::from_secs(3)
+                );
+                return;
+            }
+        };
+
+        match csv_to_json(&content) {
+            Ok(value) => self.insert_file_content_at_cursor(path, value),
+            Err(message) => self.report(message, ReportedMessageKinds::Error, Duration::from_secs(3)),
+        }
+    }
+
+    /// Inserts a value read from a file after the cursor. Inside objects, the file name (without
+    /// extension) is used as the key, with a `_copy` suffix if the object already has it.
+    fn insert_file_content_at_cursor(&mut self, path: &str, value: Value) {
+        let base_key = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("imported"));
+        let key = match get_nested_object_to_insert_into(self.line_at_cursor_without_empty_lines(), &mut self.json).0 {
+            Some(Value::Object(map)) => free_key(map, &base_key),
+            _ => base_key,
+        };
+
+        if !self.insert_value_after_cursor(key.clone(), value) {
+            self.report(
+                tr!("Could not find where to insert at the current line"),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3)
+            );
+            return;
+        }
+
+        self.mark_dirty();
+
+        self.report(
+            tr!("Inserted the content of {} as '{}'", path, key),
+            ReportedMessageKinds::Success,
+            Duration::from_secs(3)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            Command::parse("export out.json"),
+            Ok(Command::Export { path: "out.json".to_string(), force: false }),
+        );
+        assert_eq!(
+            Command::parse("  export!   my file.json "),
+            Ok(Command::Export { path: "my file.json".to_string(), force: true }),
+        );
+        assert!(Command::parse("export").is_err());
+        assert_eq!(
+            Command::parse("read ./fragment.json"),
+            Ok(Command::Read { path: "./fragment.json".to_string() }),
+        );
+        assert_eq!(Command::parse("patch fix.json"), Ok(Command::Patch { path: "fix.json".to_string() }));
+        assert_eq!(
+            Command::parse("exportmerge! changes.json"),
+            Ok(Command::ExportMergePatch { path: "changes.json".to_string(), force: true }),
+        );
+        assert_eq!(Command::parse("format JSON5"), Ok(Command::Format { syntax: Syntax::Json5 }));
+        assert!(Command::parse("format toml").is_err());
+        assert_eq!(Command::parse("w"), Ok(Command::Write { force: false }));
+        assert_eq!(Command::parse("w!"), Ok(Command::Write { force: true }));
+        assert_eq!(
+            Command::parse("saveas copy.json"),
+            Ok(Command::SaveAs { path: "copy.json".to_string(), force: false }),
+        );
+        assert_eq!(Command::parse("schema"), Ok(Command::Schema { path: None, force: false }));
+        assert_eq!(
+            Command::parse("schema! config.schema.json"),
+            Ok(Command::Schema { path: Some("config.schema.json".to_string()), force: true }),
+        );
+        assert_eq!(Command::parse("ts types.ts"), Ok(Command::TypeScript { path: Some("types.ts".to_string()), force: false }));
+        assert_eq!(Command::parse("rust!"), Ok(Command::Rust { path: None, force: true }));
+        assert_eq!(Command::parse("parse"), Ok(Command::ParseString));
+        assert_eq!(Command::parse("stringify"), Ok(Command::Stringify));
+        assert_eq!(Command::parse("stats"), Ok(Command::Stats));
+        assert_eq!(Command::parse("agg"), Ok(Command::Aggregate { key: None }));
+        assert_eq!(Command::parse("groupby! status"), Ok(Command::GroupBy { key: "status".to_string(), restructure: true }));
+        assert!(Command::parse("groupby").is_err());
+        assert_eq!(Command::parse("sort id"), Ok(Command::Sort { field: Some("id".to_string()) }));
+        assert_eq!(Command::parse("unsort"), Ok(Command::Unsort));
+        assert_eq!(Command::parse("table"), Ok(Command::Table));
+        assert_eq!(Command::parse("q"), Ok(Command::Quit { force: false }));
+        assert_eq!(Command::parse("q!"), Ok(Command::Quit { force: true }));
+        assert_eq!(Command::parse("wq!"), Ok(Command::WriteQuit { force: true }));
+        assert_eq!(Command::parse("goto 12"), Ok(Command::Goto { target: GotoTarget::Line(12) }));
+        assert_eq!(Command::parse("goto /users/0"), Ok(Command::Goto { target: GotoTarget::Pointer("/users/0".to_string()) }));
+        assert_eq!(Command::parse("12"), Ok(Command::Goto { target: GotoTarget::Line(12) }));
+        assert_eq!(Command::parse("mv /users/0"), Ok(Command::Move { pointer: "/users/0".to_string() }));
+        assert_eq!(Command::parse("delete 3..10"), Ok(Command::DeleteRange { start: 3, end: Some(10) }));
+        assert_eq!(Command::parse("d 3..=10"), Ok(Command::DeleteRange { start: 3, end: Some(11) }));
+        assert_eq!(Command::parse("delete ..2"), Ok(Command::DeleteRange { start: 0, end: Some(2) }));
+        assert_eq!(Command::parse("delete 5.."), Ok(Command::DeleteRange { start: 5, end: None }));
+        assert!(Command::parse("delete 0..=18446744073709551615").is_err());
+        assert!(Command::parse("delete 10..3").is_err());
+        assert!(Command::parse("delete 3").is_err());
+        assert!(Command::parse("goto 0").is_err());
+        assert_eq!(
+            Command::parse("set notimestamps"),
+            Ok(Command::Set { option: "timestamps".to_string(), assignment: OptionAssignment::Off }),
+        );
+        assert_eq!(Command::parse("agg amount"), Ok(Command::Aggregate { key: Some("amount".to_string()) }));
+        assert!(Command::parse("").is_err());
+        assert!(Command::parse("frobnicate").is_err());
+    }
+
+    #[test]
+    fn test_parse_and_stringify() {
+        let mut app = App::default();
+        app.json = serde_json::json!({ "payload": "{\"id\":1,\"tags\":[\"a\"]}" });
+
+        app.execute_command(Command::ParseString);
+        assert_eq!(app.json, serde_json::json!({ "payload": { "id": 1, "tags": ["a"] } }));
+
+        app.execute_command(Command::Stringify);
+        assert_eq!(app.json, serde_json::json!({ "payload": "{\"id\":1,\"tags\":[\"a\"]}" }));
+    }
+
+    #[test]
+    fn test_delete_range() {
+        let mut app = App::default();
+        app.json = serde_json::json!({ "rows": [0, 1, 2, 3, 4, 5] });
+
+        app.execute_command(Command::DeleteRange { start: 1, end: Some(3) });
+        assert_eq!(app.json, serde_json::json!({ "rows": [0, 3, 4, 5] }));
+        assert_eq!(app.registers.get(None).unwrap().value, serde_json::json!([1, 2]));
+
+        // From an element, up to an end past the last one.
+        app.line_at_cursor = 2;
+        app.execute_command(Command::DeleteRange { start: 2, end: Some(100) });
+        assert_eq!(app.json, serde_json::json!({ "rows": [0, 3] }));
+
+        app.execute_command(Command::DeleteRange { start: 2, end: None });
+        assert_eq!(app.json, serde_json::json!({ "rows": [0, 3] }));
+    }
+
+    #[test]
+    fn test_read_into_a_taken_key() {
+        let path = std::env::temp_dir().join(format!("todd_read_test_{}", std::process::id())).join("config.json");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{ "port": 81 }"#).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let mut app = App::default();
+        app.json = serde_json::json!({ "config": { "port": 80 }, "name": "todd" });
+
+        // The key of the file is taken, so the existing pair is kept.
+        app.line_at_cursor = 2;
+        app.execute_command(Command::Read { path: path.clone() });
+        assert_eq!(
+            serde_json::to_string(&app.json).unwrap(),
+            r#"{"config":{"port":80},"name":"todd","config_copy":{"port":81}}"#,
+        );
+
+        fs::remove_dir_all(Path::new(&path).parent().unwrap()).unwrap();
+    }
+}
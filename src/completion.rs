@@ -0,0 +1,180 @@
+//
+// Suggestions in the edit popup, taken from the rest of the document so that similar records
+// stay consistent.
+//
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::{actions::{Action, AppNavigationAction}, app::{value_from_input, App, CurrentlyEditing}, schema::value_choice_label, utils::json::{get_path_at_line, path_to_pointer}};
+
+/// How many suggestions are offered at most.
+pub const MAX_SUGGESTIONS: usize = 6;
+
+/// The keys of the sibling objects that the object doesn't have yet, starting with the typed
+/// prefix (case-insensitive). The most common keys come first.
+pub fn sibling_keys(object: &Value, siblings: &[Value], prefix: &str) -> Vec<String> {
+    let Value::Object(object) = object else {
+        return vec![];
+    };
+
+    let prefix = prefix.to_lowercase();
+    let mut counts: IndexMap<&str, usize> = IndexMap::new();
+    for sibling in siblings.iter().filter_map(Value::as_object) {
+        for key in sibling.keys() {
+            if !object.contains_key(key) && key.to_lowercase().starts_with(&prefix) {
+                *counts.entry(key.as_str()).or_default() += 1;
+            }
+        }
+    }
+
+    // A stable sort keeps the keys that are as common in the order they were first seen.
+    counts.sort_by(|_, a, _, b| b.cmp(a));
+
+    return counts.keys().take(MAX_SUGGESTIONS).map(|key| key.to_string()).collect();
+}
+
+/// The distinct values the key has across the document, starting with the typed prefix
+/// (case-insensitive) but not equal to it. The most common values come first. Only values that
+/// are typed back the same are suggested, so a string like `"42"` isn't.
+pub fn values_of_key(json: &Value, key: &str, prefix: &str) -> Vec<String> {
+    let mut counts: IndexMap<String, usize> = IndexMap::new();
+    count_values_of_key(json, key, &mut counts);
+
+    let prefix_lowercase = prefix.to_lowercase();
+    counts.retain(|label, _| label != prefix && label.to_lowercase().starts_with(&prefix_lowercase));
+    counts.sort_by(|_, a, _, b| b.cmp(a));
+
+    return counts.into_keys().take(MAX_SUGGESTIONS).collect();
+}
+
+fn count_values_of_key(json: &Value, key: &str, counts: &mut IndexMap<String, usize>) {
+    match json {
+        Value::Object(map) => {
+            for (current_key, value) in map {
+                if current_key == key && !value.is_object() && !value.is_array() {
+                    let label = value_choice_label(value);
+                    if value_from_input(&label) == *value {
+                        *counts.entry(label).or_default() += 1;
+                    }
+                }
+                count_values_of_key(value, key, counts);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                count_values_of_key(value, key, counts);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl App {
+    /// The suggestions for the focused input of the edit popup.
+    pub fn suggestions(&self) -> Vec<String> {
+        return match self.currently_editing {
+            Some(CurrentlyEditing::Key) => self.key_suggestions(),
+            Some(CurrentlyEditing::Value) if !self.is_choosing_value() => self.value_suggestions(),
+            _ => vec![],
+        };
+    }
+
+    /// Values the typed key has elsewhere in the document. Array elements have no key to go by.
+    fn value_suggestions(&self) -> Vec<String> {
+        let key = self.key_input.content();
+        if key.is_empty() {
+            return vec![];
+        }
+
+        return values_of_key(&self.json, key, self.value_input.content());
+    }
+
+    /// Keys from the other elements of the array when the cursor is in an object inside one.
+    fn key_suggestions(&self) -> Vec<String> {
+        let Some(mut path) = get_path_at_line(self.line_at_cursor_without_empty_lines(), &self.json) else {
+            return vec![];
+        };
+
+        // The object the pair is written into, then the array holding it.
+        path.pop();
+        let Some(object) = self.json.pointer(&path_to_pointer(&path)) else {
+            return vec![];
+        };
+        if path.pop().is_none() {
+            return vec![];
+        }
+        let Some(Value::Array(siblings)) = self.json.pointer(&path_to_pointer(&path)) else {
+            return vec![];
+        };
+
+        return sibling_keys(object, siblings, self.key_input.content());
+    }
+
+    pub fn select_suggestion(&mut self, forward: bool) {
+        let count = self.suggestions().len();
+        if count == 0 {
+            return;
+        }
+
+        self.selected_suggestion = if forward { (self.selected_suggestion + 1) % count } else { (self.selected_suggestion + count - 1) % count };
+    }
+
+    /// Fills the focused input with the selected suggestion. A key moves on to the value input.
+    pub fn accept_suggestion(&mut self) {
+        let suggestions = self.suggestions();
+        let Some(suggestion) = suggestions.get(self.selected_suggestion.min(suggestions.len().saturating_sub(1))) else {
+            return;
+        };
+
+        self.selected_suggestion = 0;
+        match self.currently_editing {
+            Some(CurrentlyEditing::Key) => {
+                self.key_input.replace_content(suggestion);
+                self.update(Action::AppNavigation(AppNavigationAction::ToEditingScreen));
+            }
+            Some(CurrentlyEditing::Value) => self.value_input.replace_content(suggestion),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sibling_keys() {
+        let invoices = json!([
+            { "id": 1, "amount": 100, "due_date": "2024-01-01" },
+            { "id": 2, "amount": 200, "due_date": "2024-02-01", "discount": 10 },
+            { "id": 3 }
+        ]);
+        let siblings = invoices.as_array().unwrap();
+
+        assert_eq!(sibling_keys(&siblings[2], siblings, ""), vec!["amount", "due_date", "discount"]);
+        assert_eq!(sibling_keys(&siblings[2], siblings, "D"), vec!["due_date", "discount"]);
+        assert_eq!(sibling_keys(&siblings[1], siblings, ""), Vec::<String>::new());
+        assert_eq!(sibling_keys(&json!(1), siblings, ""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_values_of_key() {
+        let json = json!({
+            "status": "active",
+            "users": [
+                { "status": "archived", "code": "42" },
+                { "status": "active", "code": 7 },
+                { "status": { "nested": true } },
+                { "status": "Away" }
+            ]
+        });
+
+        assert_eq!(values_of_key(&json, "status", ""), vec!["active", "archived", "Away"]);
+        assert_eq!(values_of_key(&json, "status", "a"), vec!["active", "archived", "Away"]);
+        assert_eq!(values_of_key(&json, "status", "ar"), vec!["archived"]);
+        assert_eq!(values_of_key(&json, "status", "active"), Vec::<String>::new());
+        assert_eq!(values_of_key(&json, "code", ""), vec!["7"]);
+    }
+}
@@ -0,0 +1,118 @@
+//
+// User configuration, read from `$XDG_CONFIG_HOME/todd/config.json` (or `~/.config/todd/config.json`).
+//
+
+use std::{env, fs, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::{highlight_rules::HighlightRule, i18n::{set_locale, Locale}, input_bindings::InputBindings, keymap::Keymap, schema::SchemaOnSave, status_line::StatusLine, theme::Theme, type_icons::TypeIcons};
+
+/// Every option is optional in the config file and falls back to its default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Restore the cursor and scroll position a file had when it was last closed.
+    pub restore_session: bool,
+    /// Whether saving a document that doesn't match its schema only warns (`"warn"`) or is
+    /// refused unless forced (`"block"`).
+    pub schema_on_save: SchemaOnSave,
+    /// Styles given to the keys and values matching patterns, like secrets or TODO markers.
+    pub highlight_rules: Vec<HighlightRule>,
+    /// The keys bound to the actions, for the ones remapped from their defaults.
+    pub keybindings: Keymap,
+    /// The keys editing the text of the inputs: `"default"`, or `"emacs"` for readline's.
+    pub input_bindings: InputBindings,
+    /// The colors todd draws with. Without one, it's picked for what the terminal supports.
+    pub theme: Theme,
+    /// Long lines continue on the next rows instead of being cut at the edge of the terminal.
+    pub wrap: bool,
+    /// Line numbers in front of the lines.
+    pub number: bool,
+    /// How many lines are kept between the cursor and the top or bottom of the view when scrolling.
+    pub scrolloff: usize,
+    /// Ask before deleting or replacing values that hold others.
+    pub confirm_destructive: bool,
+    /// The segments of the status line and their order.
+    pub status_line: StatusLine,
+    /// Show the opened file and whether it has unsaved changes in the terminal's title.
+    pub terminal_title: bool,
+    /// Clicking moves the cursor, the wheel scrolls, and dragging the elements of an array reorders them.
+    pub mouse: bool,
+    /// Glyphs in front of the lines telling the type of their value: `"off"`, `"ascii"` or `"nerd"`.
+    pub type_icons: TypeIcons,
+    /// The language of the interface: `"en"`, `"es"` or `"fr"`.
+    pub locale: Locale,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            restore_session: true,
+            schema_on_save: SchemaOnSave::default(),
+            highlight_rules: vec![],
+            keybindings: Keymap::default(),
+            input_bindings: InputBindings::default(),
+            theme: Theme::for_terminal(),
+            wrap: false,
+            number: false,
+            scrolloff: 5,
+            confirm_destructive: true,
+            status_line: StatusLine::default(),
+            terminal_title: true,
+            mouse: false,
+            type_icons: TypeIcons::default(),
+            locale: Locale::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file. A missing file gives back the defaults.
+    pub fn load() -> Result<Config, String> {
+        let Some(path) = config_dir().map(|dir| dir.join("config.json")) else {
+            return Ok(Config::default());
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+        };
+
+        // The locale is picked before the rest is read, for the defaults of the other options to
+        // be in its language.
+        if let Ok(LocaleOption { locale }) = serde_json::from_str(&content) {
+            set_locale(locale);
+        }
+
+        return serde_json::from_str(&content)
+            .map_err(|err| format!("Invalid config in {}: {}", path.display(), err));
+    }
+}
+
+/// Only the `locale` option of the config file.
+#[derive(Deserialize)]
+struct LocaleOption {
+    #[serde(default)]
+    locale: Locale,
+}
+
+/// The directory holding the config file.
+pub fn config_dir() -> Option<PathBuf> {
+    return base_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("todd"));
+}
+
+/// The directory holding state that todd keeps across runs.
+pub fn state_dir() -> Option<PathBuf> {
+    return base_dir("XDG_STATE_HOME", ".local/state").map(|dir| dir.join("todd"));
+}
+
+/// Reads an XDG base directory from the environment, falling back to its default under the home directory.
+fn base_dir(xdg_variable: &str, home_fallback: &str) -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(xdg_variable).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    return env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback));
+}
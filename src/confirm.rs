@@ -0,0 +1,110 @@
+//
+// A popup asking to confirm something before it's done, answered with one key per choice.
+//
+
+use crate::{actions::{Action, ConfirmAction, SystemAction}, app::{App, CurrentScreen}, i18n::{tr, translate}};
+
+/// One of the answers to a confirmation.
+#[derive(Debug)]
+pub struct ConfirmChoice {
+    /// The key choosing it, shown in parentheses in front of the label.
+    pub key: char,
+    pub label: &'static str,
+    /// What choosing it does. Without an action, it only closes the popup.
+    pub action: Option<Action>,
+}
+
+/// The content of the confirmation popup.
+#[derive(Debug)]
+pub struct Confirm {
+    pub title: String,
+    pub message: String,
+    pub choices: Vec<ConfirmChoice>,
+    /// The screen the popup was opened from, and goes back to.
+    return_screen: CurrentScreen,
+}
+
+impl Confirm {
+    pub fn new(title: &str, message: String, choices: Vec<ConfirmChoice>) -> Confirm {
+        return Confirm { title: title.to_string(), message, choices, return_screen: CurrentScreen::ViewingFile };
+    }
+
+    /// A question answered with yes, running the action, or no.
+    pub fn yes_no(title: &str, message: String, action: Action) -> Confirm {
+        return Confirm::new(title, format!("{} (y/n)", message), vec![
+            ConfirmChoice { key: 'y', label: translate("yes"), action: Some(action) },
+            ConfirmChoice { key: 'n', label: translate("no"), action: None },
+        ]);
+    }
+}
+
+impl App {
+    pub fn handle_confirm_actions(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::Choose(key) => {
+                let Some(confirm) = &mut self.confirm else {
+                    return;
+                };
+                let Some(index) = confirm.choices.iter().position(|choice| choice.key == key.to_ascii_lowercase()) else {
+                    return;
+                };
+
+                let choice = confirm.choices.swap_remove(index);
+                self.close_confirm();
+                if let Some(action) = choice.action {
+                    self.update(action);
+                }
+            }
+            ConfirmAction::Cancel => self.close_confirm(),
+        }
+    }
+
+    pub fn open_confirm(&mut self, mut confirm: Confirm) {
+        confirm.return_screen = self.current_screen;
+        self.confirm = Some(confirm);
+        self.current_screen = CurrentScreen::Confirm;
+    }
+
+    fn close_confirm(&mut self) {
+        if let Some(confirm) = self.confirm.take() {
+            self.current_screen = confirm.return_screen;
+        }
+    }
+
+    /// Quits, first asking what to do with the unsaved changes if there are some.
+    pub fn quit_or_confirm(&mut self) {
+        if !self.is_dirty {
+            self.quit();
+            return;
+        }
+
+        self.open_confirm(Confirm::new(translate("Unsaved changes"), tr!("Save the changes before quitting?"), vec![
+            ConfirmChoice { key: 's', label: translate("save"), action: Some(Action::App(SystemAction::SaveAndQuit)) },
+            ConfirmChoice { key: 'd', label: translate("discard"), action: Some(Action::App(SystemAction::DiscardAndQuit)) },
+            ConfirmChoice { key: 'c', label: translate("cancel"), action: None },
+        ]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_quitting() {
+        let mut app = App::default();
+        app.is_dirty = true;
+
+        app.update(Action::App(SystemAction::Quit));
+        assert!(matches!(app.current_screen, CurrentScreen::Confirm));
+
+        // Keys that aren't choices are ignored.
+        app.update(Action::Confirm(ConfirmAction::Choose('x')));
+        assert!(app.confirm.is_some());
+
+        app.update(Action::Confirm(ConfirmAction::Choose('C')));
+        assert!(app.confirm.is_none());
+        assert!(matches!(app.current_screen, CurrentScreen::ViewingFile));
+        assert!(app.is_dirty);
+    }
+}
@@ -0,0 +1,95 @@
+//
+// Deleting nodes from the tree.
+//
+
+use std::time::Duration;
+
+use crate::{actions::{Action, MainViewActions}, app::{App, ReportedMessageKinds}, confirm::Confirm, i18n::{tr, translate}, registers::Yanked, utils::{json::{get_path_at_line, get_value_at_line, path_to_pointer, PathSegment}, patch::remove_by_pointer, stats::descendants_count}};
+
+impl App {
+    /// Deletes the node under the cursor. Deleting one holding other values asks first, unless
+    /// `confirmed` or the config turns the confirmations off.
+    pub fn delete_at_cursor(&mut self, confirmed: bool) {
+        if self.visual_anchor.is_some() {
+            self.delete_visual_range(confirmed);
+            return;
+        }
+
+        let (Some(path), Some(value)) = (get_path_at_line(self.line_at_cursor, &self.json), get_value_at_line(self.line_at_cursor, &self.json)) else {
+            self.report(tr!("Nothing to delete at the current line"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let name = match path.last() {
+            Some(PathSegment::Key(key)) => key.clone(),
+            Some(PathSegment::Index(index)) => tr!("element {}", index),
+            None => return,
+        };
+
+        let nested_count = descendants_count(value);
+        if nested_count > 0 && !confirmed && self.config.confirm_destructive {
+            self.open_confirm(Confirm::yes_no(
+                translate("Delete"),
+                tr!("Delete '{}' and the {} values inside it?", name, nested_count),
+                Action::MainView(MainViewActions::Delete { confirmed: true }),
+            ));
+            return;
+        }
+
+        let removed = match remove_by_pointer(&mut self.json, &path_to_pointer(&path)) {
+            Ok(removed) => removed,
+            Err(err) => {
+                self.report(tr!("Failed to delete '{}': {}", name, err), ReportedMessageKinds::Error, Duration::from_secs(3));
+                return;
+            }
+        };
+
+        // Like in vim, the deleted node can be pasted back elsewhere.
+        let key = match path.last() {
+            Some(PathSegment::Key(key)) => Some(key.clone()),
+            _ => None,
+        };
+        self.registers.store(self.selected_register.take(), Yanked { key, value: removed, is_range: false });
+
+        self.mark_dirty();
+        self.report(tr!("Deleted '{}'", name), ReportedMessageKinds::Success, Duration::from_secs(2));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::{actions::ConfirmAction, app::CurrentScreen};
+
+    #[test]
+    fn test_delete_at_cursor() {
+        let mut app = App::default();
+        app.json = json!({ "name": "Omar", "billing_info": { "card": "4242", "address": { "city": "Cairo" } } });
+
+        // Line 0 is `name`, a plain value, deleted right away.
+        app.update(Action::MainView(MainViewActions::Delete { confirmed: false }));
+        assert_eq!(app.json, json!({ "billing_info": { "card": "4242", "address": { "city": "Cairo" } } }));
+
+        // `billing_info` holds three values, so it asks first.
+        app.update(Action::MainView(MainViewActions::Delete { confirmed: false }));
+        assert!(matches!(app.current_screen, CurrentScreen::Confirm));
+        assert_eq!(app.confirm.as_ref().unwrap().message, "Delete 'billing_info' and the 3 values inside it? (y/n)");
+
+        app.update(Action::Confirm(ConfirmAction::Choose('n')));
+        assert!(matches!(app.current_screen, CurrentScreen::ViewingFile));
+        assert_eq!(app.json["billing_info"]["card"], "4242");
+
+        app.update(Action::MainView(MainViewActions::Delete { confirmed: false }));
+        app.update(Action::Confirm(ConfirmAction::Choose('y')));
+        assert_eq!(app.json, json!({}));
+        assert!(app.is_dirty);
+
+        // Without confirmations, it's deleted right away.
+        app.json = json!({ "tags": ["a", "b"] });
+        app.config.confirm_destructive = false;
+        app.update(Action::MainView(MainViewActions::Delete { confirmed: false }));
+        assert_eq!(app.json, json!({}));
+    }
+}
@@ -2,16 +2,41 @@
 // Main app rendering entry point.
 //
 
-use ratatui::
-    Frame
-;
+use ratatui::{layout::Alignment, style::Style, text::Line, widgets::Paragraph, Frame};
 
-use crate::app::App;
+use crate::{app::App, i18n::{tr, translate}, helpers::get_centered_rect_of_size, theme::strip_colors};
 
-impl<'a> App<'a> {
+/// The smallest terminal the views fit in. A smaller one only gets a notice.
+const MIN_WIDTH: u16 = 30;
+const MIN_HEIGHT: u16 = 8;
+
+impl App {
     /// Draws a view based on the state.
     pub fn draw(&mut self, frame: &mut Frame) {
-        // The only view there is for this app.
-        self.draw_main_view(frame);
+        let area = frame.area();
+        self.size = area.as_size();
+
+        if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+            self.draw_too_small_notice(frame);
+        } else {
+            // The only view there is for this app.
+            self.draw_main_view(frame);
+        }
+
+        if self.no_color {
+            strip_colors(frame.buffer_mut());
+        }
+    }
+
+    /// Tells the terminal is too small to draw in, and how big it needs to be.
+    fn draw_too_small_notice(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let notice = Paragraph::new(vec![
+            Line::from(translate("Terminal too small")),
+            Line::styled(tr!("{}×{}, needs {}×{}", area.width, area.height, MIN_WIDTH, MIN_HEIGHT), Style::default().fg(self.config.theme.dimmed)),
+        ])
+        .alignment(Alignment::Center);
+
+        frame.render_widget(notice, get_centered_rect_of_size(area.width, 2, area));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,106 @@
+//
+// Duplicating the node under the cursor and editing the copy right away, to add one more entry
+// like an existing one. The copy of an object or array is edited from its first value.
+//
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::{app::{App, ReportedMessageKinds}, i18n::tr, utils::{edit::free_key, json::{get_line_at_path, get_path_at_line, get_value_at_path_mut, path_to_pointer, PathSegment}}};
+
+/// The path of the first value in the node that isn't an object or an array, depth first.
+fn first_scalar_path(value: &Value, path: &[PathSegment]) -> Option<Vec<PathSegment>> {
+    let child_path = |segment: PathSegment| [path, &[segment]].concat();
+
+    return match value {
+        Value::Object(map) => map.iter().find_map(|(key, value)| first_scalar_path(value, &child_path(PathSegment::Key(key.clone())))),
+        Value::Array(values) => values.iter().enumerate().find_map(|(index, value)| first_scalar_path(value, &child_path(PathSegment::Index(index)))),
+        _ => Some(path.to_vec()),
+    };
+}
+
+impl App {
+    /// Inserts a copy of the node under the cursor after it, and opens the edit popup on the copy.
+    /// A key already in the object gets a `_copy` suffix, like a paste.
+    pub fn duplicate_at_cursor(&mut self) {
+        let Some(path) = get_path_at_line(self.line_at_cursor, &self.json).filter(|path| !path.is_empty()) else {
+            self.report(tr!("Nothing to duplicate at the current line"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+        let Some(value) = self.json.pointer(&path_to_pointer(&path)).cloned() else {
+            return;
+        };
+        let Some((segment, parent)) = path.split_last() else {
+            return;
+        };
+
+        let copy_segment = match (get_value_at_path_mut(parent, &mut self.json), segment) {
+            (Some(Value::Object(map)), PathSegment::Key(key)) => {
+                let copy_key = free_key(map, key);
+                let index = map.keys().position(|other| other == key).map_or(map.len(), |index| index + 1);
+                map.shift_insert(index, copy_key.clone(), value.clone());
+                PathSegment::Key(copy_key)
+            }
+            (Some(Value::Array(values)), PathSegment::Index(index)) => {
+                values.insert(*index + 1, value.clone());
+                PathSegment::Index(*index + 1)
+            }
+            _ => return,
+        };
+        let copy_path = [parent, &[copy_segment]].concat();
+        self.mark_dirty();
+        self.refresh_pairs();
+
+        // Objects and arrays aren't edited in the popup, the first value in them is.
+        let Some(line) = first_scalar_path(&value, &copy_path).and_then(|path| get_line_at_path(&path, &self.json)) else {
+            if let Some(line) = get_line_at_path(&copy_path, &self.json) {
+                self.move_cursor_to_line(line);
+            }
+            self.report(tr!("Duplicated {}", path_to_pointer(&path)), ReportedMessageKinds::Success, Duration::from_secs(2));
+            return;
+        };
+
+        self.move_cursor_to_line(line);
+        self.start_editing_existing_value();
+        self.report(tr!("Duplicated {}, editing the copy", path_to_pointer(&path)), ReportedMessageKinds::Info, Duration::from_secs(3));
+    }
+
+    /// Builds the lines of the tree again, for the edits that follow a change before it's drawn.
+    fn refresh_pairs(&mut self) {
+        let mut pairs = vec![];
+        self.lines_count = self.insert_data_to_tree(&mut pairs, &self.json, 0);
+        self.json_pairs = pairs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::{actions::{Action, MainViewActions}, app::CurrentScreen};
+
+    #[test]
+    fn test_duplicate_and_edit() {
+        let mut app = App::default();
+        app.json = json!({ "users": [{ "name": "Ann", "age": 30 }], "port": 80 });
+
+        // The copy of an object in an array is edited from its first value.
+        app.line_at_cursor = 1;
+        app.update(Action::MainView(MainViewActions::Duplicate));
+        assert_eq!(app.line_at_cursor, 5);
+        assert!(matches!(app.current_screen, CurrentScreen::Editing));
+        assert_eq!(app.key_input.content(), "name");
+        app.value_input.set_content("Bob");
+        app.update_existing_data_from_user_input();
+        assert_eq!(app.json["users"], json!([{ "name": "Ann", "age": 30 }, { "name": "Bob", "age": 30 }]));
+
+        // A pair's copy gets a key of its own, right after it.
+        app.line_at_cursor = 7;
+        app.update(Action::MainView(MainViewActions::Duplicate));
+        assert_eq!(app.key_input.content(), "port_copy");
+        assert_eq!(app.value_input.content(), "80");
+        assert_eq!(serde_json::to_string(&app.json).unwrap(), r#"{"users":[{"name":"Ann","age":30},{"name":"Bob","age":30}],"port":80,"port_copy":80}"#);
+    }
+}
@@ -1,16 +1,26 @@
+use std::time::Duration;
+
 use color_eyre::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use crate::{actions::{Action, AppNavigationAction, CursorDirection, EditingAction, MainViewActions, SearchingAction, SystemAction}, app::{CurrentScreen, CurrentlyEditing}};
+use crate::{actions::{Action, AppNavigationAction, CommandAction, ConfirmAction, EditingAction, MainViewActions, PreviewAction, ProblemsAction, SearchingAction, SystemAction, TableAction, TutorialAction}, app::CurrentScreen, keymap::{KeyAction, PREVIEW_ACTIONS, PROBLEMS_ACTIONS, TABLE_ACTIONS, VIEWING_ACTIONS}};
 
 use super::app::App;
 
 
-impl<'a> App<'a> {
+/// How long to wait for an event before giving the main loop a chance to run its periodic
+/// checks (like whether the file still exists) and redraw.
+const EVENT_POLL_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl App {
     pub fn handle_crossterm_events(&mut self) -> Result<()> {
+        if !event::poll(EVENT_POLL_TIMEOUT)? {
+            return Ok(());
+        }
+
         match event::read()? {
             // it's important to check KeyEventKind::Press to avoid handling key release events
             Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key_events(key)?,
-            Event::Mouse(_) => { }
+            Event::Mouse(mouse) => self.handle_mouse_event(mouse),
             Event::Resize(columns, rows) => {
                 self.size.height = rows;
                 self.size.width = columns;
@@ -20,129 +30,324 @@ impl<'a> App<'a> {
 
         return Ok(());
     }
+
+    /// The action of the key in the tree. A named register only holds for the action right after it.
+    fn viewing_key_action(&mut self, key: KeyEvent) -> Option<KeyAction> {
+        let action = self.key_action(key, VIEWING_ACTIONS);
+        if action.is_some_and(|action| !matches!(action, KeyAction::Yank | KeyAction::Paste | KeyAction::Delete)) {
+            self.selected_register = None;
+        }
+
+        return action;
+    }
     
     /// Handles the key events based on the current screen and updates the state.
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<()> {
+        // Typing into an input, the keys of the input bindings stand for the default ones.
+        let is_typing = matches!(self.current_screen, CurrentScreen::Editing | CurrentScreen::Searching | CurrentScreen::Command)
+            || self.table.as_ref().is_some_and(|table| table.is_editing);
+        let key = if is_typing { self.config.input_bindings.default_key(key) } else { key };
+
         match self.current_screen {
-            CurrentScreen::ViewingFile => {
-                match (key.modifiers, key.code) {
-                    (_, KeyCode::Char('q')) | (KeyModifiers::CONTROL, KeyCode::Char('c')) => {
-                        self.update(Action::App(SystemAction::Quit));
-                    }
-                    (_, KeyCode::Char('i')) => {
-                        self.update(Action::AppNavigation(AppNavigationAction::ToEditingScreen));
-                    }
-                    (_, KeyCode::Char('e')) => {
-                        self.update(Action::Editing(EditingAction::EditExisting));
-                    }
-                    (_, KeyCode::Char('j') | KeyCode::Down) | (KeyModifiers::CONTROL, KeyCode::Char('n')) => {
-                        self.update(Action::MainView(MainViewActions::MoveDown));
-                    }
-                    (_, KeyCode::Char('k') | KeyCode::Up) | (KeyModifiers::CONTROL, KeyCode::Char('p')) => {
-                        self.update(Action::MainView(MainViewActions::MoveUp));
-                    }
-                    (_, KeyCode::Char('g')) => {
-                        self.update(Action::MainView(MainViewActions::MoveToTop));
-                    }
-                    (_, KeyCode::Char('G')) => {
-                        self.update(Action::MainView(MainViewActions::MoveToBottom));
-                    }
-                    (KeyModifiers::CONTROL, KeyCode::Char('d')) | (KeyModifiers::META, KeyCode::Char('v')) => {
-                        self.update(Action::MainView(MainViewActions::MoveHalfPageDown));
-                    }
-                    (KeyModifiers::CONTROL, KeyCode::Char('u')) | (KeyModifiers::CONTROL, KeyCode::Char('v')) => {
-                        self.update(Action::MainView(MainViewActions::MoveHalfPageUp));
-                    }
-                    (_, KeyCode::Char('n')) => {
-                        self.update(Action::Searching(SearchingAction::GoToNextMatch));
-                    }
-                    (KeyModifiers::SHIFT, KeyCode::Char('N')) => {
-                        self.update(Action::Searching(SearchingAction::GoToPrevMatch));
-                    }
-                    (_, KeyCode::Char('/')) => {
-                        self.update(Action::Searching(SearchingAction::ClearSearch));
-                        self.update(Action::AppNavigation(AppNavigationAction::ToSearchingWidget));
-                    }
-                    (_, KeyCode::Esc) => {
-                        self.update(Action::Searching(SearchingAction::ClearSearch));
-                    }
-                    _ => { }
+            CurrentScreen::ViewingFile if self.is_choosing_register => {
+                self.is_choosing_register = false;
+                if let KeyCode::Char(register) = key.code {
+                    self.update(Action::MainView(MainViewActions::SelectRegister(register)));
+                }
+            }
+
+            CurrentScreen::ViewingFile => match self.viewing_key_action(key) {
+                Some(KeyAction::Quit) => {
+                    self.update(Action::App(SystemAction::Quit));
+                }
+                Some(KeyAction::Save) => {
+                    self.update(Action::App(SystemAction::Save));
+                }
+                Some(KeyAction::SaveAndQuit) => {
+                    self.update(Action::App(SystemAction::SaveAndQuit));
+                }
+                Some(KeyAction::Insert) => {
+                    self.update(Action::AppNavigation(AppNavigationAction::ToEditingScreen));
+                }
+                Some(KeyAction::Edit) => {
+                    self.update(Action::Editing(EditingAction::EditExisting));
+                }
+                Some(KeyAction::MoveDown) => {
+                    self.update(Action::MainView(MainViewActions::MoveDown));
+                }
+                Some(KeyAction::MoveUp) => {
+                    self.update(Action::MainView(MainViewActions::MoveUp));
+                }
+                Some(KeyAction::MoveToTop) => {
+                    self.update(Action::MainView(MainViewActions::MoveToTop));
+                }
+                Some(KeyAction::MoveToBottom) => {
+                    self.update(Action::MainView(MainViewActions::MoveToBottom));
+                }
+                Some(KeyAction::HalfPageDown) => {
+                    self.update(Action::MainView(MainViewActions::MoveHalfPageDown));
+                }
+                Some(KeyAction::HalfPageUp) => {
+                    self.update(Action::MainView(MainViewActions::MoveHalfPageUp));
+                }
+                Some(KeyAction::NextMatch) => {
+                    self.update(Action::Searching(SearchingAction::GoToNextMatch));
                 }
+                Some(KeyAction::PreviousMatch) => {
+                    self.update(Action::Searching(SearchingAction::GoToPrevMatch));
+                }
+                Some(KeyAction::Search) => {
+                    self.update(Action::Searching(SearchingAction::ClearSearch));
+                    self.update(Action::AppNavigation(AppNavigationAction::ToSearchingWidget));
+                }
+                Some(KeyAction::ClearSearch) if self.visual_anchor.is_some() => {
+                    self.update(Action::MainView(MainViewActions::ToggleVisual));
+                }
+                Some(KeyAction::ClearSearch) => {
+                    self.update(Action::Searching(SearchingAction::ClearSearch));
+                }
+                Some(KeyAction::CommandLine) => {
+                    self.update(Action::AppNavigation(AppNavigationAction::ToCommandWidget));
+                }
+                Some(KeyAction::ToggleTimestamps) => {
+                    self.update(Action::MainView(MainViewActions::ToggleTimestamps));
+                }
+                Some(KeyAction::Yank) => {
+                    self.update(Action::MainView(MainViewActions::Yank));
+                }
+                Some(KeyAction::YankEscaped) => {
+                    self.update(Action::MainView(MainViewActions::YankEscaped));
+                }
+                Some(KeyAction::Register) => {
+                    self.is_choosing_register = true;
+                }
+                Some(KeyAction::Paste) => {
+                    self.update(Action::MainView(MainViewActions::Paste));
+                }
+                Some(KeyAction::Exchange) => {
+                    self.update(Action::MainView(MainViewActions::Exchange));
+                }
+                Some(KeyAction::MoveInto) => {
+                    self.update(Action::MainView(MainViewActions::MoveInto));
+                }
+                Some(KeyAction::Visual) => {
+                    self.update(Action::MainView(MainViewActions::ToggleVisual));
+                }
+                Some(KeyAction::Duplicate) => {
+                    self.update(Action::MainView(MainViewActions::Duplicate));
+                }
+                Some(KeyAction::Delete) => {
+                    self.update(Action::MainView(MainViewActions::Delete { confirmed: false }));
+                }
+                Some(KeyAction::ToggleEnvValues) => {
+                    self.update(Action::MainView(MainViewActions::ToggleEnvValues));
+                }
+                Some(KeyAction::DecodeBase64) => {
+                    self.update(Action::Preview(PreviewAction::DecodeBase64));
+                }
+                Some(KeyAction::DecodeJwt) => {
+                    self.update(Action::Preview(PreviewAction::DecodeJwt));
+                }
+                Some(KeyAction::Inspect) => {
+                    self.update(Action::Preview(PreviewAction::Inspect));
+                }
+                Some(KeyAction::DecodeUrl) => {
+                    self.update(Action::Preview(PreviewAction::DecodeUrl));
+                }
+                Some(KeyAction::Help) => {
+                    self.update(Action::Preview(PreviewAction::Help));
+                }
+                _ => { }
             }
             
             CurrentScreen::Editing => match (key.modifiers, key.code) {
                 (_, KeyCode::Enter) => {
                     self.update(Action::Editing(EditingAction::Submit));
                 }
-                
-                (_, KeyCode::Backspace) => {
-                    if let Some(currently_editing) = &self.currently_editing {
-                        match currently_editing {
-                            CurrentlyEditing::Key => {
-                                if self.key_input.is_focused {
-                                    self.update(Action::Editing(EditingAction::PopFromKey));
-                                }
-                            }
-                            CurrentlyEditing::Value => {
-                                if self.value_input.is_focused {
-                                    self.update(Action::Editing(EditingAction::PopFromValue));
-                                }
-                            }
-                        }
-                    }
-                }
-                
+
                 (_, KeyCode::Esc) => {
                     self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
                 }
                 
+                (_, KeyCode::Tab) if !self.suggestions().is_empty() => {
+                    self.update(Action::Editing(EditingAction::AcceptSuggestion));
+                }
                 (_, KeyCode::Tab) => {
                     self.update(Action::AppNavigation(AppNavigationAction::ToEditingScreen)); // Has the logic of switching between the inputs.
                 }
                 
-                (_, KeyCode::Char(value)) => {
-                    self.update(Action::Editing(EditingAction::AppendChar(value)));
+                (KeyModifiers::CONTROL, KeyCode::Char('p')) => {
+                    self.update(Action::Editing(EditingAction::RecallHistory { older: true }));
                 }
-                
-                (_, KeyCode::Left) => {
-                    self.update(Action::Editing(EditingAction::MoveCursor(CursorDirection::Left)));
+                (KeyModifiers::CONTROL, KeyCode::Char('n')) => {
+                    self.update(Action::Editing(EditingAction::RecallHistory { older: false }));
+                }
+
+                (_, KeyCode::Down) => {
+                    self.update(Action::Editing(EditingAction::SelectNext));
                 }
-                (_, KeyCode::Right) => {
-                    self.update(Action::Editing(EditingAction::MoveCursor(CursorDirection::Right)));
+                // The allowed values are picked with the keys moving in the tree, as there's no text to type.
+                _ if self.is_choosing_value() && self.config.keybindings.binds(KeyAction::MoveDown, key) => {
+                    self.update(Action::Editing(EditingAction::SelectNext));
                 }
+
+                (_, KeyCode::Up) => {
+                    self.update(Action::Editing(EditingAction::SelectPrevious));
+                }
+                _ if self.is_choosing_value() && self.config.keybindings.binds(KeyAction::MoveUp, key) => {
+                    self.update(Action::Editing(EditingAction::SelectPrevious));
+                }
+
                 _ => {
-                    {}
+                    if let Some(edit) = self.config.input_bindings.input_edit_for(key) {
+                        self.update(Action::Editing(EditingAction::from_input_edit(edit)));
+                    }
                 }
             }
             
-            CurrentScreen::Searching => match (key.modifiers, key.code) {
-                (_, KeyCode::Backspace) => {
-                    self.update(Action::Searching(SearchingAction::PopChar));
-                }
-                
-                (_, KeyCode::Esc) => {
+            CurrentScreen::Searching => match key.code {
+                KeyCode::Esc => {
                     self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
                 }
                 
-                (_, KeyCode::Enter) => {
+                KeyCode::Enter => {
                     self.update(Action::Searching(SearchingAction::ReportResults));
                     self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
                 }
                 
-                (_, KeyCode::Left) => {
-                    self.update(Action::Searching(SearchingAction::MoveCursor(CursorDirection::Left)));
+                _ => {
+                    if let Some(action) = self.config.input_bindings.input_edit_for(key).and_then(SearchingAction::from_input_edit) {
+                        self.update(Action::Searching(action));
+                    }
+                }
+            }
+            
+            CurrentScreen::Problems => match self.key_action(key, PROBLEMS_ACTIONS) {
+                Some(KeyAction::Close) => {
+                    self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+                }
+
+                Some(KeyAction::Select) => {
+                    self.update(Action::Problems(ProblemsAction::JumpToSelected));
+                }
+
+                Some(KeyAction::MoveDown) => {
+                    self.update(Action::Problems(ProblemsAction::SelectNext));
+                }
+
+                Some(KeyAction::MoveUp) => {
+                    self.update(Action::Problems(ProblemsAction::SelectPrevious));
+                }
+
+                _ => {
+                    {}
+                }
+            }
+
+            CurrentScreen::Table if self.table.as_ref().is_some_and(|table| table.is_editing) => match key.code {
+                KeyCode::Enter => {
+                    self.update(Action::Table(TableAction::SubmitCell));
+                }
+                KeyCode::Esc => {
+                    self.update(Action::Table(TableAction::CancelEditing));
+                }
+                _ => {
+                    if let Some(edit) = self.config.input_bindings.input_edit_for(key) {
+                        self.update(Action::Table(TableAction::EditInput(edit)));
+                    }
+                }
+            }
+
+            CurrentScreen::Table => match self.key_action(key, TABLE_ACTIONS) {
+                Some(KeyAction::Close) => {
+                    self.update(Action::Table(TableAction::Close));
+                }
+                Some(KeyAction::MoveDown) => {
+                    self.update(Action::Table(TableAction::MoveDown));
+                }
+                Some(KeyAction::MoveUp) => {
+                    self.update(Action::Table(TableAction::MoveUp));
                 }
+                Some(KeyAction::MoveLeft) => {
+                    self.update(Action::Table(TableAction::MoveLeft));
+                }
+                Some(KeyAction::MoveRight) => {
+                    self.update(Action::Table(TableAction::MoveRight));
+                }
+                Some(KeyAction::Select | KeyAction::Edit) => {
+                    self.update(Action::Table(TableAction::EditCell));
+                }
+                Some(KeyAction::ToggleSummaries) => {
+                    self.update(Action::Table(TableAction::ToggleSummaries));
+                }
+                Some(KeyAction::Save) => {
+                    self.update(Action::App(SystemAction::Save));
+                }
+                _ => {}
+            }
+
+            CurrentScreen::Preview => match self.key_action(key, PREVIEW_ACTIONS) {
+                Some(KeyAction::Close) => {
+                    self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+                }
+
+                Some(KeyAction::MoveDown) => {
+                    self.update(Action::Preview(PreviewAction::ScrollDown));
+                }
+
+                Some(KeyAction::MoveUp) => {
+                    self.update(Action::Preview(PreviewAction::ScrollUp));
+                }
+
+                Some(KeyAction::Replace) => {
+                    self.update(Action::Preview(PreviewAction::Replace));
+                }
+
+                _ => {
+                    {}
+                }
+            }
 
-                (_, KeyCode::Right) => {
-                    self.update(Action::Searching(SearchingAction::MoveCursor(CursorDirection::Right)));
+            CurrentScreen::Tutorial => match key.code {
+                KeyCode::Enter | KeyCode::Right | KeyCode::Char('l' | ' ') => {
+                    self.update(Action::Tutorial(TutorialAction::Next));
+                }
+                KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                    self.update(Action::Tutorial(TutorialAction::Previous));
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.update(Action::Tutorial(TutorialAction::Close));
+                }
+                _ => {}
+            }
+
+            CurrentScreen::Confirm => match key.code {
+                KeyCode::Esc => {
+                    self.update(Action::Confirm(ConfirmAction::Cancel));
+                }
+                KeyCode::Char(value) => {
+                    self.update(Action::Confirm(ConfirmAction::Choose(value)));
+                }
+                _ => {}
+            }
+
+            CurrentScreen::Command => match key.code {
+                KeyCode::Esc => {
+                    self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
                 }
                 
-                (_, KeyCode::Char(value)) => {
-                    self.update(Action::Searching(SearchingAction::AppendChar(value)));
+                KeyCode::Enter => {
+                    self.update(Action::Command(CommandAction::Submit));
+                }
+
+                KeyCode::Tab => {
+                    self.update(Action::Command(CommandAction::Complete));
                 }
                 
                 _ => {
-                    {}
+                    if let Some(action) = self.config.input_bindings.input_edit_for(key).and_then(CommandAction::from_input_edit) {
+                        self.update(Action::Command(action));
+                    }
                 }
             }
         };
@@ -0,0 +1,102 @@
+//
+// Swapping two nodes of the same object or array: the first exchange marks the node under the
+// cursor, and the next one, on one of its siblings, swaps them. Pairs keep their keys and trade
+// places, so the new order is the one saved.
+//
+
+use std::time::Duration;
+
+use crate::{app::{App, ReportedMessageKinds}, i18n::tr, utils::{edit::swap_children, json::{get_path_at_line, get_value_at_path_mut, PathSegment}}};
+
+/// How a node is called in the messages: its key, or its index in an array.
+fn segment_name(segment: &PathSegment) -> String {
+    return match segment {
+        PathSegment::Key(key) => key.clone(),
+        PathSegment::Index(index) => tr!("element {}", index),
+    };
+}
+
+impl App {
+    /// Marks the node under the cursor, or swaps it with the marked one.
+    pub fn exchange_at_cursor(&mut self) {
+        let Some(path) = get_path_at_line(self.line_at_cursor, &self.json) else {
+            self.report(tr!("Nothing to swap at the current line"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+        let Some((segment, parent)) = path.split_last() else {
+            return;
+        };
+
+        let Some(marked) = self.exchange_mark.take() else {
+            self.report(
+                tr!("Marked '{}', do it again on a sibling to swap them", segment_name(segment)),
+                ReportedMessageKinds::Info,
+                Duration::from_secs(3),
+            );
+            self.exchange_mark = Some(path);
+            return;
+        };
+
+        if marked == path {
+            self.report(tr!("Unmarked '{}'", segment_name(segment)), ReportedMessageKinds::Info, Duration::from_secs(2));
+            return;
+        }
+
+        let Some((marked_segment, marked_parent)) = marked.split_last() else {
+            return;
+        };
+        if marked_parent != parent {
+            self.report(
+                tr!("'{}' and '{}' aren't in the same object or array, mark a sibling instead", segment_name(marked_segment), segment_name(segment)),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3),
+            );
+            return;
+        }
+
+        let swapped = get_value_at_path_mut(parent, &mut self.json).is_some_and(|container| swap_children(container, marked_segment, segment));
+        if !swapped {
+            return;
+        }
+
+        self.mark_dirty();
+        self.report(
+            tr!("Swapped '{}' and '{}'", segment_name(marked_segment), segment_name(segment)),
+            ReportedMessageKinds::Success,
+            Duration::from_secs(2),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::actions::{Action, MainViewActions};
+
+    #[test]
+    fn test_exchange() {
+        let mut app = App::default();
+        app.json = json!({ "a": 1, "b": { "c": [true, false] }, "d": 2 });
+
+        // Marking `a` then swapping it with `d` keeps their keys and trades their places.
+        app.update(Action::MainView(MainViewActions::Exchange));
+        app.line_at_cursor = 5;
+        app.update(Action::MainView(MainViewActions::Exchange));
+        assert_eq!(serde_json::to_string(&app.json).unwrap(), r#"{"d":2,"b":{"c":[true,false]},"a":1}"#);
+        assert_eq!(app.exchange_mark, None);
+
+        // The elements of an array, but not nodes of different parents.
+        app.line_at_cursor = 3;
+        app.update(Action::MainView(MainViewActions::Exchange));
+        app.line_at_cursor = 4;
+        app.update(Action::MainView(MainViewActions::Exchange));
+        assert_eq!(app.json["b"]["c"], json!([false, true]));
+
+        app.update(Action::MainView(MainViewActions::Exchange));
+        app.line_at_cursor = 0;
+        app.update(Action::MainView(MainViewActions::Exchange));
+        assert_eq!(serde_json::to_string(&app.json).unwrap(), r#"{"d":2,"b":{"c":[false,true]},"a":1}"#);
+    }
+}
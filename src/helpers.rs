@@ -21,4 +21,82 @@ pub fn get_centered_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
             Constraint::Percentage((100 - percent_x) / 2),
         ])
         .split(layout[1])[1]; // Return the middle chunk
-}
\ No newline at end of file
+}
+/// A rect of the given size centered in the available rect, shrunk to fit in it.
+pub fn get_centered_rect_of_size(width: u16, height: u16, rect: Rect) -> Rect {
+    let width = width.min(rect.width);
+    let height = height.min(rect.height);
+
+    return Rect::new(rect.x + (rect.width - width) / 2, rect.y + (rect.height - height) / 2, width, height);
+}
+
+/// The lines of a text around a position, for pointing at an error in it.
+pub struct SourceExcerpt<'a> {
+    /// The lines with their (1-based) numbers.
+    pub lines: Vec<(usize, &'a str)>,
+    pub error_line: usize,
+    /// How many characters into the error line the caret goes.
+    pub caret_offset: usize,
+}
+
+impl<'a> SourceExcerpt<'a> {
+    /// Takes the lines around `line`, `column` (both 1-based, the column in bytes like serde's).
+    pub fn new(text: &'a str, line: usize, column: usize, context_lines: usize) -> SourceExcerpt<'a> {
+        let source_lines: Vec<&str> = text.lines().collect();
+        // Errors at the very end of the text can be on a line past the last one.
+        let error_line = line.clamp(1, source_lines.len().max(1));
+        let first = error_line.saturating_sub(context_lines).max(1);
+        let last = (error_line + context_lines).min(source_lines.len());
+
+        let error_source = source_lines.get(error_line - 1).copied().unwrap_or_default();
+        let mut byte_column = if line == error_line { column.saturating_sub(1) } else { error_source.len() };
+        byte_column = byte_column.min(error_source.len());
+        while !error_source.is_char_boundary(byte_column) {
+            byte_column -= 1;
+        }
+
+        return SourceExcerpt {
+            lines: (first..=last).map(|number| (number, source_lines[number - 1])).collect(),
+            error_line,
+            caret_offset: error_source[..byte_column].chars().count(),
+        };
+    }
+
+    /// The width of the line numbers.
+    pub fn number_width(&self) -> usize {
+        return self.lines.last().map_or(1, |(number, _)| number.to_string().len());
+    }
+}
+
+impl std::fmt::Display for SourceExcerpt<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.number_width();
+
+        for (number, source) in &self.lines {
+            writeln!(f, "{:>width$} | {}", number, source.replace('\t', " "))?;
+            if *number == self.error_line {
+                writeln!(f, "{:>width$} | {}^", "", " ".repeat(self.caret_offset))?;
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_excerpt() {
+        let text = "{\n  \"name\": \"Ømar\"\n  \"age\": 30\n}";
+        let error = serde_json::from_str::<serde_json::Value>(text).unwrap_err();
+
+        let excerpt = SourceExcerpt::new(text, error.line(), error.column(), 1);
+        assert_eq!(excerpt.to_string(), "2 |   \"name\": \"Ømar\"\n3 |   \"age\": 30\n  |   ^\n4 | }\n");
+
+        // At the end of the text.
+        let excerpt = SourceExcerpt::new("{\n", 2, 0, 1);
+        assert_eq!(excerpt.to_string(), "1 | {\n  |  ^\n");
+    }
+}
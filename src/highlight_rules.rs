@@ -0,0 +1,139 @@
+//
+// Conditional formatting: rules from the config file that style the keys and values matching
+// regular expressions, like secrets or TODO markers.
+//
+
+use ratatui::style::{Color, Modifier, Style};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::schema::value_choice_label;
+
+/// A rule styling the keys matching `key` and the values matching `value`. When both are given,
+/// a line has to match both. Values are matched as they're shown, strings without their quotes.
+#[derive(Debug, Clone)]
+pub struct HighlightRule {
+    key: Option<Regex>,
+    value: Option<Regex>,
+    style: Style,
+}
+
+/// A rule as it's written in the config file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawHighlightRule {
+    key: Option<String>,
+    value: Option<String>,
+    /// A color name (`red`, `lightblue`...), an index (`208`) or a hex code (`#ff8800`).
+    color: Option<String>,
+    background: Option<String>,
+    #[serde(default)]
+    bold: bool,
+    #[serde(default)]
+    italic: bool,
+    #[serde(default)]
+    underlined: bool,
+}
+
+impl<'de> Deserialize<'de> for HighlightRule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawHighlightRule::deserialize(deserializer)?;
+        return HighlightRule::try_from(raw).map_err(serde::de::Error::custom);
+    }
+}
+
+impl TryFrom<RawHighlightRule> for HighlightRule {
+    type Error = String;
+
+    fn try_from(raw: RawHighlightRule) -> Result<Self, Self::Error> {
+        if raw.key.is_none() && raw.value.is_none() {
+            return Err("A highlight rule needs a `key` or a `value` pattern".to_string());
+        }
+
+        let regex = |pattern: Option<String>| -> Result<Option<Regex>, String> {
+            return pattern
+                .map(|pattern| Regex::new(&pattern).map_err(|err| format!("Invalid pattern `{}`: {}", pattern, err)))
+                .transpose();
+        };
+        let color = |name: Option<String>| -> Result<Option<Color>, String> {
+            return name
+                .map(|name| name.parse::<Color>().map_err(|_| format!("Unknown color `{}`", name)))
+                .transpose();
+        };
+
+        let mut style = Style::default();
+        if let Some(color) = color(raw.color)? {
+            style = style.fg(color);
+        }
+        if let Some(background) = color(raw.background)? {
+            style = style.bg(background);
+        }
+        for (is_set, modifier) in [(raw.bold, Modifier::BOLD), (raw.italic, Modifier::ITALIC), (raw.underlined, Modifier::UNDERLINED)] {
+            if is_set {
+                style = style.add_modifier(modifier);
+            }
+        }
+
+        return Ok(HighlightRule { key: regex(raw.key)?, value: regex(raw.value)?, style });
+    }
+}
+
+impl HighlightRule {
+    fn matches(&self, key: &str, value: Option<&Value>) -> bool {
+        let key_matches = self.key.as_ref().is_none_or(|pattern| pattern.is_match(key));
+        let value_matches = match (&self.value, value) {
+            (Some(pattern), Some(value)) => pattern.is_match(&value_choice_label(value)),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        return key_matches && value_matches;
+    }
+}
+
+/// The styles the rules give to the key and to the value of a line, if any. Later rules are
+/// applied over earlier ones.
+pub fn highlight_styles(rules: &[HighlightRule], key: &str, value: Option<&Value>) -> (Option<Style>, Option<Style>) {
+    let mut key_style: Option<Style> = None;
+    let mut value_style: Option<Style> = None;
+
+    for rule in rules.iter().filter(|rule| rule.matches(key, value)) {
+        if rule.key.is_some() {
+            key_style = Some(key_style.unwrap_or_default().patch(rule.style));
+        }
+        if rule.value.is_some() {
+            value_style = Some(value_style.unwrap_or_default().patch(rule.style));
+        }
+    }
+
+    return (key_style, value_style);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_highlight_styles() {
+        let rules: Vec<HighlightRule> = serde_json::from_value(json!([
+            { "key": "(?i)^password$", "bold": true },
+            { "value": "TODO", "color": "red" },
+            { "key": "^token$", "value": "^ey", "background": "#ff8800" }
+        ])).unwrap();
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let red = Style::default().fg(Color::Red);
+        let orange = Style::default().bg(Color::Rgb(255, 136, 0));
+
+        assert_eq!(highlight_styles(&rules, "Password", Some(&json!("TODO: rotate"))), (Some(bold), Some(red)));
+        assert_eq!(highlight_styles(&rules, "password", None), (Some(bold), None));
+        assert_eq!(highlight_styles(&rules, "token", Some(&json!("eyJhbGciOi"))), (Some(orange), Some(orange)));
+        assert_eq!(highlight_styles(&rules, "token", Some(&json!(42))), (None, None));
+
+        assert!(serde_json::from_value::<Vec<HighlightRule>>(json!([{ "color": "red" }])).is_err());
+        assert!(serde_json::from_value::<Vec<HighlightRule>>(json!([{ "key": "(", "color": "red" }])).is_err());
+        assert!(serde_json::from_value::<Vec<HighlightRule>>(json!([{ "key": "a", "color": "reddish" }])).is_err());
+    }
+}
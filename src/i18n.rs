@@ -0,0 +1,171 @@
+//
+// The user-facing strings in the language picked with the `locale` option of the config. The
+// English text is the key of its translations in `locales/<locale>.json`, so a string a
+// translation doesn't have yet is shown in English.
+//
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use serde::Deserialize;
+
+/// The languages todd is translated to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+}
+
+/// The locale of the session, set once the config is read.
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+impl Locale {
+    /// The translations of the English strings. `None` for English itself.
+    fn catalog(self) -> Option<&'static HashMap<String, String>> {
+        static ES: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static FR: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+        let (catalog, source) = match self {
+            Locale::En => return None,
+            Locale::Es => (&ES, include_str!("../locales/es.json")),
+            Locale::Fr => (&FR, include_str!("../locales/fr.json")),
+        };
+
+        return Some(catalog.get_or_init(|| serde_json::from_str(source).unwrap_or_default()));
+    }
+
+    /// The text in this locale, or as is without a translation.
+    pub fn translate(self, text: &'static str) -> &'static str {
+        return self.catalog().and_then(|catalog| catalog.get(text)).map_or(text, String::as_str);
+    }
+}
+
+/// Picks the locale of the session. Only the first call counts.
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// The text in the locale of the session.
+pub fn translate(text: &'static str) -> &'static str {
+    return LOCALE.get().copied().unwrap_or_default().translate(text);
+}
+
+/// Puts the arguments in the placeholders of the text: `{}` takes the next argument, and `{0}`,
+/// `{1}`... the one at that position, for the translations that order them differently.
+pub fn fill(text: &str, args: &[String]) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut next_arg = 0;
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let placeholder = &rest[1..end];
+        let arg = if placeholder.is_empty() {
+            next_arg += 1;
+            args.get(next_arg - 1)
+        } else {
+            placeholder.parse::<usize>().ok().and_then(|index| args.get(index))
+        };
+
+        match arg {
+            Some(arg) => {
+                filled.push_str(arg);
+                rest = &rest[end + 1..];
+            }
+            // Not a placeholder, like the braces of `{}` shown to the user.
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+
+    return filled;
+}
+
+/// The string in the locale of the session, with the arguments put in its placeholders:
+/// `tr!("Saved to {}", path)`.
+macro_rules! tr {
+    ($text:literal) => {
+        $crate::i18n::translate($text).to_string()
+    };
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::translate($text), &[$($arg.to_string()),+])
+    };
+}
+pub(crate) use tr;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::{fs, path::Path};
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(fill("Saved to {}", &["a.json".to_string()]), "Saved to a.json");
+        assert_eq!(fill("{1} de {0}", &["3".to_string(), "1".to_string()]), "1 de 3");
+        assert_eq!(fill("{0} and no{0}", &["wrap".to_string()]), "wrap and nowrap");
+        assert_eq!(fill("Empty objects are {}", &[]), "Empty objects are {}");
+
+        assert_eq!(Locale::Es.translate("Saved"), "Guardado");
+        assert_eq!(Locale::En.translate("Saved"), "Saved");
+        assert_eq!(Locale::Fr.translate("Not translated at all"), "Not translated at all");
+    }
+
+    /// Every string of the code is translated, and the translations keep its placeholders.
+    #[test]
+    fn test_catalogs_are_complete() {
+        let call = Regex::new(r#"\b(?:tr!|translate)\(\s*"((?:[^"\\]|\\.)*)""#).unwrap();
+        // The placeholders filled in order or by position, and the named segments of the status line.
+        let placeholder = Regex::new(r"\{(\w*)\}").unwrap();
+        let placeholders = |text: &str| {
+            let mut named: Vec<String> = placeholder.captures_iter(text).map(|captures| captures[1].to_string()).collect();
+            let filled_count = named.iter().filter(|name| name.chars().all(|char| char.is_ascii_digit())).count();
+            named.retain(|name| !name.chars().all(|char| char.is_ascii_digit()));
+            named.sort();
+            return (filled_count, named);
+        };
+
+        let mut texts = vec![];
+        let mut dirs = vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("src")];
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|extension| extension == "rs") && !path.ends_with("i18n.rs") {
+                    let source = fs::read_to_string(&path).unwrap();
+                    for captures in call.captures_iter(&source) {
+                        texts.push(serde_json::from_str::<String>(&format!("\"{}\"", &captures[1])).unwrap());
+                    }
+                }
+            }
+        }
+
+        for locale in [Locale::Es, Locale::Fr] {
+            let catalog = locale.catalog().unwrap();
+            for text in &texts {
+                let translation = catalog.get(text).unwrap_or_else(|| panic!("{:?} has no {:?} translation", text, locale));
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(translation),
+                    "{:?} and its {:?} translation have different placeholders",
+                    text,
+                    locale,
+                );
+            }
+            for text in catalog.keys() {
+                assert!(texts.contains(text), "{:?} is translated to {:?} but no longer used", text, locale);
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//
+// The keys for editing text in the inputs. On top of the default ones, the emacs profile adds the
+// bindings of readline, for people used to them from their shell.
+//
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::actions::{CursorDirection, InputEdit};
+
+/// Which keys edit the text of the inputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputBindings {
+    #[default]
+    Default,
+    /// `<C-b>`/`<C-f>` and `<M-b>`/`<M-f>` move by characters and words, `<C-d>` deletes forward
+    /// and `<C-t>` swaps the characters around the cursor.
+    Emacs,
+}
+
+impl InputBindings {
+    /// The default key doing what the key does in these bindings, like `<Left>` for `<C-b>` in
+    /// emacs. Keys without one are given back as they are.
+    pub fn default_key(self, key: KeyEvent) -> KeyEvent {
+        if self == InputBindings::Default {
+            return key;
+        }
+
+        let (modifiers, code) = match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('b')) => (KeyModifiers::NONE, KeyCode::Left),
+            (KeyModifiers::CONTROL, KeyCode::Char('f')) => (KeyModifiers::NONE, KeyCode::Right),
+            (KeyModifiers::ALT, KeyCode::Char('b')) => (KeyModifiers::CONTROL, KeyCode::Left),
+            (KeyModifiers::ALT, KeyCode::Char('f')) => (KeyModifiers::CONTROL, KeyCode::Right),
+            (KeyModifiers::CONTROL, KeyCode::Char('d')) => (KeyModifiers::NONE, KeyCode::Delete),
+            _ => return key,
+        };
+
+        return KeyEvent::new(code, modifiers);
+    }
+
+    /// Whether `<C-t>` swaps the characters around the cursor.
+    pub fn transposes(self) -> bool {
+        return self == InputBindings::Emacs;
+    }
+
+    /// The edit a key makes to the text of an input, once translated with `default_key`. The keys
+    /// an input of a screen gives another meaning, like `<Enter>`, are matched before.
+    pub fn input_edit_for(self, key: KeyEvent) -> Option<InputEdit> {
+        // Moving the cursor with shift held selects the text it moves over.
+        let selecting = key.modifiers.contains(KeyModifiers::SHIFT);
+        let move_cursor = |direction| Some(InputEdit::MoveCursor { direction, selecting });
+
+        return match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('w')) | (KeyModifiers::ALT, KeyCode::Backspace) => Some(InputEdit::PopWord),
+            (_, KeyCode::Backspace) => Some(InputEdit::PopChar),
+            (_, KeyCode::Delete) => Some(InputEdit::DeleteForward),
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => Some(InputEdit::DeleteTo(CursorDirection::Start)),
+            (KeyModifiers::CONTROL, KeyCode::Char('k')) => Some(InputEdit::DeleteTo(CursorDirection::End)),
+            (KeyModifiers::CONTROL, KeyCode::Char('t')) if self.transposes() => Some(InputEdit::TransposeChars),
+            // <C-_> comes as <C-7> from most terminals.
+            (KeyModifiers::CONTROL, KeyCode::Char('z' | '_' | '7')) => Some(InputEdit::Undo),
+            (KeyModifiers::CONTROL, KeyCode::Char('l')) => Some(InputEdit::Clear),
+            (KeyModifiers::CONTROL, KeyCode::Char('r')) => Some(InputEdit::ToggleHidden),
+            (KeyModifiers::CONTROL, KeyCode::Char('c')) => Some(InputEdit::CopySelection { cut: false }),
+            (KeyModifiers::CONTROL, KeyCode::Char('x')) => Some(InputEdit::CopySelection { cut: true }),
+            (KeyModifiers::CONTROL, KeyCode::Char('a')) | (_, KeyCode::Home) => move_cursor(CursorDirection::Start),
+            (KeyModifiers::CONTROL, KeyCode::Char('e')) | (_, KeyCode::End) => move_cursor(CursorDirection::End),
+            (modifiers, KeyCode::Left) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => move_cursor(CursorDirection::WordLeft),
+            (modifiers, KeyCode::Right) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => move_cursor(CursorDirection::WordRight),
+            (_, KeyCode::Left) => move_cursor(CursorDirection::Left),
+            (_, KeyCode::Right) => move_cursor(CursorDirection::Right),
+            (_, KeyCode::Char(c)) => Some(InputEdit::AppendChar(c)),
+            _ => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_emacs_keys() {
+        let key = |modifiers, code| KeyEvent::new(code, modifiers);
+
+        assert_eq!(InputBindings::Emacs.default_key(key(KeyModifiers::CONTROL, KeyCode::Char('b'))), key(KeyModifiers::NONE, KeyCode::Left));
+        assert_eq!(InputBindings::Emacs.default_key(key(KeyModifiers::ALT, KeyCode::Char('f'))), key(KeyModifiers::CONTROL, KeyCode::Right));
+        assert_eq!(InputBindings::Emacs.default_key(key(KeyModifiers::NONE, KeyCode::Char('b'))), key(KeyModifiers::NONE, KeyCode::Char('b')));
+        assert_eq!(InputBindings::Default.default_key(key(KeyModifiers::CONTROL, KeyCode::Char('d'))), key(KeyModifiers::CONTROL, KeyCode::Char('d')));
+
+        assert_eq!(InputBindings::Emacs.input_edit_for(key(KeyModifiers::CONTROL, KeyCode::Char('t'))), Some(InputEdit::TransposeChars));
+        assert_eq!(InputBindings::Default.input_edit_for(key(KeyModifiers::CONTROL, KeyCode::Char('t'))), Some(InputEdit::AppendChar('t')));
+        assert_eq!(
+            InputBindings::Default.input_edit_for(key(KeyModifiers::CONTROL | KeyModifiers::SHIFT, KeyCode::Left)),
+            Some(InputEdit::MoveCursor { direction: CursorDirection::WordLeft, selecting: true }),
+        );
+
+        assert_eq!(serde_json::from_value::<InputBindings>(json!("emacs")).unwrap(), InputBindings::Emacs);
+        assert!(serde_json::from_value::<InputBindings>(json!("vi")).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//
+// The keys and values entered in the edit popup during the session, recalled with the arrows
+// like in a shell, to add many similar entries without typing them again.
+//
+
+use crate::app::{App, CurrentlyEditing};
+
+/// How many entries an input remembers.
+const MAX_ENTRIES: usize = 100;
+
+/// The texts entered in an input, the most recent last, and the one recalled into it.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// The index of the recalled entry, while going through them.
+    recalled: Option<usize>,
+    /// What was typed before recalling, given back past the most recent entry.
+    draft: String,
+}
+
+impl InputHistory {
+    /// Remembers the text as the most recent entry, moving it there if it was entered before.
+    pub fn push(&mut self, text: &str) {
+        self.reset();
+        if text.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|entry| entry != text);
+        self.entries.push(text.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Stops going through the entries, for the next time the popup opens.
+    pub fn reset(&mut self) {
+        self.recalled = None;
+        self.draft.clear();
+    }
+
+    /// The entry before the recalled one when `older`, or after it, with the input's text kept to
+    /// come back to. `None` when there's no entry that way.
+    pub fn recall(&mut self, older: bool, typed: &str) -> Option<String> {
+        let index = match (self.recalled, older) {
+            (None, true) if !self.entries.is_empty() => {
+                self.draft = typed.to_string();
+                self.entries.len() - 1
+            }
+            (Some(index), true) if index > 0 => index - 1,
+            (Some(index), false) if index + 1 < self.entries.len() => index + 1,
+            (Some(_), false) => {
+                self.recalled = None;
+                return Some(std::mem::take(&mut self.draft));
+            }
+            _ => return None,
+        };
+
+        self.recalled = Some(index);
+
+        return Some(self.entries[index].clone());
+    }
+}
+
+impl App {
+    /// Fills the focused input with the entry entered before (`older`) or after the one it holds.
+    pub fn recall_input_history(&mut self, older: bool) {
+        let is_choosing_value = self.is_choosing_value();
+        let (input, history) = match self.currently_editing {
+            Some(CurrentlyEditing::Key) => (&mut self.key_input, &mut self.key_history),
+            // Values restricted by the schema are picked, not typed.
+            Some(CurrentlyEditing::Value) if !is_choosing_value => (&mut self.value_input, &mut self.value_history),
+            _ => return,
+        };
+        if !input.is_focused {
+            return;
+        }
+
+        if let Some(text) = history.recall(older, input.content()) {
+            input.replace_content(text);
+            self.selected_suggestion = 0;
+        }
+    }
+
+    /// Remembers the key and the value of the popup once they were written to the document.
+    pub fn remember_inputs(&mut self) {
+        self.key_history.push(self.key_input.content());
+        self.value_history.push(self.value_input.content());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recall() {
+        let mut history = InputHistory::default();
+        assert_eq!(history.recall(true, "typed"), None);
+
+        history.push("first");
+        history.push("second");
+        history.push("first");
+        history.push("");
+
+        assert_eq!(history.recall(true, "typed").as_deref(), Some("first"));
+        assert_eq!(history.recall(true, "first").as_deref(), Some("second"));
+        assert_eq!(history.recall(true, "second"), None);
+
+        assert_eq!(history.recall(false, "second").as_deref(), Some("first"));
+        assert_eq!(history.recall(false, "first").as_deref(), Some("typed"));
+        assert_eq!(history.recall(false, "typed"), None);
+    }
+}
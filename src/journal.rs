@@ -0,0 +1,64 @@
+//
+// Crash recovery journal.
+//
+// While there are unsaved changes, a shadow copy of the whole document is kept in a hidden
+// sidecar file next to the opened file (`.name.json.todd-swp`). It is removed once the changes
+// are saved, so finding one on startup means a previous session didn't get to save.
+//
+
+use std::{fs, io, path::{Path, PathBuf}};
+
+use serde_json::Value;
+
+/// Gives back the path of the journal file for the given file.
+pub fn journal_path(file_path: &Path) -> PathBuf {
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    return file_path.with_file_name(format!(".{}.todd-swp", file_name));
+}
+
+/// Writes the document to the journal of the given file, replacing the previous one.
+pub fn write_journal(file_path: &Path, json: &Value) -> io::Result<()> {
+    let content = serde_json::to_string(json)?;
+
+    return fs::write(journal_path(file_path), content);
+}
+
+/// Reads back the document from the journal of the given file if there is a readable one.
+pub fn read_journal(file_path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(journal_path(file_path)).ok()?;
+
+    return serde_json::from_str(&content).ok();
+}
+
+/// Removes the journal of the given file if it exists.
+pub fn remove_journal(file_path: &Path) {
+    let _ = fs::remove_file(journal_path(file_path));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_journal_round_trip() {
+        let file_path = std::env::temp_dir().join(format!("todd_journal_test_{}.json", std::process::id()));
+
+        assert_eq!(
+            journal_path(&file_path).file_name().unwrap().to_string_lossy(),
+            format!(".{}.todd-swp", file_path.file_name().unwrap().to_string_lossy()),
+        );
+        assert!(read_journal(&file_path).is_none());
+
+        let json = json!({ "name": "Omar", "tags": [1, 2] });
+        write_journal(&file_path, &json).unwrap();
+        assert_eq!(read_journal(&file_path), Some(json));
+
+        remove_journal(&file_path);
+        assert!(read_journal(&file_path).is_none());
+    }
+}
@@ -0,0 +1,568 @@
+//
+// The keys bound to the actions of the screens that aren't typing into an input. The defaults
+// can be remapped from the `keybindings` of the config file, action name → key sequence(s).
+//
+
+use std::{collections::HashMap, fmt};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{app::App, i18n::{tr, translate}};
+
+/// What a key can be bound to. The same name does the analogous thing on every screen handling
+/// it: `move_down` moves the cursor in the tree, selects the next problem, scrolls a popup...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    Quit,
+    Save,
+    SaveAndQuit,
+    Insert,
+    Edit,
+    /// Jumps to the selected problem, or edits the selected cell of the table.
+    Select,
+    Close,
+    MoveDown,
+    MoveUp,
+    MoveLeft,
+    MoveRight,
+    MoveToTop,
+    MoveToBottom,
+    HalfPageDown,
+    HalfPageUp,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    ClearSearch,
+    CommandLine,
+    ToggleTimestamps,
+    ToggleEnvValues,
+    Yank,
+    YankEscaped,
+    /// Names the register of the next yank, delete or paste with the key after it.
+    Register,
+    Paste,
+    /// Marks the node under the cursor, then swaps it with the sibling it's pressed on next.
+    Exchange,
+    /// Marks the node under the cursor, then moves it into the object or array it's pressed on next.
+    MoveInto,
+    /// Selects a range of the elements of an array, for the yank, delete and move after it.
+    Visual,
+    /// Copies the node under the cursor after it and opens the edit popup on the copy.
+    Duplicate,
+    Delete,
+    DecodeBase64,
+    DecodeJwt,
+    DecodeUrl,
+    Inspect,
+    Replace,
+    ToggleSummaries,
+    Help,
+}
+
+// The actions each screen binds keys to. When a key is bound to several, the first one wins.
+pub const VIEWING_ACTIONS: &[KeyAction] = &[
+    KeyAction::Quit, KeyAction::Save, KeyAction::SaveAndQuit, KeyAction::Insert, KeyAction::Edit, KeyAction::MoveDown, KeyAction::MoveUp,
+    KeyAction::MoveToTop, KeyAction::MoveToBottom, KeyAction::HalfPageDown, KeyAction::HalfPageUp, KeyAction::Search,
+    KeyAction::NextMatch, KeyAction::PreviousMatch, KeyAction::ClearSearch, KeyAction::CommandLine,
+    KeyAction::ToggleTimestamps, KeyAction::ToggleEnvValues, KeyAction::Yank, KeyAction::YankEscaped,
+    KeyAction::Register, KeyAction::Paste, KeyAction::Exchange, KeyAction::MoveInto,
+    KeyAction::Visual, KeyAction::Duplicate, KeyAction::Delete, KeyAction::DecodeBase64, KeyAction::DecodeJwt, KeyAction::DecodeUrl, KeyAction::Inspect, KeyAction::Help,
+];
+pub const PROBLEMS_ACTIONS: &[KeyAction] = &[KeyAction::Close, KeyAction::Select, KeyAction::MoveDown, KeyAction::MoveUp];
+pub const TABLE_ACTIONS: &[KeyAction] = &[
+    KeyAction::Close, KeyAction::MoveDown, KeyAction::MoveUp, KeyAction::MoveLeft, KeyAction::MoveRight,
+    KeyAction::Select, KeyAction::Edit, KeyAction::ToggleSummaries, KeyAction::Save,
+];
+pub const PREVIEW_ACTIONS: &[KeyAction] = &[KeyAction::Close, KeyAction::MoveDown, KeyAction::MoveUp, KeyAction::Replace];
+const SCREEN_ACTIONS: [&[KeyAction]; 4] = [VIEWING_ACTIONS, PROBLEMS_ACTIONS, TABLE_ACTIONS, PREVIEW_ACTIONS];
+
+impl KeyAction {
+    /// What the action does, for the help popup.
+    pub fn description(&self) -> &'static str {
+        return match self {
+            KeyAction::Quit => translate("Quit"),
+            KeyAction::Save => translate("Save the changes"),
+            KeyAction::SaveAndQuit => translate("Save the changes and quit"),
+            KeyAction::Insert => translate("Insert a new pair, or a value in an array"),
+            KeyAction::Edit => translate("Edit the pair or cell under the cursor"),
+            KeyAction::Select => translate("Jump to the problem, or edit the cell"),
+            KeyAction::Close => translate("Close"),
+            KeyAction::MoveDown => translate("Move down"),
+            KeyAction::MoveUp => translate("Move up"),
+            KeyAction::MoveLeft => translate("Move left"),
+            KeyAction::MoveRight => translate("Move right"),
+            KeyAction::MoveToTop => translate("Go to the top"),
+            KeyAction::MoveToBottom => translate("Go to the bottom"),
+            KeyAction::HalfPageDown => translate("Scroll half a page down"),
+            KeyAction::HalfPageUp => translate("Scroll half a page up"),
+            KeyAction::Search => translate("Search"),
+            KeyAction::NextMatch => translate("Go to the next match"),
+            KeyAction::PreviousMatch => translate("Go to the previous match"),
+            KeyAction::ClearSearch => translate("Clear the search"),
+            KeyAction::CommandLine => translate("Open the command line"),
+            KeyAction::ToggleTimestamps => translate("Show or hide the dates of timestamps"),
+            KeyAction::ToggleEnvValues => translate("Show or hide the values of environment placeholders"),
+            KeyAction::Yank => translate("Copy the value"),
+            KeyAction::YankEscaped => translate("Copy the value as an escaped string"),
+            KeyAction::Register => translate("Yank, delete or paste with the register named next, a to z"),
+            KeyAction::Paste => translate("Paste the yanked or deleted node after the cursor"),
+            KeyAction::Exchange => translate("Mark the node, then swap it with a sibling"),
+            KeyAction::MoveInto => translate("Mark the node, then move it into an object or array"),
+            KeyAction::Visual => translate("Select elements of an array to yank, delete or move them at once"),
+            KeyAction::Duplicate => translate("Duplicate the node and edit the copy"),
+            KeyAction::Delete => translate("Delete the node under the cursor"),
+            KeyAction::DecodeBase64 => translate("Decode the base64 string"),
+            KeyAction::DecodeJwt => translate("Decode the JSON Web Token"),
+            KeyAction::DecodeUrl => translate("Percent-decode the URL"),
+            KeyAction::Inspect => translate("Show details about the node"),
+            KeyAction::Replace => translate("Replace the value with the decoded text"),
+            KeyAction::ToggleSummaries => translate("Show or hide the column summaries"),
+            KeyAction::Help => translate("Show this help"),
+        };
+    }
+}
+
+/// A key with the modifiers held with it, written like `j`, `G`, `<C-d>`, `<M-v>` or `<Down>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub modifiers: KeyModifiers,
+    pub code: KeyCode,
+}
+
+impl KeyChord {
+    /// The chord of a key press. Shift is part of the character it types, so it's dropped for them.
+    pub fn from_event(event: KeyEvent) -> KeyChord {
+        let mut modifiers = event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(event.code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+
+        return KeyChord { modifiers, code: event.code };
+    }
+
+    pub fn parse(text: &str) -> Result<KeyChord, String> {
+        let invalid = || format!("Invalid key `{}`", text);
+
+        let mut chars = text.chars();
+        if let (Some(char), None) = (chars.next(), chars.next()) {
+            return Ok(KeyChord { modifiers: KeyModifiers::NONE, code: KeyCode::Char(char) });
+        }
+
+        let inner = text.strip_prefix('<').and_then(|text| text.strip_suffix('>')).ok_or_else(invalid)?;
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = inner;
+        while let Some((modifier, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty()) {
+            modifiers |= match modifier {
+                "C" | "c" => KeyModifiers::CONTROL,
+                "M" | "m" | "A" | "a" => KeyModifiers::ALT,
+                "S" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+            key = rest;
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(char), None) => KeyCode::Char(char),
+            _ => match key.to_lowercase().as_str() {
+                "down" => KeyCode::Down,
+                "up" => KeyCode::Up,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "enter" | "cr" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "bs" | "backspace" => KeyCode::Backspace,
+                "del" | "delete" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "lt" => KeyCode::Char('<'),
+                name => match name.strip_prefix('f').and_then(|number| number.parse::<u8>().ok()) {
+                    Some(number @ 1..=12) => KeyCode::F(number),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+
+        // Shift is typed as the uppercase character, like key presses are.
+        let code = match code {
+            KeyCode::Char(char) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(char.to_ascii_uppercase())
+            }
+            code => code,
+        };
+
+        return Ok(KeyChord { modifiers, code });
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char('<') => "lt".to_string(),
+            KeyCode::Char(char) if self.modifiers.is_empty() => return write!(f, "{}", char),
+            KeyCode::Char(char) => char.to_string(),
+            KeyCode::F(number) => format!("F{}", number),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Backspace => "BS".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            code => format!("{:?}", code),
+        };
+
+        let mut prefix = String::new();
+        for (modifier, letter) in [(KeyModifiers::CONTROL, "C-"), (KeyModifiers::ALT, "M-"), (KeyModifiers::SHIFT, "S-")] {
+            if self.modifiers.contains(modifier) {
+                prefix.push_str(letter);
+            }
+        }
+
+        return write!(f, "<{}{}>", prefix, key);
+    }
+}
+
+/// Chords pressed one after the other, written one after the other like `ZZ` or `<C-w>j`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence(pub Vec<KeyChord>);
+
+impl KeySequence {
+    pub fn parse(text: &str) -> Result<KeySequence, String> {
+        let mut chords = vec![];
+        let mut rest = text;
+        while let Some(first) = rest.chars().next() {
+            // A `<` starts a named chord when a `>` closes it, and is the `<` key otherwise.
+            let length = match rest.get(2..).and_then(|after| after.find('>')) {
+                Some(end) if first == '<' => end + 3,
+                _ => first.len_utf8(),
+            };
+            chords.push(KeyChord::parse(&rest[..length]).map_err(|_| format!("Invalid key `{}`", text))?);
+            rest = &rest[length..];
+        }
+
+        if chords.is_empty() {
+            return Err("Empty key".to_string());
+        }
+
+        return Ok(KeySequence(chords));
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chord in &self.0 {
+            write!(f, "{}", chord)?;
+        }
+
+        return Ok(());
+    }
+}
+
+/// What the keys pressed so far do.
+#[derive(Debug, PartialEq)]
+pub enum KeyMatch {
+    Action(KeyAction),
+    /// They start a longer sequence, so the next key is waited for.
+    Pending,
+    None,
+}
+
+/// The keys bound to each action.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: IndexMap<KeyAction, Vec<KeySequence>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use KeyAction::*;
+
+        let defaults: [(KeyAction, &[&str]); 38] = [
+            (Quit, &["q", "<C-c>"]),
+            (Save, &["<C-s>"]),
+            (SaveAndQuit, &["ZZ"]),
+            (Insert, &["i"]),
+            (Edit, &["e"]),
+            (Select, &["<Enter>"]),
+            (Close, &["<Esc>", "q"]),
+            (MoveDown, &["j", "<Down>", "<C-n>"]),
+            (MoveUp, &["k", "<Up>", "<C-p>"]),
+            (MoveLeft, &["h", "<Left>"]),
+            (MoveRight, &["l", "<Right>"]),
+            (MoveToTop, &["g"]),
+            (MoveToBottom, &["G"]),
+            (HalfPageDown, &["<C-d>", "<M-v>"]),
+            (HalfPageUp, &["<C-u>", "<C-v>"]),
+            (Search, &["/"]),
+            (NextMatch, &["n"]),
+            (PreviousMatch, &["N"]),
+            (ClearSearch, &["<Esc>"]),
+            (CommandLine, &[":"]),
+            (ToggleTimestamps, &["t"]),
+            (ToggleEnvValues, &["$"]),
+            (Yank, &["y"]),
+            (YankEscaped, &["Y"]),
+            (Register, &["\""]),
+            (Paste, &["p"]),
+            (Exchange, &["x"]),
+            (MoveInto, &["m"]),
+            (Visual, &["V"]),
+            (Duplicate, &["c"]),
+            (Delete, &["dd"]),
+            (DecodeBase64, &["b"]),
+            (DecodeJwt, &["J"]),
+            (DecodeUrl, &["%"]),
+            (Inspect, &["K"]),
+            (Replace, &["r"]),
+            (ToggleSummaries, &["s"]),
+            (Help, &["?"]),
+        ];
+
+        let bindings = defaults
+            .into_iter()
+            .map(|(action, keys)| (action, keys.iter().map(|key| KeySequence::parse(key).unwrap()).collect()))
+            .collect();
+
+        return Keymap { bindings };
+    }
+}
+
+impl Keymap {
+    /// What the keys pressed so far do on a screen: the first of its actions bound to them. A sequence bound to an action wins over the
+    /// longer ones it starts, so those are only waited for when it isn't bound.
+    pub fn match_keys(&self, keys: &[KeyChord], screen_actions: &[KeyAction]) -> KeyMatch {
+        let bound = |action: &KeyAction| self.keys_of(*action).iter().any(|sequence| sequence.0 == keys);
+        if let Some(action) = screen_actions.iter().find(|action| bound(action)) {
+            return KeyMatch::Action(*action);
+        }
+
+        let started = |action: &KeyAction| self.keys_of(*action).iter().any(|sequence| sequence.0.starts_with(keys));
+        if screen_actions.iter().any(started) {
+            return KeyMatch::Pending;
+        }
+
+        return KeyMatch::None;
+    }
+
+    /// Whether the key press is bound to the action on its own, for the screens that take the
+    /// other keys as text.
+    pub fn binds(&self, action: KeyAction, event: KeyEvent) -> bool {
+        let chord = KeyChord::from_event(event);
+        return self.keys_of(action).iter().any(|sequence| sequence.0 == [chord]);
+    }
+
+    pub fn keys_of(&self, action: KeyAction) -> &[KeySequence] {
+        return self.bindings.get(&action).map(Vec::as_slice).unwrap_or_default();
+    }
+
+    /// The keys of each screen, one action per line, for the help popup.
+    pub fn help_lines(&self) -> Vec<String> {
+        let sections: [(&str, &[KeyAction]); 4] = [
+            (translate("Tree"), VIEWING_ACTIONS),
+            (translate("Problems panel"), PROBLEMS_ACTIONS),
+            (translate("Table"), TABLE_ACTIONS),
+            (translate("Popups"), PREVIEW_ACTIONS),
+        ];
+
+        let mut lines = vec![];
+        for (title, actions) in sections {
+            lines.push(title.to_string());
+            for action in actions {
+                let keys: Vec<String> = self.keys_of(*action).iter().map(KeySequence::to_string).collect();
+                let keys = if keys.is_empty() { tr!("(unbound)") } else { keys.join(", ") };
+                lines.push(format!("  {:<18} {}", keys, action.description()));
+            }
+            lines.push(String::new());
+        }
+
+        lines.extend([
+            tr!("Inputs"),
+            format!("  {:<18} {}", "<Enter>", translate("Submit")),
+            format!("  {:<18} {}", "<Esc>", translate("Cancel")),
+            format!("  {:<18} {}", "<Tab>", translate("Switch between the key and the value, accept the suggestion, or complete the command")),
+            format!("  {:<18} {}", "<Up>, <Down>", translate("Select a suggestion or an allowed value")),
+            format!("  {:<18} {}", "<C-p>, <C-n>", translate("Recall the keys and values entered before")),
+            format!("  {:<18} {}", "<C-Left>, <C-Right>", translate("Move the cursor by words")),
+            format!("  {:<18} {}", "<C-w>, <M-BS>", translate("Delete the word before the cursor")),
+            format!("  {:<18} {}", "<Home>, <End>", translate("Move the cursor to the start or the end")),
+            format!("  {:<18} {}", "<Del>", translate("Delete the character under the cursor")),
+            format!("  {:<18} {}", "<C-u>, <C-k>", translate("Delete everything before or after the cursor")),
+            format!("  {:<18} {}", "<C-l>", translate("Clear the input")),
+            format!("  {:<18} {}", "<C-z>, <C-_>", translate("Undo the last edit of the input")),
+            format!("  {:<18} {}", "<C-r>", translate("Show or hide the value of a secret")),
+            format!("  {:<18} {}", "<S-Left>, <S-Right>", translate("Select text")),
+            format!("  {:<18} {}", "<C-c>, <C-x>", translate("Copy or cut the selection")),
+        ]);
+
+        return lines;
+    }
+}
+
+/// The keys of an action in the config file: one, or a list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConfigKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl App {
+    /// The action a key press completes on a screen, keeping the keys that start a longer
+    /// sequence until the next press.
+    pub fn key_action(&mut self, event: KeyEvent, screen_actions: &[KeyAction]) -> Option<KeyAction> {
+        self.pending_keys.push(KeyChord::from_event(event));
+
+        return match self.config.keybindings.match_keys(&self.pending_keys, screen_actions) {
+            KeyMatch::Action(action) => {
+                self.pending_keys.clear();
+                Some(action)
+            }
+            KeyMatch::Pending => None,
+            KeyMatch::None => {
+                self.pending_keys.clear();
+                None
+            }
+        };
+    }
+}
+
+/// The config lists only the actions it remaps. The others keep their default keys, but lose the
+/// ones that would win over a remapped sequence on a screen they share with its action: the same
+/// sequence on an action the screen matches first, or one the remapped sequence starts with.
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let remapped = HashMap::<KeyAction, ConfigKeys>::deserialize(deserializer)?;
+
+        let mut keymap = Keymap::default();
+        let mut remapped_sequences: Vec<(KeyAction, KeySequence)> = vec![];
+        for (action, keys) in &remapped {
+            let keys = match keys {
+                ConfigKeys::One(key) => std::slice::from_ref(key),
+                ConfigKeys::Many(keys) => keys.as_slice(),
+            };
+            let sequences = keys
+                .iter()
+                .map(|key| KeySequence::parse(key))
+                .collect::<Result<Vec<KeySequence>, String>>()
+                .map_err(serde::de::Error::custom)?;
+
+            remapped_sequences.extend(sequences.iter().map(|sequence| (*action, sequence.clone())));
+            keymap.bindings.insert(*action, sequences);
+        }
+
+        // The positions of both actions on each screen that has them.
+        let shared_screens = |action: KeyAction, remapped_action: KeyAction| {
+            return SCREEN_ACTIONS.iter().filter_map(move |screen| {
+                let position = screen.iter().position(|screen_action| *screen_action == action)?;
+                let remapped_position = screen.iter().position(|screen_action| *screen_action == remapped_action)?;
+                return Some((position, remapped_position));
+            });
+        };
+        let wins_over_remapped = |action: &KeyAction, sequence: &KeySequence| {
+            return remapped_sequences.iter().any(|(remapped_action, remapped)| {
+                let mut shared = shared_screens(*action, *remapped_action);
+                if remapped.0 == sequence.0 {
+                    return shared.any(|(position, remapped_position)| position < remapped_position);
+                }
+
+                return remapped.0.starts_with(&sequence.0) && shared.next().is_some();
+            });
+        };
+        for (action, sequences) in &mut keymap.bindings {
+            if !remapped.contains_key(action) {
+                sequences.retain(|sequence| !wins_over_remapped(action, sequence));
+            }
+        }
+
+        return Ok(keymap);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_chords() {
+        let chord = |modifiers, code| KeyChord { modifiers, code };
+
+        assert_eq!(KeyChord::parse("j"), Ok(chord(KeyModifiers::NONE, KeyCode::Char('j'))));
+        assert_eq!(KeyChord::parse("<C-d>"), Ok(chord(KeyModifiers::CONTROL, KeyCode::Char('d'))));
+        assert_eq!(KeyChord::parse("<M-S-Down>"), Ok(chord(KeyModifiers::ALT | KeyModifiers::SHIFT, KeyCode::Down)));
+        assert_eq!(KeyChord::parse("<S-g>"), Ok(chord(KeyModifiers::NONE, KeyCode::Char('G'))));
+        assert_eq!(KeyChord::parse("<C-->"), Ok(chord(KeyModifiers::CONTROL, KeyCode::Char('-'))));
+        assert_eq!(KeyChord::parse("<F5>"), Ok(chord(KeyModifiers::NONE, KeyCode::F(5))));
+        assert!(KeyChord::parse("jj").is_err());
+        assert!(KeyChord::parse("<X-j>").is_err());
+        assert!(KeyChord::parse("<Nope>").is_err());
+
+        for key in ["j", "G", "<C-d>", "<Down>", "<M-S-Down>", "<Enter>", "<Space>", "<lt>"] {
+            assert_eq!(KeyChord::parse(key).unwrap().to_string(), key);
+        }
+
+        let sequence = |keys: &[&str]| KeySequence(keys.iter().map(|key| KeyChord::parse(key).unwrap()).collect());
+        assert_eq!(KeySequence::parse("ZZ"), Ok(sequence(&["Z", "Z"])));
+        assert_eq!(KeySequence::parse("<C-w>j"), Ok(sequence(&["<C-w>", "j"])));
+        assert_eq!(KeySequence::parse("<lt><"), Ok(sequence(&["<lt>", "<"])));
+        assert_eq!(KeySequence::parse("<C-->"), Ok(sequence(&["<C-->"])));
+        assert!(KeySequence::parse("").is_err());
+        assert!(KeySequence::parse("g<Nope>").is_err());
+        assert_eq!(KeySequence::parse("g<Down>").unwrap().to_string(), "g<Down>");
+
+        // Shift comes with the uppercase character it types.
+        let shifted = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from_event(shifted), chord(KeyModifiers::NONE, KeyCode::Char('G')));
+    }
+
+    #[test]
+    fn test_keymap() {
+        let keymap: Keymap = serde_json::from_value(json!({ "move_down": ["n", "<Down>"], "next_match": "j" })).unwrap();
+        let press = |char| [KeyChord { modifiers: KeyModifiers::NONE, code: KeyCode::Char(char) }];
+
+        assert_eq!(keymap.match_keys(&press('n'), &[KeyAction::NextMatch, KeyAction::MoveDown]), KeyMatch::Action(KeyAction::MoveDown));
+        assert_eq!(keymap.match_keys(&press('j'), &[KeyAction::NextMatch, KeyAction::MoveDown]), KeyMatch::Action(KeyAction::NextMatch));
+        assert_eq!(keymap.match_keys(&press('k'), &[KeyAction::MoveUp]), KeyMatch::Action(KeyAction::MoveUp));
+        assert_eq!(keymap.match_keys(&press('k'), &[KeyAction::MoveDown]), KeyMatch::None);
+
+        // Earlier actions of a screen win when a key is bound to several.
+        let escape = [KeyChord { modifiers: KeyModifiers::NONE, code: KeyCode::Esc }];
+        assert_eq!(keymap.match_keys(&escape, &[KeyAction::ClearSearch, KeyAction::Close]), KeyMatch::Action(KeyAction::ClearSearch));
+
+        let help = keymap.help_lines();
+        assert!(help.contains(&format!("  {:<18} {}", "n, <Down>", "Move down")));
+        assert!(help.contains(&format!("  {:<18} {}", "j", "Go to the next match")));
+
+        let keys = |keys: &str| KeySequence::parse(keys).unwrap().0;
+        assert_eq!(keymap.match_keys(&keys("Z"), VIEWING_ACTIONS), KeyMatch::Pending);
+        assert_eq!(keymap.match_keys(&keys("ZZ"), VIEWING_ACTIONS), KeyMatch::Action(KeyAction::SaveAndQuit));
+        assert_eq!(keymap.match_keys(&keys("Zj"), VIEWING_ACTIONS), KeyMatch::None);
+        assert_eq!(keymap.match_keys(&keys("Z"), PREVIEW_ACTIONS), KeyMatch::None);
+
+        // The keys remapped to an action are taken from the defaults of the others.
+        let keymap: Keymap = serde_json::from_value(json!({ "yank": "j", "quit": "tt" })).unwrap();
+        assert_eq!(keymap.match_keys(&keys("j"), VIEWING_ACTIONS), KeyMatch::Action(KeyAction::Yank));
+        assert_eq!(keymap.keys_of(KeyAction::MoveDown), [KeySequence::parse("<Down>").unwrap(), KeySequence::parse("<C-n>").unwrap()]);
+        assert_eq!(keymap.match_keys(&keys("t"), VIEWING_ACTIONS), KeyMatch::Pending);
+        assert_eq!(keymap.match_keys(&keys("tt"), VIEWING_ACTIONS), KeyMatch::Action(KeyAction::Quit));
+        assert!(keymap.keys_of(KeyAction::ToggleTimestamps).is_empty());
+        assert!(keymap.binds(KeyAction::Yank, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)));
+        assert!(!keymap.binds(KeyAction::MoveDown, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)));
+
+        // Only the actions on the screens of the remapped one, and matched before it, lose the key.
+        let keymap: Keymap = serde_json::from_value(json!({ "quit": "<Esc>" })).unwrap();
+        assert_eq!(keymap.match_keys(&escape, VIEWING_ACTIONS), KeyMatch::Action(KeyAction::Quit));
+        assert_eq!(keymap.match_keys(&escape, PREVIEW_ACTIONS), KeyMatch::Action(KeyAction::Close));
+        assert_eq!(keymap.keys_of(KeyAction::ClearSearch), [KeySequence::parse("<Esc>").unwrap()]);
+
+        assert!(serde_json::from_value::<Keymap>(json!({ "fly": "f" })).is_err());
+        assert!(serde_json::from_value::<Keymap>(json!({ "quit": "<Hyper-q>" })).is_err());
+    }
+}
@@ -1,68 +1,307 @@
-use std::{env, fs::{self, OpenOptions}, io::Read, process::{self, exit}};
-use app::App;
+// Explicit `return`s and un-collapsed `if`s are the house style of this codebase.
+#![allow(
+    clippy::needless_return,
+    clippy::collapsible_if,
+    clippy::collapsible_match,
+    clippy::comparison_to_empty,
+    clippy::len_zero,
+    clippy::enum_variant_names,
+)]
+
+use std::{env, fs, io::{self, IsTerminal, Write}, path::{Path, PathBuf}, process::{self, exit}, time::Duration};
+use app::{App, CurrentScreen, FileStatus, Problem, ProblemKind, ReportedMessageKinds};
+use config::Config;
+use i18n::tr;
+use theme::Theme;
+use helpers::SourceExcerpt;
+use serde::de::IgnoredAny;
+use serde_json::Value;
+use utils::{duplicates::find_duplicate_keys, repair::describe_error};
+// How documents are read and written is shared with the other tools through `todd-core`.
+use todd_core::formats::{self, Decoded, FileFormat, Syntax, TextEncoding};
 
 mod events;
+mod exchange;
 mod actions;
 mod app;
+mod clipboard;
+mod command_completion;
+mod commands;
+mod completion;
+mod config;
+mod confirm;
+mod delete;
 mod draw;
+mod duplicate;
 mod helpers;
+mod highlight_rules;
+mod i18n;
+mod input_bindings;
+mod input_history;
+mod journal;
+mod keymap;
+mod mouse;
+mod move_into;
+mod options;
+mod preview;
+mod registers;
+mod repair_screen;
+mod schema;
+mod session;
+mod sorted_view;
+mod status_line;
+mod subcommands;
+mod table;
+mod terminal_title;
+mod theme;
+mod tutorial;
+mod type_icons;
 mod views;
+mod visual;
 mod widgets;
 mod utils;
 
+const USAGE: &str = "Usage: todd [--create] [--utf8] [--lossy] [--lenient] [--schema <schema.json | url>] [--theme <name>] [--no-color] <file.json | url>\n       todd --check [--lenient] [--schema <schema.json | url>] <file>...\n       todd convert [--force] [--lenient] <input> <output>\n       todd get [--json] <file> <path>\n       todd set <file> <path> <value>\n       todd delete <file> <path>\n       todd fmt [--compact] <file>...";
+
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
-    
+
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 {
-        eprintln!("Usage: todd <file.json>");
+        eprintln!("{}", USAGE);
         exit(1);
     }
 
-    let argument = args[1].clone();
-    
-    if argument == "--version" || argument == "-version" {
-        const VERSION: &str = env!("CARGO_PKG_VERSION");
-
-        println!("Todd version {}", VERSION);
+    if let Some(subcommand) = subcommands::find(&args[1]) {
+        if let Err(message) = subcommand(&args[2..]) {
+            eprintln!("{}", message);
+            exit(1);
+        }
         exit(0);
     }
 
-    if argument.starts_with("-") {
-        println!("Usage: todd <file.json>");
-        exit(0);
+    // More than one file is only taken by `--check`, otherwise the last one is opened.
+    let mut file_paths: Vec<String> = vec![];
+    // Checks the files and exits instead of opening them.
+    let mut check = false;
+    // Opens an empty root object if the file doesn't exist yet. The file is written on the first save.
+    let mut create_if_missing = false;
+    // Saves UTF-16 or BOM-prefixed files as plain UTF-8 instead of their original encoding.
+    let mut save_as_utf8 = false;
+    // Replaces invalid byte sequences instead of refusing to open the file.
+    let mut lossy = false;
+    // Opens files that are malformed in small ways (missing commas, stray comments...) as best it can.
+    let mut lenient = false;
+    let mut no_color = theme::no_color_requested();
+
+    // The JSON Schema to validate the document against. Without it, the `$schema` of the document is used.
+    let mut schema_source: Option<String> = None;
+    let mut theme_name: Option<String> = None;
+
+    let mut arguments = args.iter().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--version" | "-version" => {
+                const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+                println!("Todd version {}", VERSION);
+                exit(0);
+            }
+            "--create" | "-c" => {
+                create_if_missing = true;
+            }
+            "--utf8" => {
+                save_as_utf8 = true;
+            }
+            "--lossy" => {
+                lossy = true;
+            }
+            "--no-color" => {
+                no_color = true;
+            }
+            "--lenient" => {
+                lenient = true;
+            }
+            "--check" => {
+                check = true;
+            }
+            "--schema" => {
+                let Some(source) = arguments.next() else {
+                    eprintln!("{}", USAGE);
+                    exit(1);
+                };
+                schema_source = Some(source.clone());
+            }
+            "--theme" => {
+                let Some(name) = arguments.next() else {
+                    eprintln!("{}", USAGE);
+                    exit(1);
+                };
+                theme_name = Some(name.clone());
+            }
+            _ if argument.starts_with("-") => {
+                println!("{}", USAGE);
+                exit(0);
+            }
+            _ => {
+                file_paths.push(argument.clone());
+            }
+        }
     }
 
-    
-    let file_path = argument;
-    let mut file = match OpenOptions::new()
-        .read(true)       // Allow reading
-        .write(true)      // Allow writing
-        .open(&file_path) {
-            Ok(val) => val,
+    if check {
+        if file_paths.is_empty() {
+            eprintln!("{}", USAGE);
+            exit(1);
+        }
+
+        let mut failed = false;
+        for file_path in &file_paths {
+            if let Err(problems) = subcommands::check(file_path, schema_source.as_deref(), lenient) {
+                eprintln!("{}", problems);
+                failed = true;
+            }
+        }
+        exit(if failed { 1 } else { 0 });
+    }
+
+    let Some(file_path) = file_paths.pop() else {
+        eprintln!("{}", USAGE);
+        exit(1);
+    };
+
+    // Documents opened from a URL have no file behind them. They can only be saved elsewhere with `:saveas`.
+    let is_url = utils::http::is_url(&file_path);
+
+    let mut is_new_file = false;
+    let file_bytes = if is_url {
+        match utils::http::fetch(&file_path) {
+            Ok(bytes) => bytes,
+            Err(message) => {
+                eprintln!("{}", message);
+                exit(1);
+            }
+        }
+    } else {
+        match fs::read(&file_path) {
+            Ok(bytes) => bytes,
+            // The new file is only written on the first save.
+            Err(err) if err.kind() == io::ErrorKind::NotFound && create_if_missing => {
+                is_new_file = true;
+                vec![]
+            }
             Err(err) => {
                 eprintln!("Failed to open file: {}", err);
+                if err.kind() == io::ErrorKind::NotFound {
+                    eprintln!("Pass --create to start a new file at this path.");
+                }
+                exit(1);
+            }
+        }
+    };
+
+    let mut file_format = FileFormat::detect(Path::new(&file_path), &file_bytes);
+    file_format.lossy = lossy;
+    file_format.lenient = lenient;
+
+    let Decoded { text: mut file_content, comments, replaced_lines, parsed_leniently } = if file_bytes.is_empty() {
+        Decoded::default()
+    } else {
+        match file_format.decode(file_bytes) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Failed to read file: {}", err);
                 exit(1);
-            },
+            }
+        }
+    };
+
+    if save_as_utf8 {
+        file_format.encoding = TextEncoding::Utf8;
+    }
+
+    if file_content.trim().is_empty() {
+        if !create_if_missing {
+            println!("File is empty.");
+            process::exit(0);
+        }
+
+        // Start authoring from an empty root object. It's written to the file on the first save.
+        file_content = String::from("{}");
+    }
+
+    // Read before the first screen is shown, to show it in the language of the config.
+    let config = Config::load();
+
+    // Instead of failing to open a file that isn't valid JSON, offer to repair it.
+    let mut is_repaired = false;
+    if let Err(err) = serde_json::from_str::<IgnoredAny>(&file_content) {
+        // Without a terminal to show the screen in, the error is printed.
+        let repaired = if io::stdout().is_terminal() {
+            let mut terminal = ratatui::init();
+            let repaired = repair_screen::run(&mut terminal, &file_content, &err, no_color);
+            ratatui::restore();
+            repaired?
+        } else {
+            None
         };
-    let mut file_content = String::new();
-    file.read_to_string(&mut file_content)?;
 
-    if file_content.is_empty() {
-        println!("File is empty.");
-        process::exit(0);
+        match repaired {
+            Some(repaired) => {
+                file_content = repaired;
+                is_repaired = true;
+            }
+            None => {
+                eprintln!("Failed to parse file: {}\n", err);
+                eprint!("{}", SourceExcerpt::new(&file_content, err.line(), err.column(), repair_screen::CONTEXT_LINES));
+                eprintln!("\n{}", describe_error(&err));
+                if !lenient {
+                    eprintln!("Pass --lenient to open it anyway if it's only slightly malformed (missing commas, stray comments...).");
+                }
+                exit(1);
+            }
+        }
+    }
+
+    // XML is only read. Like URLs, there's no file to save back to, only `:saveas` to a JSON file.
+    let is_xml = file_format.syntax == Syntax::Xml;
+
+    // A relative `$schema` is relative to the document, like editors resolve it.
+    let schema_base_dir = if is_url { PathBuf::new() } else { Path::new(&file_path).parent().map(Path::to_path_buf).unwrap_or_default() };
+
+    let (file_metadata, file_path) = if is_url || is_xml {
+        (None, None)
+    } else {
+        (fs::metadata(&file_path).ok(), Some(PathBuf::from(file_path)))
+    };
+
+    // A leftover journal means that a previous session ended without saving its changes.
+    let mut recovered_json = None;
+    if let Some(file_path) = &file_path && let Some(journal_json) = journal::read_journal(file_path) {
+        print!(
+            "Found unsaved changes to {} from a previous session. Recover them? [y/N] ",
+            file_path.display(),
+        );
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+
+        if answer.trim().eq_ignore_ascii_case("y") {
+            recovered_json = Some(journal_json);
+        } else {
+            journal::remove_journal(file_path);
+        }
     }
-    
-    let file_metadata = fs::metadata(file_path)?;
-    
+
     let terminal = ratatui::init();
 
-    let app = match App::new(
-        &file_content, 
-        Some(file_metadata), 
-        Some(&mut file),
-        terminal.size().unwrap().clone(),
+    let mut app = match App::new(
+        &file_content,
+        file_metadata,
+        file_path,
+        terminal.size().unwrap(),
     ) {
         Ok(app) => app,
         Err(err) => {
@@ -72,9 +311,148 @@ fn main() -> color_eyre::Result<()> {
         }
     };
 
+    app.file_format = file_format;
+    app.comments = comments;
+    if is_new_file {
+        app.file_status = FileStatus::New;
+    }
+
+    let lossy_syntax_name = match file_format.syntax {
+        Syntax::Json5 => Some("JSON5"),
+        Syntax::Hjson => Some("Hjson"),
+        Syntax::Yaml => Some("YAML"),
+        _ => None,
+    };
+    if file_format.concatenated.is_some() {
+        app.report(
+            tr!("The file holds {} JSON documents. They're shown as an array and saved back one after the other", app.json.as_array().map_or(0, Vec::len)),
+            ReportedMessageKinds::Info,
+            Duration::from_secs(5),
+        );
+    } else if parsed_leniently {
+        app.report(
+            tr!("The file isn't valid JSON and was opened leniently. It's saved as strict JSON"),
+            ReportedMessageKinds::Warning,
+            Duration::from_secs(5),
+        );
+    } else if let Some(name) = lossy_syntax_name {
+        app.report(
+            tr!("Opened as {}. Comments are not kept on save. Use :format json to save it as strict JSON", name),
+            ReportedMessageKinds::Info,
+            Duration::from_secs(5),
+        );
+    }
+
+    match config {
+        Ok(config) => app.config = config,
+        Err(message) => {
+            app.report(message, ReportedMessageKinds::Error, Duration::from_secs(5));
+        }
+    }
+
+    // `--theme` wins over the theme of the config file.
+    let theme = match theme_name.as_deref().map(Theme::load) {
+        Some(Ok(theme)) => theme,
+        Some(Err(message)) => {
+            app.report(message, ReportedMessageKinds::Error, Duration::from_secs(5));
+            app.config.theme.clone()
+        }
+        None => app.config.theme.clone(),
+    };
+    app.set_theme(theme);
+    app.no_color = no_color;
+
+    app.restore_session();
+    app.offer_tutorial();
+
+    if is_xml {
+        app.report(
+            tr!("Opened XML as a JSON tree. Use :saveas <path> to convert it to a JSON file"),
+            ReportedMessageKinds::Info,
+            Duration::from_secs(5),
+        );
+    } else if is_url {
+        app.report(
+            tr!("Opened from a URL. Use :saveas <path> to save it to a file"),
+            ReportedMessageKinds::Info,
+            Duration::from_secs(5),
+        );
+    }
+
+    if !replaced_lines.is_empty() {
+        const MAX_LISTED_LINES: usize = 10;
+
+        let mut lines = replaced_lines
+            .iter()
+            .take(MAX_LISTED_LINES)
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if replaced_lines.len() > MAX_LISTED_LINES {
+            lines.push_str(&tr!(" and {} more", replaced_lines.len() - MAX_LISTED_LINES));
+        }
+
+        app.report(
+            tr!("Replaced invalid bytes with � on line(s) {}. Saving keeps the replacements", lines),
+            ReportedMessageKinds::Warning,
+            Duration::from_secs(8),
+        );
+    }
+
+    // serde keeps only the last value of a repeated key. List them before the others are lost on save.
+    let duplicate_keys = find_duplicate_keys(&file_content);
+    if !duplicate_keys.is_empty() {
+        app.problems = duplicate_keys
+            .into_iter()
+            .map(|duplicate| Problem {
+                kind: ProblemKind::DuplicateKey,
+                message: tr!(
+                    "Duplicate key on line {} (first on line {}). Only the last value is kept",
+                    duplicate.line,
+                    duplicate.first_line,
+                ),
+                path: duplicate.path,
+            })
+            .collect();
+        app.current_screen = CurrentScreen::Problems;
+        app.report(
+            tr!("Found {} duplicate key(s). The values before the last one are dropped on save", app.problems.len()),
+            ReportedMessageKinds::Warning,
+            Duration::from_secs(8),
+        );
+    }
+
+    // A `--schema` is relative to where todd was run from, like any other path on the command line.
+    let schema = match schema_source {
+        Some(source) => Some((source, PathBuf::new())),
+        None => app.json.get("$schema").and_then(Value::as_str).map(|source| (source.to_string(), schema_base_dir)),
+    };
+    if let Some((schema_source, base_dir)) = schema {
+        app.set_schema(&schema_source, &base_dir);
+    }
+
+    if is_repaired {
+        app.mark_dirty();
+        app.report(
+            tr!("Opened the repaired document. Press <C-s> to save it"),
+            ReportedMessageKinds::Warning,
+            Duration::from_secs(5),
+        );
+    }
+
+    if let Some(json) = recovered_json {
+        app.json = json;
+        app.mark_dirty();
+        app.report(
+            tr!("Recovered unsaved changes. Press <C-s> to save them"),
+            ReportedMessageKinds::Warning,
+            Duration::from_secs(5),
+        );
+    }
+
     let app_result = app.run(terminal);
-    
+
     ratatui::restore();
-    
+
     return app_result;
-}
\ No newline at end of file
+}
@@ -0,0 +1,175 @@
+//
+// The mouse, with the `mouse` option of the config: clicking a line moves the cursor to it, the
+// wheel scrolls, and dragging an element of an array onto one of its siblings reorders them.
+//
+
+use std::{io, time::Duration};
+
+use crossterm::{event::{DisableMouseCapture, EnableMouseCapture, MouseButton, MouseEvent, MouseEventKind}, execute};
+use serde_json::Value;
+
+use crate::{actions::{Action, MainViewActions}, app::{App, CurrentScreen, ReportedMessageKinds}, i18n::tr, utils::json::{get_line_at_path, get_path_at_line, get_value_at_path_mut, PathSegment}};
+
+/// An element of an array being dragged, and the index of the sibling it's over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drag {
+    pub from: Vec<PathSegment>,
+    pub target: usize,
+}
+
+impl Drag {
+    /// The array the element is dragged in, and its index there.
+    fn array_and_index(&self) -> Option<(&[PathSegment], usize)> {
+        return match self.from.split_last() {
+            Some((PathSegment::Index(index), parent)) => Some((parent, *index)),
+            _ => None,
+        };
+    }
+
+    /// The path of the sibling the element would be dropped on, while it's over another one.
+    pub fn drop_path(&self) -> Option<Vec<PathSegment>> {
+        let (array, index) = self.array_and_index()?;
+        if index == self.target {
+            return None;
+        }
+
+        let mut path = array.to_vec();
+        path.push(PathSegment::Index(self.target));
+
+        return Some(path);
+    }
+}
+
+impl App {
+    /// Has the terminal report the mouse, if it's enabled.
+    pub fn capture_mouse(&self) -> io::Result<()> {
+        if !self.config.mouse {
+            return Ok(());
+        }
+
+        return execute!(io::stdout(), EnableMouseCapture);
+    }
+
+    /// Gives the mouse back to the terminal, for selecting text after todd exits.
+    pub fn release_mouse(&self) -> io::Result<()> {
+        if !self.config.mouse {
+            return Ok(());
+        }
+
+        return execute!(io::stdout(), DisableMouseCapture);
+    }
+
+    /// The line of the tree shown at a row of the terminal.
+    fn line_at_row(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.tree_area;
+        if column < area.x || column >= area.right() || row < area.y || row >= area.bottom() {
+            return None;
+        }
+
+        return self.row_lines.get((row - area.y) as usize).copied();
+    }
+
+    pub fn handle_mouse_event(&mut self, event: MouseEvent) {
+        if !self.config.mouse || !matches!(self.current_screen, CurrentScreen::ViewingFile) {
+            return;
+        }
+
+        let line = self.line_at_row(event.column, event.row);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(line) = line else {
+                    return;
+                };
+                self.move_cursor_to_line(line);
+
+                if let Some(path) = get_path_at_line(line, &self.json) && let Some(PathSegment::Index(index)) = path.last() {
+                    self.drag = Some(Drag { target: *index, from: path });
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let Some(drag) = &mut self.drag else {
+                    return;
+                };
+                let Some((array, _)) = drag.array_and_index() else {
+                    return;
+                };
+
+                // Only the siblings of the element are places to drop it.
+                let over = line.and_then(|line| get_path_at_line(line, &self.json)).unwrap_or_default();
+                if over.starts_with(array) && let Some(PathSegment::Index(index)) = over.get(array.len()) {
+                    drag.target = *index;
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                if let Some(drag) = self.drag.take() {
+                    self.drop_element(drag);
+                }
+            }
+            MouseEventKind::ScrollDown => self.update(Action::MainView(MainViewActions::MoveDown)),
+            MouseEventKind::ScrollUp => self.update(Action::MainView(MainViewActions::MoveUp)),
+            _ => { }
+        }
+    }
+
+    /// Moves the dragged element to the index it was dropped on, shifting the ones between.
+    fn drop_element(&mut self, drag: Drag) {
+        let (Some((array, from)), Some(moved_to)) = (drag.array_and_index(), drag.drop_path()) else {
+            return;
+        };
+        if !self.check_editable() {
+            return;
+        }
+
+        let Some(Value::Array(values)) = get_value_at_path_mut(array, &mut self.json) else {
+            return;
+        };
+        if drag.target >= values.len() {
+            return;
+        }
+        let element = values.remove(from);
+        values.insert(drag.target, element);
+
+        self.mark_dirty();
+        if let Some(line) = get_line_at_path(&moved_to, &self.json) {
+            self.move_cursor_to_line(line);
+        }
+        self.report(tr!("Moved element {} to {}", from, drag.target), ReportedMessageKinds::Success, Duration::from_secs(2));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use ratatui::layout::Rect;
+    use serde_json::json;
+
+    #[test]
+    fn test_drag_to_reorder() {
+        let mut app = App::default();
+        app.config.mouse = true;
+        app.json = json!({ "list": ["a", "b", "c"], "other": 1 });
+        // The tree as drawn from the top of the terminal, a line per row.
+        app.tree_area = Rect::new(0, 0, 40, 10);
+        app.row_lines = (0..5).collect();
+
+        let mouse = |kind, row| MouseEvent { kind, column: 4, row, modifiers: KeyModifiers::NONE };
+
+        // `a` is dragged over `other`, which isn't a sibling, then dropped on `c`.
+        app.handle_mouse_event(mouse(MouseEventKind::Down(MouseButton::Left), 1));
+        app.handle_mouse_event(mouse(MouseEventKind::Drag(MouseButton::Left), 3));
+        app.handle_mouse_event(mouse(MouseEventKind::Drag(MouseButton::Left), 4));
+        assert_eq!(app.drag.as_ref().map(|drag| drag.target), Some(2));
+        app.handle_mouse_event(mouse(MouseEventKind::Up(MouseButton::Left), 4));
+
+        assert_eq!(app.json, json!({ "list": ["b", "c", "a"], "other": 1 }));
+        assert_eq!(app.line_at_cursor, 3);
+        assert_eq!(app.drag, None);
+
+        // Clicking a line that isn't an element only moves the cursor.
+        app.handle_mouse_event(mouse(MouseEventKind::Down(MouseButton::Left), 0));
+        app.handle_mouse_event(mouse(MouseEventKind::Up(MouseButton::Left), 2));
+        assert_eq!(app.line_at_cursor, 0);
+        assert_eq!(app.json, json!({ "list": ["b", "c", "a"], "other": 1 }));
+    }
+}
@@ -0,0 +1,149 @@
+//
+// Moving a node into another object or array: marked with `m`, it's moved into the container
+// the cursor is on when `m` is pressed again, or into the one at a pointer with `:move`. Both
+// containers change in the same step.
+//
+
+use std::time::Duration;
+
+use crate::{app::{App, ReportedMessageKinds}, i18n::tr, utils::{edit::{move_node, MoveError}, json::{get_line_at_path, get_path_at_line, path_to_pointer, pointer_to_path, PathSegment}}};
+
+/// How a container is called in the messages.
+fn container_name(path: &[PathSegment]) -> String {
+    return if path.is_empty() { tr!("the document") } else { path_to_pointer(path) };
+}
+
+/// What went wrong moving the node at `from` into the container at `into`.
+fn move_error_message(error: MoveError, from: &[PathSegment], into: &[PathSegment]) -> String {
+    let name = path_to_pointer(from);
+
+    return match error {
+        MoveError::Root => tr!("The document can't be moved"),
+        MoveError::NotFound => tr!("There is no such line or value in the document"),
+        MoveError::IntoItself => tr!("{} can't be moved into itself", name),
+        MoveError::AlreadyThere => tr!("{} is already in {}", name, container_name(into)),
+        MoveError::KeyTaken(key) => tr!("{} already has a '{}' key", container_name(into), key),
+        MoveError::NoKey => tr!("{} is an element of an array, it has no key to go into an object", name),
+        MoveError::NotAContainer => tr!("{} isn't an object or an array", container_name(into)),
+    };
+}
+
+impl App {
+    /// Marks the node under the cursor, or moves the marked one into the object or array under the cursor.
+    pub fn move_into_at_cursor(&mut self) {
+        if self.visual_anchor.is_some() {
+            self.mark_visual_range_to_move();
+            return;
+        }
+
+        let Some(path) = get_path_at_line(self.line_at_cursor, &self.json) else {
+            self.report(tr!("Nothing to move at the current line"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let Some((marked, count)) = self.move_mark.take() else {
+            self.report(
+                tr!("Marked {} to move, do it again on the object or array to move it into", path_to_pointer(&path)),
+                ReportedMessageKinds::Info,
+                Duration::from_secs(3),
+            );
+            self.move_mark = Some((path, 1));
+            return;
+        };
+
+        if marked == path {
+            self.report(tr!("Unmarked {}", path_to_pointer(&path)), ReportedMessageKinds::Info, Duration::from_secs(2));
+            return;
+        }
+
+        self.move_nodes_into(&marked, count, &path);
+    }
+
+    /// Moves the node under the cursor into the object or array at the JSON Pointer. `/` is the document itself.
+    pub fn move_at_cursor_to_pointer(&mut self, pointer: &str) {
+        let Some(path) = get_path_at_line(self.line_at_cursor, &self.json) else {
+            self.report(tr!("Nothing to move at the current line"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+        let into = if pointer == "/" { Some(vec![]) } else { pointer_to_path(pointer, &self.json) };
+        let Some(into) = into else {
+            self.report(tr!("There is no such line or value in the document"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        self.move_nodes_into(&path, 1, &into);
+    }
+
+    /// Moves the node at `from` and the siblings after it, `count` in all, into the container.
+    fn move_nodes_into(&mut self, from: &[PathSegment], count: usize, into: &[PathSegment]) {
+        let Some((segment, parent)) = from.split_last() else {
+            return;
+        };
+        // The elements of the range after the first one can't be the target either.
+        if let (PathSegment::Index(first), Some(PathSegment::Index(index))) = (segment, into.get(parent.len()))
+            && count > 1
+            && into.starts_with(parent)
+            && (*first..*first + count).contains(index)
+        {
+            self.report(tr!("{} can't be moved into itself", path_to_pointer(into)), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        }
+
+        let mut into = into.to_vec();
+        let mut moved_to = vec![];
+        for _ in 0..count {
+            match move_node(&mut self.json, from, &into) {
+                Ok(path) => {
+                    into = path[..path.len() - 1].to_vec();
+                    moved_to = path;
+                }
+                Err(error) => {
+                    self.report(move_error_message(error, from, &into), ReportedMessageKinds::Error, Duration::from_secs(3));
+                    break;
+                }
+            }
+        }
+        if moved_to.is_empty() {
+            return;
+        }
+
+        self.mark_dirty();
+        if let Some(line) = get_line_at_path(&moved_to, &self.json) {
+            self.move_cursor_to_line(line);
+        }
+        self.report(
+            if count == 1 {
+                tr!("Moved {} into {}", path_to_pointer(from), container_name(&into))
+            } else {
+                tr!("Moved {} elements into {}", count, container_name(&into))
+            },
+            ReportedMessageKinds::Success,
+            Duration::from_secs(2),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::actions::{Action, MainViewActions};
+
+    #[test]
+    fn test_move_into_at_cursor() {
+        let mut app = App::default();
+        app.json = json!({ "a": 1, "b": { "c": 2 } });
+
+        // `a` is marked, then moved into `b`, where the cursor follows it.
+        app.update(Action::MainView(MainViewActions::MoveInto));
+        app.line_at_cursor = 1;
+        app.update(Action::MainView(MainViewActions::MoveInto));
+        assert_eq!(app.json, json!({ "b": { "c": 2, "a": 1 } }));
+        assert_eq!(app.line_at_cursor, 2);
+        assert_eq!(app.move_mark, None);
+
+        app.move_at_cursor_to_pointer("/");
+        assert_eq!(app.json, json!({ "b": { "c": 2 }, "a": 1 }));
+    }
+}
@@ -0,0 +1,123 @@
+//
+// Options of the view that can be changed while todd runs, with `:set`.
+//
+
+use std::time::Duration;
+
+use crate::{actions::{Action, MainViewActions}, app::{App, ReportedMessageKinds}, i18n::tr};
+
+/// The names of the options `:set` knows.
+pub const OPTION_NAMES: [&str; 5] = ["timestamps", "envvalues", "wrap", "number", "scrolloff"];
+/// The options that are turned on or off, and also take a `no` prefix.
+pub const BOOLEAN_OPTION_NAMES: [&str; 4] = ["timestamps", "envvalues", "wrap", "number"];
+
+/// What `:set` does to an option: `:set name`, `:set noname`, `:set name!`, `:set name=value`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionAssignment {
+    On,
+    Off,
+    Toggle,
+    Value(String),
+}
+
+impl OptionAssignment {
+    /// Parses the argument of `:set` into the name of the option and what to do with it.
+    pub fn parse(argument: &str) -> Result<(String, OptionAssignment), String> {
+        let usage = || tr!("Usage: :set <option> | no<option> | <option>! | <option>=<value>");
+        if argument.is_empty() || argument.contains(char::is_whitespace) {
+            return Err(usage());
+        }
+
+        if let Some((name, value)) = argument.split_once('=') {
+            return Ok((name.to_string(), OptionAssignment::Value(value.to_string())));
+        }
+        if let Some(name) = argument.strip_suffix('!') {
+            return Ok((name.to_string(), OptionAssignment::Toggle));
+        }
+        if let Some(name) = argument.strip_prefix("no").filter(|name| BOOLEAN_OPTION_NAMES.contains(name)) {
+            return Ok((name.to_string(), OptionAssignment::Off));
+        }
+
+        return Ok((argument.to_string(), OptionAssignment::On));
+    }
+}
+
+impl App {
+    /// Sets an option of the view.
+    pub fn set_option(&mut self, name: &str, assignment: OptionAssignment) {
+        if let Err(message) = self.try_set_option(name, assignment) {
+            self.report(message, ReportedMessageKinds::Error, Duration::from_secs(3));
+        }
+    }
+
+    fn try_set_option(&mut self, name: &str, assignment: OptionAssignment) -> Result<(), String> {
+        if name == "scrolloff" {
+            let OptionAssignment::Value(value) = assignment else {
+                return Err(tr!("Usage: :set scrolloff=<lines>"));
+            };
+            self.config.scrolloff = value.parse().map_err(|_| tr!("Not a number of lines: {}", value))?;
+
+            return Ok(());
+        }
+
+        let is_on = match name {
+            "timestamps" => self.show_timestamps,
+            "envvalues" => self.show_env_values,
+            "wrap" => self.config.wrap,
+            "number" => self.config.number,
+            _ => return Err(tr!("Unknown option: {}. The options are {}", name, OPTION_NAMES.join(", "))),
+        };
+
+        let turn_on = match assignment {
+            OptionAssignment::On => true,
+            OptionAssignment::Off => false,
+            OptionAssignment::Toggle => !is_on,
+            OptionAssignment::Value(_) => return Err(tr!("{0} is turned on with :set {0} and off with :set no{0}", name)),
+        };
+        if turn_on == is_on {
+            return Ok(());
+        }
+
+        match name {
+            "timestamps" => self.update(Action::MainView(MainViewActions::ToggleTimestamps)),
+            "envvalues" => self.update(Action::MainView(MainViewActions::ToggleEnvValues)),
+            "wrap" => self.config.wrap = turn_on,
+            _ => self.config.number = turn_on,
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_options() {
+        assert_eq!(OptionAssignment::parse("timestamps"), Ok(("timestamps".to_string(), OptionAssignment::On)));
+        assert_eq!(OptionAssignment::parse("notimestamps"), Ok(("timestamps".to_string(), OptionAssignment::Off)));
+        assert_eq!(OptionAssignment::parse("envvalues!"), Ok(("envvalues".to_string(), OptionAssignment::Toggle)));
+        assert_eq!(OptionAssignment::parse("scrolloff=3"), Ok(("scrolloff".to_string(), OptionAssignment::Value("3".to_string()))));
+        assert!(OptionAssignment::parse("").is_err());
+
+        let mut app = App::default();
+        app.set_option("timestamps", OptionAssignment::Off);
+        assert!(!app.show_timestamps);
+        app.set_option("timestamps", OptionAssignment::Off);
+        assert!(!app.show_timestamps);
+        app.set_option("envvalues", OptionAssignment::Toggle);
+        assert!(app.show_env_values);
+
+        app.set_option("wrap", OptionAssignment::On);
+        app.set_option("number", OptionAssignment::Toggle);
+        assert!(app.config.wrap && app.config.number);
+
+        app.set_option("scrolloff", OptionAssignment::Value("3".to_string()));
+        assert_eq!(app.config.scrolloff, 3);
+        assert!(app.try_set_option("scrolloff", OptionAssignment::Value("many".to_string())).is_err());
+        assert!(app.try_set_option("scrolloff", OptionAssignment::On).is_err());
+        assert!(app.try_set_option("wrap", OptionAssignment::Value("1".to_string())).is_err());
+        assert!(app.try_set_option("ruler", OptionAssignment::On).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//
+// Read-only popups previewing what the value under the cursor holds, like decoded base64, JWTs or URLs.
+//
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::{actions::{Action, AppNavigationAction, PreviewAction}, app::{App, CurrentScreen, ReportedMessageKinds}, i18n::{tr, translate}, utils::{decode::{decode_base64, decode_jwt, hex_dump, percent_decode, query_parameters}, timestamp::format_utc, json::{get_path_at_line, get_value_at_line, get_value_at_path_mut, path_to_pointer}, stats::{descendants_count, human_size, nesting_depth, serialized_size, type_of}}};
+
+/// The content of the preview popup.
+#[derive(Debug)]
+pub struct Preview {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+    /// A value that can replace the one under the cursor, with what to call it in the popup.
+    pub replacement: Option<(Value, &'static str)>,
+}
+
+impl App {
+    pub fn handle_preview_actions(&mut self, action: PreviewAction) {
+        match action {
+            PreviewAction::DecodeBase64 => self.preview_base64(),
+            PreviewAction::DecodeJwt => self.preview_jwt(),
+            PreviewAction::DecodeUrl => self.preview_url_decoded(),
+            PreviewAction::Inspect => self.inspect_node(),
+            PreviewAction::Help => self.open_preview(Preview {
+                title: tr!("Help"),
+                lines: self.config.keybindings.help_lines(),
+                scroll: 0,
+                replacement: None,
+            }),
+            PreviewAction::ScrollDown => {
+                if let Some(preview) = &mut self.preview && preview.scroll + 1 < preview.lines.len() {
+                    preview.scroll += 1;
+                }
+            }
+            PreviewAction::ScrollUp => {
+                if let Some(preview) = &mut self.preview {
+                    preview.scroll = preview.scroll.saturating_sub(1);
+                }
+            }
+            PreviewAction::Replace => self.replace_with_preview(),
+        }
+    }
+
+    pub fn open_preview(&mut self, preview: Preview) {
+        self.preview = Some(preview);
+        self.current_screen = CurrentScreen::Preview;
+    }
+
+    /// The string under the cursor, or `None` after telling the user there isn't one.
+    fn string_at_cursor(&self) -> Option<&str> {
+        let string = get_value_at_line(self.line_at_cursor, &self.json).and_then(Value::as_str);
+        if string.is_none() {
+            self.report(tr!("The value under the cursor isn't a string"), ReportedMessageKinds::Error, Duration::from_secs(3));
+        }
+
+        return string;
+    }
+
+    fn preview_base64(&mut self) {
+        let Some(string) = self.string_at_cursor() else {
+            return;
+        };
+
+        let Some(bytes) = decode_base64(string) else {
+            self.report(tr!("The value isn't valid base64"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        // Text is shown as is and can replace the value. Anything else is binary, shown as a hex dump.
+        let preview = match String::from_utf8(bytes) {
+            Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => Preview {
+                title: tr!("Base64 decoded ({} bytes of text)", text.len()),
+                lines: text.lines().map(str::to_string).collect(),
+                scroll: 0,
+                replacement: Some((Value::String(text), translate("replace the value with the decoded text"))),
+            },
+            Ok(text) => Preview {
+                title: tr!("Base64 decoded ({} bytes of binary data)", text.len()),
+                lines: hex_dump(text.as_bytes()),
+                scroll: 0,
+                replacement: None,
+            },
+            Err(err) => {
+                let bytes = err.into_bytes();
+                Preview {
+                    title: tr!("Base64 decoded ({} bytes of binary data)", bytes.len()),
+                    lines: hex_dump(&bytes),
+                    scroll: 0,
+                    replacement: None,
+                }
+            }
+        };
+
+        self.open_preview(preview);
+    }
+
+    fn preview_jwt(&mut self) {
+        let Some(string) = self.string_at_cursor() else {
+            return;
+        };
+
+        let Some((header, payload)) = decode_jwt(string) else {
+            self.report(tr!("The value isn't a JSON Web Token"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let mut lines = vec![tr!("Header")];
+        lines.extend(serde_json::to_string_pretty(&header).unwrap_or_default().lines().map(str::to_string));
+        lines.push(String::new());
+        lines.push(tr!("Payload"));
+        lines.extend(serde_json::to_string_pretty(&payload).unwrap_or_default().lines().map(str::to_string));
+
+        // The registered time claims are epoch seconds, which are hard to read as is.
+        let claims: Vec<String> = ["iat", "nbf", "exp"]
+            .iter()
+            .filter_map(|claim| payload.get(claim).and_then(Value::as_i64).map(|time| format!("{}: {}", claim, format_utc(time))))
+            .collect();
+        if !claims.is_empty() {
+            lines.push(String::new());
+            lines.extend(claims);
+        }
+
+        lines.push(String::new());
+        lines.push(tr!("The signature isn't verified"));
+
+        self.open_preview(Preview {
+            title: tr!("JSON Web Token"),
+            lines,
+            scroll: 0,
+            replacement: None,
+        });
+    }
+
+    fn preview_url_decoded(&mut self) {
+        let Some(string) = self.string_at_cursor() else {
+            return;
+        };
+
+        let Some(decoded) = percent_decode(string, false) else {
+            self.report(tr!("The value isn't valid URL-encoded text"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let mut lines: Vec<String> = decoded.lines().map(str::to_string).collect();
+
+        // The query parameters are listed one per line, their names aligned.
+        let parameters = query_parameters(string);
+        if !parameters.is_empty() {
+            let name_width = parameters.iter().map(|(name, _)| name.chars().count()).max().unwrap_or_default();
+
+            lines.push(String::new());
+            lines.push(tr!("Query parameters ({})", parameters.len()));
+            for (name, value) in &parameters {
+                lines.push(format!("{:<width$}  {}", name, value, width = name_width));
+            }
+        }
+
+        let replacement = (decoded != string).then(|| (Value::String(decoded), translate("replace the value with the decoded text")));
+        self.open_preview(Preview {
+            title: tr!("URL decoded"),
+            lines,
+            scroll: 0,
+            replacement,
+        });
+    }
+
+    /// Shows where the node under the cursor is and how big it is.
+    fn inspect_node(&mut self) {
+        let (Some(path), Some(value)) = (get_path_at_line(self.line_at_cursor, &self.json), get_value_at_line(self.line_at_cursor, &self.json)) else {
+            self.report(tr!("There is no value under the cursor"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let mut fields = vec![
+            (translate("Path"), path_to_pointer(&path)),
+            (translate("Type"), type_of(value).to_string()),
+            (translate("Depth"), path.len().to_string()),
+        ];
+        match value {
+            Value::Object(map) => fields.push((translate("Keys"), map.len().to_string())),
+            Value::Array(values) => fields.push((translate("Elements"), values.len().to_string())),
+            _ => {}
+        }
+        if value.is_object() || value.is_array() {
+            fields.push((translate("Descendants"), descendants_count(value).to_string()));
+            fields.push((translate("Nesting"), tr!("{} level(s)", nesting_depth(value))));
+        }
+
+        let pretty_size = serde_json::to_string_pretty(value).map(|text| text.len()).unwrap_or_default();
+        fields.push((translate("Size"), tr!("{} compact, {} pretty-printed", human_size(serialized_size(value)), human_size(pretty_size))));
+
+        let document_size = serialized_size(&self.json);
+        if document_size > 0 {
+            fields.push((translate("Share"), tr!("{}% of the document", format!("{:.1}", serialized_size(value) as f64 * 100.0 / document_size as f64))));
+        }
+
+        let name_width = fields.iter().map(|(name, _)| name.chars().count()).max().unwrap_or_default();
+        self.open_preview(Preview {
+            title: tr!("Node"),
+            lines: fields.into_iter().map(|(name, value)| format!("{:<width$}  {}", name, value, width = name_width)).collect(),
+            scroll: 0,
+            replacement: None,
+        });
+    }
+
+    /// Replaces the value under the cursor with the one the preview offers.
+    fn replace_with_preview(&mut self) {
+        let Some((replacement, _)) = self.preview.as_mut().and_then(|preview| preview.replacement.take()) else {
+            return;
+        };
+
+        let Some(value) = get_path_at_line(self.line_at_cursor, &self.json).and_then(|path| get_value_at_path_mut(&path, &mut self.json)) else {
+            return;
+        };
+
+        *value = replacement;
+        self.mark_dirty();
+
+        self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+        self.report(tr!("Replaced the value"), ReportedMessageKinds::Success, Duration::from_secs(2));
+    }
+}
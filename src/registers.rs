@@ -0,0 +1,161 @@
+//
+// The registers holding yanked and deleted nodes, to paste them elsewhere in the document. Like
+// in vim, `"a` to `"z` name the register of the next yank, delete or paste, and the unnamed one
+// holds the last node yanked or deleted, to restructure a document a few pieces at a time.
+//
+
+use std::{collections::HashMap, time::Duration};
+
+use serde_json::Value;
+
+use crate::{app::{App, ReportedMessageKinds}, i18n::tr, utils::{edit::free_key, json::get_nested_object_to_insert_into}};
+
+/// The register used when none is named.
+pub const UNNAMED_REGISTER: char = '"';
+
+/// A node put in a register, with its key when it was in an object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Yanked {
+    pub key: Option<String>,
+    pub value: Value,
+    /// Whether the value is the array of elements yanked together in visual mode, pasted one by one.
+    pub is_range: bool,
+}
+
+/// The nodes of the session, by the letter of their register.
+#[derive(Debug, Default)]
+pub struct Registers {
+    nodes: HashMap<char, Yanked>,
+}
+
+impl Registers {
+    /// Puts the node in the named register, or in the unnamed one without a name. The unnamed one
+    /// always gets the last node, so `p` pastes it either way.
+    pub fn store(&mut self, register: Option<char>, yanked: Yanked) {
+        if let Some(register) = register {
+            self.nodes.insert(register, yanked.clone());
+        }
+        self.nodes.insert(UNNAMED_REGISTER, yanked);
+    }
+
+    pub fn get(&self, register: Option<char>) -> Option<&Yanked> {
+        return self.nodes.get(&register.unwrap_or(UNNAMED_REGISTER));
+    }
+}
+
+impl App {
+    /// Names the register of the next yank, delete or paste, after `"`.
+    pub fn select_register(&mut self, register: char) {
+        if !register.is_ascii_lowercase() {
+            self.selected_register = None;
+            self.report(tr!("There's no register \"{}, they're named a to z", register), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        }
+
+        self.selected_register = Some(register);
+        self.report(tr!("The next yank, delete or paste uses register \"{}", register), ReportedMessageKinds::Info, Duration::from_secs(3));
+    }
+
+    /// Inserts the node of the selected register after the cursor. A key already in the object
+    /// gets a `_copy` suffix instead of being replaced.
+    pub fn paste_at_cursor(&mut self) {
+        let register = self.selected_register.take();
+        let Some(yanked) = self.registers.get(register).cloned() else {
+            self.report(
+                tr!("Register \"{} is empty, yank or delete a node into it first", register.unwrap_or(UNNAMED_REGISTER)),
+                ReportedMessageKinds::Error,
+                Duration::from_secs(3),
+            );
+            return;
+        };
+
+        if yanked.is_range {
+            self.paste_elements_at_cursor(yanked.value);
+            return;
+        }
+
+        let base_key = yanked.key.unwrap_or_else(|| String::from("pasted"));
+        let key = match get_nested_object_to_insert_into(self.line_at_cursor_without_empty_lines(), &mut self.json).0 {
+            Some(Value::Object(map)) => free_key(map, &base_key),
+            _ => base_key,
+        };
+
+        if !self.insert_value_after_cursor(key.clone(), yanked.value) {
+            self.report(tr!("Could not find where to insert at the current line"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        }
+
+        self.mark_dirty();
+        self.report(tr!("Pasted '{}'", key), ReportedMessageKinds::Success, Duration::from_secs(2));
+    }
+
+    /// Inserts the elements yanked together after the cursor, in the array it's in.
+    fn paste_elements_at_cursor(&mut self, elements: Value) {
+        let Value::Array(elements) = elements else {
+            return;
+        };
+
+        let count = elements.len();
+        let (container, at) = if self.is_root_empty() {
+            (Some(&mut self.json), 0)
+        } else {
+            let (container, index) = get_nested_object_to_insert_into(self.line_at_cursor_without_empty_lines(), &mut self.json);
+            (container, index + 1)
+        };
+        let Some(Value::Array(values)) = container else {
+            self.report(tr!("Elements yanked together can only be pasted into an array"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let at = at.min(values.len());
+        values.splice(at..at, elements);
+
+        self.mark_dirty();
+        self.report(tr!("Pasted {} elements", count), ReportedMessageKinds::Success, Duration::from_secs(2));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::actions::{Action, MainViewActions};
+
+    #[test]
+    fn test_registers() {
+        let mut app = App::default();
+        app.json = json!({ "a": 1, "b": { "c": 2 }, "d": [true] });
+
+        // `"x` yanks `a` into x, then `b` is yanked into the unnamed register alone.
+        app.update(Action::MainView(MainViewActions::SelectRegister('x')));
+        app.update(Action::MainView(MainViewActions::Yank));
+        assert_eq!(app.selected_register, None);
+        app.line_at_cursor = 1;
+        app.update(Action::MainView(MainViewActions::Yank));
+
+        // Pasting `b` next to itself gives it another key, and x still holds `a`.
+        app.update(Action::MainView(MainViewActions::Paste));
+        assert_eq!(app.json["b_copy"], json!({ "c": 2 }));
+        app.line_at_cursor = 6;
+        app.update(Action::MainView(MainViewActions::SelectRegister('x')));
+        app.update(Action::MainView(MainViewActions::Paste));
+        assert_eq!(app.json["d"], json!([true, 1]));
+
+        // Deleting into a register moves the node, like `"ydd` then `"yp` elsewhere.
+        app.line_at_cursor = 0;
+        app.update(Action::MainView(MainViewActions::SelectRegister('y')));
+        app.update(Action::MainView(MainViewActions::Delete { confirmed: false }));
+        assert_eq!(app.json.get("a"), None);
+        app.line_at_cursor = 1;
+        app.update(Action::MainView(MainViewActions::SelectRegister('y')));
+        app.update(Action::MainView(MainViewActions::Paste));
+        assert_eq!(app.json["b"], json!({ "c": 2, "a": 1 }));
+
+        app.update(Action::MainView(MainViewActions::SelectRegister('1')));
+        assert_eq!(app.selected_register, None);
+        app.update(Action::MainView(MainViewActions::SelectRegister('q')));
+        app.update(Action::MainView(MainViewActions::Paste));
+        assert_eq!(app.json["b"], json!({ "c": 2, "a": 1 }));
+    }
+}
@@ -0,0 +1,166 @@
+//
+// The screen shown instead of the editor when the file isn't valid JSON. It points at the error
+// and can repair the document, showing what was changed before opening it.
+//
+
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout}, style::{Color, Style, Stylize}, text::{Line, Span}, widgets::{Block, Borders, Padding, Paragraph}, DefaultTerminal, Frame
+};
+
+use crate::{helpers::SourceExcerpt, i18n::{tr, translate}, theme::strip_colors, utils::repair::{describe_error, repair, Fix}};
+
+/// How many lines to show before and after the line of the error.
+pub const CONTEXT_LINES: usize = 3;
+
+struct RepairScreen<'a> {
+    text: &'a str,
+    error: &'a serde_json::Error,
+    /// The repaired document and what was changed, once a repair was asked for.
+    repaired: Option<Result<(String, Vec<Fix>), String>>,
+    preview_scroll: u16,
+}
+
+impl RepairScreen<'_> {
+    fn draw(&self, frame: &mut Frame) {
+        let error_lines = self.error_lines();
+
+        let fixes_height = match &self.repaired {
+            Some(Ok((_, fixes))) => fixes.len().min(8) as u16 + 2,
+            Some(Err(_)) => 3,
+            None => 0,
+        };
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(error_lines.len() as u16 + 2),
+                Constraint::Length(fixes_height),
+                Constraint::Min(0),
+                Constraint::Length(3),
+            ])
+            .split(frame.area());
+
+        frame.render_widget(
+            Paragraph::new(error_lines).block(Block::default().title(translate(" Invalid JSON ")).borders(Borders::ALL).padding(Padding::horizontal(1))),
+            layout[0],
+        );
+
+        match &self.repaired {
+            Some(Ok((text, fixes))) => {
+                let mut lines: Vec<Line> = fixes
+                    .iter()
+                    .take(8)
+                    .map(|fix| Line::from(tr!("Line {}: {}", fix.line, fix.description)))
+                    .collect();
+                if fixes.len() > 8 {
+                    lines[7] = Line::from(tr!("...and {} more", fixes.len() - 7));
+                }
+
+                frame.render_widget(
+                    Paragraph::new(lines).block(Block::default().title(tr!(" Fixes ({}) ", fixes.len())).borders(Borders::ALL).padding(Padding::horizontal(1))),
+                    layout[1],
+                );
+                frame.render_widget(
+                    Paragraph::new(text.as_str())
+                        .scroll((self.preview_scroll, 0))
+                        .block(Block::default().title(translate(" Repaired document ")).borders(Borders::ALL).padding(Padding::horizontal(1))),
+                    layout[2],
+                );
+            }
+            Some(Err(message)) => {
+                frame.render_widget(
+                    Paragraph::new(message.as_str().red()).block(Block::default().borders(Borders::ALL).padding(Padding::horizontal(1))),
+                    layout[1],
+                );
+            }
+            None => {}
+        }
+
+        let keys = if matches!(self.repaired, Some(Ok(_))) {
+            translate("(enter) to open the repaired document, (j/k) to scroll, (q) to quit")
+        } else {
+            translate("(r) to try repairing it, (q) to quit")
+        };
+        frame.render_widget(
+            Paragraph::new(keys).block(Block::default().borders(Borders::ALL).padding(Padding::left(1))),
+            layout[3],
+        );
+    }
+
+    /// The error, the lines around it with a caret under where it is, and what commonly causes it.
+    fn error_lines(&self) -> Vec<Line<'_>> {
+        let mut lines = vec![
+            Line::from(self.error.to_string().red()),
+            Line::from(""),
+        ];
+
+        let excerpt = SourceExcerpt::new(self.text, self.error.line(), self.error.column(), CONTEXT_LINES);
+        let number_width = excerpt.number_width();
+
+        for (number, source) in &excerpt.lines {
+            let source = source.replace('\t', " ");
+            let gutter = Span::styled(format!("{:>width$} | ", number, width = number_width), Style::default().fg(Color::DarkGray));
+
+            if *number != excerpt.error_line {
+                lines.push(Line::from(vec![gutter, Span::from(source)]));
+                continue;
+            }
+
+            lines.push(Line::from(vec![gutter, Span::from(source).bold()]));
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:>width$} | ", "", width = number_width), Style::default().fg(Color::DarkGray)),
+                Span::from(format!("{}^", " ".repeat(excerpt.caret_offset))).red().bold(),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(describe_error(self.error)));
+
+        return lines;
+    }
+}
+
+/// Shows the error until the user either accepts a repaired version of the document, which is
+/// given back, or quits.
+pub fn run(terminal: &mut DefaultTerminal, text: &str, error: &serde_json::Error, no_color: bool) -> io::Result<Option<String>> {
+    let mut screen = RepairScreen {
+        text,
+        error,
+        repaired: None,
+        preview_scroll: 0,
+    };
+
+    loop {
+        terminal.draw(|frame| {
+            screen.draw(frame);
+            if no_color {
+                strip_colors(frame.buffer_mut());
+            }
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Char('q') | KeyCode::Esc) | (KeyModifiers::CONTROL, KeyCode::Char('c')) => return Ok(None),
+            (_, KeyCode::Char('r')) if screen.repaired.is_none() => {
+                screen.repaired = Some(repair(text));
+            }
+            (_, KeyCode::Enter) => {
+                if let Some(Ok((repaired, _))) = &screen.repaired {
+                    return Ok(Some(repaired.clone()));
+                }
+            }
+            (_, KeyCode::Char('j') | KeyCode::Down) => screen.preview_scroll = screen.preview_scroll.saturating_add(1),
+            (_, KeyCode::Char('k') | KeyCode::Up) => screen.preview_scroll = screen.preview_scroll.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,551 @@
+//
+// Validating the document against a JSON Schema, given with `--schema` or named by the `$schema`
+// key of the document. The document is validated again after every change, and what's wrong is
+// listed in the problems panel and marked in the tree.
+//
+
+use std::{collections::{HashMap, HashSet}, fmt, fs, path::Path, time::Duration};
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{app::{App, CurrentlyEditing, EditingMode, Problem, ProblemKind, ReportedMessageKinds}, i18n::tr, utils::{http, json::{get_line_at_path, get_path_at_line, path_to_pointer, pointer_to_path, PathSegment}}, widgets::text_input::InputMask};
+
+/// How deep `$ref`s and `allOf`/`anyOf`/`oneOf` are followed, so that recursive schemas end.
+const MAX_SUBSCHEMA_DEPTH: usize = 16;
+/// The keywords combining subschemas that are followed to find what applies to a value.
+const COMBINING_KEYWORDS: [&str; 3] = ["allOf", "anyOf", "oneOf"];
+/// Only these apply for sure: one branch of an `anyOf` or `oneOf` is enough.
+const COMBINING_KEYWORDS_THAT_ALL_APPLY: [&str; 1] = ["allOf"];
+
+/// What saving does when the document doesn't match its schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaOnSave {
+    /// Save, and tell how many errors there are.
+    #[default]
+    Warn,
+    /// Refuse to save, unless with `:w!`.
+    Block,
+}
+
+pub struct Schema {
+    /// Where the schema was loaded from.
+    pub source: String,
+    /// The schema itself, to look up what it says about a place of the document.
+    root: Value,
+    validator: jsonschema::Validator,
+}
+
+impl fmt::Debug for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_struct("Schema").field("source", &self.source).finish_non_exhaustive();
+    }
+}
+
+impl Schema {
+    /// Loads a schema from a file or an http(s) URL. Relative paths are relative to `base_dir`.
+    pub fn load(source: &str, base_dir: &Path) -> Result<Schema, String> {
+        let content = if http::is_url(source) {
+            http::fetch(source)?
+        } else {
+            let path = base_dir.join(source);
+            fs::read(&path).map_err(|err| tr!("Failed to read the schema {}: {}", path.display(), err))?
+        };
+
+        let schema: Value = serde_json::from_slice(&content).map_err(|err| tr!("Failed to parse the schema {}: {}", source, err))?;
+
+        return Schema::from_value(source, schema);
+    }
+
+    pub fn from_value(source: &str, schema: Value) -> Result<Schema, String> {
+        let validator = jsonschema::validator_for(&schema).map_err(|err| tr!("Invalid schema {}: {}", source, err))?;
+
+        return Ok(Schema {
+            source: source.to_string(),
+            root: schema,
+            validator,
+        });
+    }
+
+    /// The values the schema allows at a path of the document, from its `enum`s and `const`s.
+    /// Empty if the schema doesn't restrict the value to a list.
+    pub fn allowed_values(&self, path: &[PathSegment]) -> Vec<Value> {
+        let mut values: Vec<Value> = vec![];
+        for schema in self.schemas_at(path) {
+            let allowed = match (schema.get("enum"), schema.get("const")) {
+                (Some(Value::Array(allowed)), _) => allowed.clone(),
+                (_, Some(allowed)) => vec![allowed.clone()],
+                _ => continue,
+            };
+
+            for value in allowed {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+        }
+
+        return values;
+    }
+
+    /// The keys the object at the path must have. Those of `anyOf` and `oneOf` branches are left
+    /// out, as any of the branches may be the one the object matches.
+    pub fn required_keys(&self, path: &[PathSegment]) -> Vec<String> {
+        let mut keys: Vec<String> = vec![];
+        let described_by = match path.split_last() {
+            Some((segment, parent_path)) => self.schemas_at(parent_path)
+                .into_iter()
+                .flat_map(|schema| child_schemas(schema, segment))
+                .collect(),
+            None => vec![&self.root],
+        };
+        let schemas = described_by
+            .into_iter()
+            .flat_map(|schema| self.expand(schema, &COMBINING_KEYWORDS_THAT_ALL_APPLY, 0));
+
+        for schema in schemas {
+            for key in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+                if !keys.iter().any(|known| known == key) {
+                    keys.push(key.to_string());
+                }
+            }
+        }
+
+        return keys;
+    }
+
+    /// What can be typed for the value at the path: only the characters of numbers when its
+    /// `type` only allows numbers, or else what its `pattern` flags.
+    pub fn value_mask(&self, path: &[PathSegment]) -> Option<InputMask> {
+        let schemas = self.schemas_at(path);
+        let types: Vec<&str> = schemas
+            .iter()
+            .filter_map(|schema| schema.get("type"))
+            .flat_map(|types| match types {
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => types.as_str().into_iter().collect::<Vec<_>>(),
+            })
+            .collect();
+
+        if !types.is_empty() && types.iter().all(|kind| *kind == "integer") {
+            return Some(InputMask::Chars(is_integer_char));
+        }
+        if !types.is_empty() && types.iter().all(|kind| matches!(*kind, "integer" | "number")) {
+            return Some(InputMask::Chars(is_number_char));
+        }
+
+        return schemas
+            .iter()
+            .find_map(|schema| schema.get("pattern")?.as_str().and_then(|pattern| Regex::new(pattern).ok()))
+            .map(InputMask::Pattern);
+    }
+
+    /// What the keys of the object at the path are flagged by: the `pattern` of its `propertyNames`.
+    pub fn key_mask(&self, path: &[PathSegment]) -> Option<InputMask> {
+        return self.schemas_at(path)
+            .iter()
+            .find_map(|schema| schema.get("propertyNames")?.get("pattern")?.as_str().and_then(|pattern| Regex::new(pattern).ok()))
+            .map(InputMask::Pattern);
+    }
+
+    /// The schemas describing the value at the path.
+    fn schemas_at(&self, path: &[PathSegment]) -> Vec<&Value> {
+        let mut schemas = self.expand(&self.root, &COMBINING_KEYWORDS, 0);
+        for segment in path {
+            schemas = schemas
+                .into_iter()
+                .flat_map(|schema| child_schemas(schema, segment))
+                .flat_map(|schema| self.expand(schema, &COMBINING_KEYWORDS, 0))
+                .collect();
+        }
+
+        return schemas;
+    }
+
+    /// The schema with the ones it refers to (`$ref`) or is made of with the given keywords.
+    /// Only references within the schema itself are followed.
+    fn expand<'a>(&'a self, schema: &'a Value, keywords: &[&str], depth: usize) -> Vec<&'a Value> {
+        if depth > MAX_SUBSCHEMA_DEPTH {
+            return vec![];
+        }
+
+        let mut schemas = vec![schema];
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str)
+            && let Some(pointer) = reference.strip_prefix('#')
+            && let Some(referenced) = self.root.pointer(pointer) {
+            schemas.extend(self.expand(referenced, keywords, depth + 1));
+        }
+
+        for keyword in keywords {
+            if let Some(Value::Array(subschemas)) = schema.get(keyword) {
+                for subschema in subschemas {
+                    schemas.extend(self.expand(subschema, keywords, depth + 1));
+                }
+            }
+        }
+
+        return schemas;
+    }
+
+    /// Lists where the document doesn't match the schema, as JSON Pointers with what's wrong.
+    pub fn errors(&self, json: &Value) -> Vec<(String, String)> {
+        return self.validator
+            .iter_errors(json)
+            .map(|error| (error.instance_path().as_str().to_string(), error.to_string()))
+            .collect();
+    }
+}
+
+/// The schemas a schema gives to the value at one step into it.
+fn child_schemas<'a>(schema: &'a Value, segment: &PathSegment) -> Vec<&'a Value> {
+    let child = match segment {
+        PathSegment::Key(key) => schema
+            .get("properties")
+            .and_then(|properties| properties.get(key))
+            .or_else(|| schema.get("additionalProperties").filter(|schema| schema.is_object())),
+        PathSegment::Index(index) => match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix_items)), items) if *index < prefix_items.len() || items.is_none() => prefix_items.get(*index),
+            // Before draft 2020-12, an array of `items` described the elements by position.
+            (_, Some(Value::Array(items))) => items
+                .get(*index)
+                .or_else(|| schema.get("additionalItems").filter(|schema| schema.is_object())),
+            (_, items) => items.filter(|schema| schema.is_object()),
+        },
+    };
+
+    return child.into_iter().collect();
+}
+
+fn is_integer_char(char: char) -> bool {
+    return char.is_ascii_digit() || char == '-';
+}
+
+fn is_number_char(char: char) -> bool {
+    return char.is_ascii_digit() || matches!(char, '-' | '+' | '.' | 'e' | 'E');
+}
+
+/// How an allowed value is written in the value input.
+pub fn value_choice_label(value: &Value) -> String {
+    return match value {
+        Value::String(string) => string.clone(),
+        _ => value.to_string(),
+    };
+}
+
+impl App {
+    /// Validates the document against the schema, replacing the schema problems found before.
+    pub fn validate_against_schema(&mut self) {
+        let Some(schema) = &self.schema else {
+            return;
+        };
+
+        let errors = schema.errors(&self.json);
+
+        self.problems.retain(|problem| problem.kind != ProblemKind::Schema);
+        for (pointer, message) in errors {
+            self.problems.push(Problem {
+                kind: ProblemKind::Schema,
+                path: pointer_to_path(&pointer, &self.json).unwrap_or_default(),
+                message,
+            });
+        }
+
+        self.selected_problem = self.selected_problem.min(self.problems.len().saturating_sub(1));
+    }
+
+    /// How many schema errors the document has.
+    pub fn schema_errors_count(&self) -> usize {
+        return self.problems.iter().filter(|problem| problem.kind == ProblemKind::Schema).count();
+    }
+
+    /// The lines of the tree holding values that don't match the schema.
+    pub fn schema_error_lines(&self) -> HashSet<usize> {
+        return self.problems
+            .iter()
+            .filter(|problem| problem.kind == ProblemKind::Schema)
+            .filter_map(|problem| get_line_at_path(&problem.path, &self.json))
+            .collect();
+    }
+
+    /// What the schema requires of the objects of the tree: the lines of the keys it requires, and
+    /// the lines of the objects missing some, with the keys they miss.
+    pub fn required_keys_marks(&self) -> (HashSet<usize>, HashMap<usize, Vec<String>>) {
+        fn walk(
+            schema: &Schema,
+            value: &Value,
+            path: &mut Vec<PathSegment>,
+            line: &mut usize,
+            required_lines: &mut HashSet<usize>,
+            missing_keys: &mut HashMap<usize, Vec<String>>,
+        ) {
+            // The root has no line of its own.
+            let value_line = line.checked_sub(1);
+
+            match value {
+                Value::Object(map) => {
+                    let required = schema.required_keys(path);
+                    let missing: Vec<String> = required.iter().filter(|key| !map.contains_key(*key)).cloned().collect();
+                    if let Some(value_line) = value_line && !missing.is_empty() {
+                        missing_keys.insert(value_line, missing);
+                    }
+
+                    for (key, child) in map {
+                        if required.contains(key) {
+                            required_lines.insert(*line);
+                        }
+                        *line += 1;
+                        path.push(PathSegment::Key(key.clone()));
+                        walk(schema, child, path, line, required_lines, missing_keys);
+                        path.pop();
+                    }
+                }
+                Value::Array(values) => {
+                    for (index, child) in values.iter().enumerate() {
+                        *line += 1;
+                        path.push(PathSegment::Index(index));
+                        walk(schema, child, path, line, required_lines, missing_keys);
+                        path.pop();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut required_lines = HashSet::new();
+        let mut missing_keys = HashMap::new();
+        if let Some(schema) = &self.schema {
+            walk(schema, &self.json, &mut vec![], &mut 0, &mut required_lines, &mut missing_keys);
+        }
+
+        return (required_lines, missing_keys);
+    }
+
+    /// Loads the schema and validates the document against it. Failing to load it is reported,
+    /// and the document is then used without one.
+    pub fn set_schema(&mut self, source: &str, base_dir: &Path) {
+        match Schema::load(source, base_dir) {
+            Ok(schema) => {
+                self.schema = Some(schema);
+                self.validate_against_schema();
+
+                let errors_count = self.schema_errors_count();
+                if errors_count > 0 {
+                    self.report(
+                        tr!("The document has {} schema error(s). Use :problems to list them", errors_count),
+                        ReportedMessageKinds::Warning,
+                        Duration::from_secs(5),
+                    );
+                }
+            }
+            Err(message) => self.report(message, ReportedMessageKinds::Error, Duration::from_secs(5)),
+        }
+    }
+
+    /// The path the value in the edit popup is written to, with the key as it's typed.
+    fn edited_value_path(&mut self) -> Option<Vec<PathSegment>> {
+        let (mut path, index) = if self.is_root_empty() {
+            (vec![], 0)
+        } else {
+            let mut path = get_path_at_line(self.line_at_cursor_without_empty_lines(), &self.json)?;
+            let index = match path.pop()? {
+                PathSegment::Index(index) if self.editing_mode == EditingMode::Inserting => index + 1,
+                PathSegment::Index(index) => index,
+                PathSegment::Key(_) => 0,
+            };
+            (path, index)
+        };
+
+        if self.json.pointer(&path_to_pointer(&path))?.is_array() {
+            path.push(PathSegment::Index(index));
+        } else {
+            path.push(PathSegment::Key(self.key_input.content().to_string()));
+        }
+
+        return Some(path);
+    }
+
+    /// Looks up the values the schema allows for the value being edited. When it restricts them
+    /// to a list, the value is picked from it instead of typed.
+    pub fn refresh_value_choices(&mut self) {
+        self.value_choices.clear();
+        self.selected_choice = 0;
+
+        if self.schema.is_none() || self.currently_editing != Some(CurrentlyEditing::Value) {
+            return;
+        }
+
+        let Some(path) = self.edited_value_path() else {
+            return;
+        };
+        let choices = self.schema.as_ref().map(|schema| schema.allowed_values(&path)).unwrap_or_default();
+        if choices.is_empty() {
+            return;
+        }
+
+        self.selected_choice = choices
+            .iter()
+            .position(|choice| value_choice_label(choice) == self.value_input.content())
+            .unwrap_or(0);
+        self.value_choices = choices;
+        self.value_input.set_content(value_choice_label(&self.value_choices[self.selected_choice]));
+    }
+
+    /// Restricts or flags what's typed in the edit popup by what the schema says of the key and
+    /// of the value.
+    pub fn refresh_input_masks(&mut self) {
+        let path = self.edited_value_path().filter(|_| self.schema.is_some());
+        let (key_mask, value_mask) = match (&self.schema, path) {
+            (Some(schema), Some(path)) => (schema.key_mask(&path[..path.len() - 1]), schema.value_mask(&path)),
+            _ => (None, None),
+        };
+
+        self.key_input.set_mask(key_mask);
+        self.value_input.set_mask(value_mask);
+    }
+
+    /// Whether the value is being picked from the values the schema allows.
+    pub fn is_choosing_value(&self) -> bool {
+        return !self.value_choices.is_empty() && self.currently_editing == Some(CurrentlyEditing::Value);
+    }
+
+    /// Moves the selection through the allowed values, wrapping around.
+    pub fn select_value_choice(&mut self, forward: bool) {
+        if !self.is_choosing_value() {
+            return;
+        }
+
+        let count = self.value_choices.len();
+        self.selected_choice = if forward { (self.selected_choice + 1) % count } else { (self.selected_choice + count - 1) % count };
+        self.value_input.set_content(value_choice_label(&self.value_choices[self.selected_choice]));
+    }
+
+    /// The allowed value that was picked, if the value input still holds it.
+    pub fn chosen_value(&self) -> Option<Value> {
+        return self.value_choices
+            .get(self.selected_choice)
+            .filter(|choice| value_choice_label(choice) == self.value_input.content())
+            .cloned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_against_schema() {
+        let schema_path = std::env::temp_dir().join(format!("todd_schema_test_{}.json", std::process::id()));
+        fs::write(&schema_path, json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "ports": { "type": "array", "items": { "type": "integer" } }
+            }
+        }).to_string()).unwrap();
+
+        let mut app = App::default();
+        app.json = json!({
+            "name": 1,   // 0
+            "ports": [   // 1
+                80,      // 2
+                "443"    // 3
+            ]
+        });
+        app.set_schema(schema_path.to_str().unwrap(), Path::new(""));
+        fs::remove_file(&schema_path).unwrap();
+
+        assert_eq!(app.schema_errors_count(), 2);
+        assert_eq!(app.schema_error_lines(), HashSet::from([0, 3]));
+
+        app.json["name"] = json!("todd");
+        app.json["ports"][1] = json!(443);
+        app.validate_against_schema();
+
+        assert_eq!(app.schema_errors_count(), 0);
+    }
+
+    #[test]
+    fn test_allowed_values() {
+        let schema = Schema::from_value("test", json!({
+            "type": "object",
+            "properties": {
+                "level": { "enum": ["debug", "info", "error"] },
+                "mode": { "$ref": "#/$defs/mode" },
+                "targets": {
+                    "type": "array",
+                    "prefixItems": [{ "const": "stdout" }],
+                    "items": { "anyOf": [{ "const": 1 }, { "const": 2 }] }
+                },
+                "name": { "type": "string" }
+            },
+            "$defs": {
+                "mode": { "oneOf": [{ "const": "fast" }, { "const": "safe" }] }
+            }
+        })).unwrap();
+
+        let key = |k: &str| PathSegment::Key(k.to_string());
+
+        assert_eq!(schema.allowed_values(&[key("level")]), vec![json!("debug"), json!("info"), json!("error")]);
+        assert_eq!(schema.allowed_values(&[key("mode")]), vec![json!("fast"), json!("safe")]);
+        assert_eq!(schema.allowed_values(&[key("targets"), PathSegment::Index(0)]), vec![json!("stdout")]);
+        assert_eq!(schema.allowed_values(&[key("targets"), PathSegment::Index(3)]), vec![json!(1), json!(2)]);
+        assert_eq!(schema.allowed_values(&[key("name")]), Vec::<Value>::new());
+        assert_eq!(schema.allowed_values(&[key("missing")]), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn test_input_masks() {
+        let schema = Schema::from_value("test", json!({
+            "properties": {
+                "port": { "type": "integer" },
+                "ratio": { "type": ["number", "integer"] },
+                "mixed": { "type": ["number", "string"] },
+                "name": { "type": "string", "pattern": "^[a-z]+$" },
+                "env": { "type": "object", "propertyNames": { "pattern": "^[A-Z_]+$" } }
+            }
+        })).unwrap();
+        let path = |key: &str| vec![PathSegment::Key(key.to_string())];
+        let accepts = |mask: Option<InputMask>, char: char| match mask {
+            Some(InputMask::Chars(accepts)) => accepts(char),
+            _ => true,
+        };
+
+        assert!(!accepts(schema.value_mask(&path("port")), '.'));
+        assert!(accepts(schema.value_mask(&path("ratio")), '.'));
+        assert!(!accepts(schema.value_mask(&path("ratio")), 'x'));
+        assert!(schema.value_mask(&path("mixed")).is_none());
+        assert!(matches!(schema.value_mask(&path("name")), Some(InputMask::Pattern(pattern)) if pattern.is_match("abc")));
+        assert!(matches!(schema.key_mask(&path("env")), Some(InputMask::Pattern(pattern)) if !pattern.is_match("path")));
+        assert!(schema.key_mask(&[]).is_none());
+    }
+
+    #[test]
+    fn test_required_keys() {
+        let mut app = App::default();
+        app.json = json!({ "users": [{ "id": 1, "name": "a" }, { "name": "b" }], "version": 2 });
+        app.schema = Some(Schema::from_value("test", json!({
+            "required": ["users"],
+            "properties": {
+                "users": { "items": { "$ref": "#/$defs/user" } }
+            },
+            "$defs": {
+                "user": {
+                    "allOf": [{ "required": ["id"] }],
+                    "anyOf": [{ "required": ["name"] }, { "required": ["email"] }]
+                }
+            }
+        })).unwrap());
+
+        let schema = app.schema.as_ref().unwrap();
+        assert_eq!(schema.required_keys(&[]), vec!["users"]);
+        assert_eq!(schema.required_keys(&[PathSegment::Key("users".to_string()), PathSegment::Index(1)]), vec!["id"]);
+
+        let (required_lines, missing_keys) = app.required_keys_marks();
+        assert_eq!(required_lines, HashSet::from([0, 2]));
+        assert_eq!(missing_keys, HashMap::from([(4, vec!["id".to_string()])]));
+    }
+}
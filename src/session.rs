@@ -0,0 +1,173 @@
+//
+// Per-file view state (cursor and scroll) that is restored the next time the same file is opened.
+//
+
+use std::{fs, io, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{app::App, config::state_dir, utils::json::{get_line_at_path, get_path_at_line, PathSegment}};
+
+/// How many files to remember sessions for. The least recently closed ones are dropped first.
+const MAX_SESSIONS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// The path to the value under the cursor. Survives the file changing in between sessions.
+    pub cursor_path: Vec<PathSegment>,
+    /// The cursor line, used when the value at `cursor_path` doesn't exist anymore.
+    pub cursor_line: usize,
+    pub scroll: usize,
+}
+
+fn sessions_file() -> Option<PathBuf> {
+    return state_dir().map(|dir| dir.join("sessions.json"));
+}
+
+/// Sessions are stored by absolute path so that the same file opened from different
+/// directories shares its session.
+fn session_key(file_path: &Path) -> String {
+    return fs::canonicalize(file_path)
+        .unwrap_or_else(|_| file_path.to_path_buf())
+        .to_string_lossy()
+        .to_string();
+}
+
+fn read_sessions(sessions_file: &Path) -> Map<String, Value> {
+    return fs::read_to_string(sessions_file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+}
+
+pub fn load_session(file_path: &Path) -> Option<Session> {
+    let mut sessions = read_sessions(&sessions_file()?);
+    let session = sessions.remove(&session_key(file_path))?;
+
+    return serde_json::from_value(session).ok();
+}
+
+pub fn save_session(file_path: &Path, session: &Session) -> io::Result<()> {
+    let Some(sessions_file) = sessions_file() else {
+        return Ok(());
+    };
+
+    let mut sessions = read_sessions(&sessions_file);
+
+    // Re-insert the session at the end to keep the map ordered from least to most recently closed.
+    let key = session_key(file_path);
+    sessions.shift_remove(&key);
+    sessions.insert(key, serde_json::to_value(session)?);
+
+    let stale_keys: Vec<String> = sessions.keys().take(sessions.len().saturating_sub(MAX_SESSIONS)).cloned().collect();
+    for stale_key in stale_keys {
+        sessions.shift_remove(&stale_key);
+    }
+
+    if let Some(dir) = sessions_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    return fs::write(sessions_file, serde_json::to_string_pretty(&sessions)?);
+}
+
+impl App {
+    /// Moves the cursor and scroll to where they were when the file was last closed.
+    pub fn restore_session(&mut self) {
+        if !self.config.restore_session {
+            return;
+        }
+
+        let Some(session) = self.file_path.as_deref().and_then(load_session) else {
+            return;
+        };
+
+        let line = get_line_at_path(&session.cursor_path, &self.json)
+            .or_else(|| get_path_at_line(session.cursor_line, &self.json).map(|_| session.cursor_line));
+
+        if let Some(line) = line {
+            self.line_at_cursor = line;
+            // Keep the cursor in view even if the file changed in between.
+            self.vertical_scroll = session.scroll.min(line);
+            self.scrolled_so_far = self.vertical_scroll;
+            self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        }
+    }
+
+    /// Remembers the cursor and scroll position of the file for the next time it's opened.
+    pub fn save_session(&self) {
+        if !self.config.restore_session {
+            return;
+        }
+
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+
+        let Some(cursor_path) = get_path_at_line(self.line_at_cursor, &self.json) else {
+            return;
+        };
+
+        let session = Session {
+            cursor_path,
+            cursor_line: self.line_at_cursor,
+            scroll: self.vertical_scroll,
+        };
+
+        // Failing to remember the session shouldn't get in the way of quitting.
+        let _ = save_session(file_path, &session);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Once;
+    use ratatui::layout::Size;
+
+    /// Keeps the sessions of the tests out of the real state directory.
+    fn use_temp_state_dir() {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            let dir = std::env::temp_dir().join(format!("todd_state_test_{}", std::process::id()));
+            // SAFETY: Only these tests go through the state directory, and it's only ever set here, once.
+            unsafe { std::env::set_var("XDG_STATE_HOME", dir) };
+        });
+    }
+
+    #[test]
+    fn test_save_and_load_session() {
+        use_temp_state_dir();
+        let file_path = std::env::temp_dir().join("todd_session_round_trip.json");
+
+        let session = Session {
+            cursor_path: vec![PathSegment::Key("list".to_string()), PathSegment::Index(1)],
+            cursor_line: 3,
+            scroll: 2,
+        };
+        save_session(&file_path, &session).unwrap();
+
+        assert_eq!(load_session(&file_path), Some(session));
+        assert_eq!(load_session(&std::env::temp_dir().join("todd_session_never_saved.json")), None);
+    }
+
+    #[test]
+    fn test_restoring_a_session_falls_back_to_the_line() {
+        use_temp_state_dir();
+        let file_path = std::env::temp_dir().join("todd_session_fallback.json");
+
+        // The key under the cursor was renamed in between sessions.
+        save_session(&file_path, &Session {
+            cursor_path: vec![PathSegment::Key("renamed".to_string())],
+            cursor_line: 2,
+            scroll: 1,
+        }).unwrap();
+
+        let mut app = App::new(r#"{ "a": 1, "b": 2, "c": 3 }"#, None, Some(file_path), Size::default()).unwrap();
+        app.restore_session();
+
+        assert_eq!(app.line_at_cursor, 2);
+        assert_eq!(app.vertical_scroll, 1);
+    }
+}
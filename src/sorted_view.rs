@@ -0,0 +1,64 @@
+//
+// Showing the document sorted, without changing it: the tree shows a sorted copy while the
+// document itself is set aside, untouched, and is what gets saved.
+//
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::{app::{App, ReportedMessageKinds}, i18n::tr, utils::json::{get_line_at_path, get_path_at_line, sorted, PathSegment}};
+
+impl App {
+    /// The document as it is, even while the tree shows it sorted.
+    pub fn document(&self) -> &Value {
+        return self.unsorted_json.as_ref().unwrap_or(&self.json);
+    }
+
+    /// Shows the keys of the objects sorted alphabetically and, given a field, the arrays of
+    /// objects sorted by it.
+    pub fn sort_view(&mut self, field: Option<&str>) {
+        let cursor_path = get_path_at_line(self.line_at_cursor, &self.json);
+
+        let document = self.unsorted_json.take().unwrap_or_else(|| self.json.clone());
+        self.json = sorted(&document, field);
+        self.unsorted_json = Some(document);
+        self.move_cursor_to_path(cursor_path);
+
+        let message = match field {
+            Some(field) => tr!("Showing keys sorted, and arrays sorted by \"{}\". The file is unchanged. Use :unsort to go back", field),
+            None => tr!("Showing keys sorted. The file is unchanged. Use :unsort to go back"),
+        };
+        self.report(message, ReportedMessageKinds::Info, Duration::from_secs(5));
+    }
+
+    /// Shows the document in its own order again.
+    pub fn unsort_view(&mut self) {
+        let Some(document) = self.unsorted_json.take() else {
+            self.report(tr!("The view isn't sorted"), ReportedMessageKinds::Info, Duration::from_secs(2));
+            return;
+        };
+
+        let cursor_path = get_path_at_line(self.line_at_cursor, &self.json);
+        self.json = document;
+        self.move_cursor_to_path(cursor_path);
+    }
+
+    /// Refuses changes while the view is sorted, since they'd be made to the sorted copy.
+    /// Returns whether the document can be changed.
+    pub fn check_editable(&self) -> bool {
+        if self.unsorted_json.is_none() {
+            return true;
+        }
+
+        self.report(tr!("The view is sorted. Use :unsort to make changes"), ReportedMessageKinds::Error, Duration::from_secs(3));
+        return false;
+    }
+
+    /// Keeps the cursor on the same key when the tree is rearranged.
+    fn move_cursor_to_path(&mut self, path: Option<Vec<PathSegment>>) {
+        if let Some(line) = path.and_then(|path| get_line_at_path(&path, &self.json)) {
+            self.move_cursor_to_line(line);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//
+// The status line at the bottom right, made of segments picked and ordered by a format string of
+// the config, like `{file}{dirty} | {path} | {line}/{lines}`.
+//
+
+use ratatui::{style::Style, text::Span};
+use serde::Deserialize;
+
+use crate::{app::{App, CurrentScreen, EditingMode, FileStatus}, i18n::{tr, translate}, utils::json::{get_path_at_line, PathSegment}};
+
+/// The names of the segments, as written between braces.
+pub const SEGMENT_NAMES: [&str; 10] = ["file", "size", "length", "lines", "line", "path", "dirty", "sorted", "status", "mode"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// The name of the opened file.
+    File,
+    /// The size of the opened file.
+    Size,
+    /// How many values the root holds.
+    Length,
+    /// How many lines the tree has.
+    Lines,
+    /// The line under the cursor, counted from 1.
+    Line,
+    /// The keys leading to the value under the cursor.
+    Path,
+    /// Whether there are unsaved changes.
+    Dirty,
+    /// Whether the view is sorted.
+    Sorted,
+    /// Whether the file was deleted or emptied outside of todd.
+    Status,
+    /// What the keys currently do: viewing, inserting, searching...
+    Mode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Part {
+    Text(String),
+    Segment(Segment),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusLine {
+    pub parts: Vec<Part>,
+}
+
+impl Default for StatusLine {
+    fn default() -> Self {
+        let format = translate("File size: {size}, Parent length: {length}, Total lines: {lines}, Current line: {line}, {dirty}, {sorted}, {status}");
+        return StatusLine::parse(format).unwrap();
+    }
+}
+
+impl StatusLine {
+    /// Parses a format string: text, and segments written between braces. `{{` and `}}` are braces.
+    pub fn parse(format: &str) -> Result<StatusLine, String> {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut rest = format;
+
+        while let Some(char) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+                text.push(char);
+                rest = after;
+                continue;
+            }
+
+            if char == '{' {
+                let end = rest.find('}').ok_or_else(|| tr!("Unclosed `{` in the status line `{}`", format))?;
+                let name = &rest[1..end];
+                let segment = Segment::named(name).ok_or_else(|| {
+                    tr!("Unknown status line segment `{{}}`. The segments are {}", name, SEGMENT_NAMES.join(", "))
+                })?;
+
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(Part::Segment(segment));
+                rest = &rest[end + 1..];
+                continue;
+            }
+
+            text.push(char);
+            rest = &rest[char.len_utf8()..];
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        return Ok(StatusLine { parts });
+    }
+}
+
+impl Segment {
+    fn named(name: &str) -> Option<Segment> {
+        return match name {
+            "file" => Some(Segment::File),
+            "size" => Some(Segment::Size),
+            "length" => Some(Segment::Length),
+            "lines" => Some(Segment::Lines),
+            "line" => Some(Segment::Line),
+            "path" => Some(Segment::Path),
+            "dirty" => Some(Segment::Dirty),
+            "sorted" => Some(Segment::Sorted),
+            "status" => Some(Segment::Status),
+            "mode" => Some(Segment::Mode),
+            _ => None,
+        };
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusLine {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let format = String::deserialize(deserializer)?;
+        return StatusLine::parse(&format).map_err(serde::de::Error::custom);
+    }
+}
+
+impl App {
+    /// The status line of the config, filled in. The text in front of a segment that has nothing
+    /// to show (like `{dirty}` without unsaved changes) is left out with it.
+    pub fn status_line_spans(&self) -> Vec<Span<'static>> {
+        let mut spans = vec![];
+        let mut pending_text: Option<&str> = None;
+
+        for part in &self.config.status_line.parts {
+            match part {
+                Part::Text(text) => {
+                    if let Some(pending) = pending_text.take() {
+                        spans.push(Span::from(pending.to_string()));
+                    }
+                    pending_text = Some(text);
+                }
+                Part::Segment(segment) => {
+                    let Some(span) = self.segment_span(*segment) else {
+                        pending_text = None;
+                        continue;
+                    };
+
+                    if let Some(pending) = pending_text.take() {
+                        spans.push(Span::from(pending.to_string()));
+                    }
+                    spans.push(span);
+                }
+            }
+        }
+        if let Some(pending) = pending_text {
+            spans.push(Span::from(pending.to_string()));
+        }
+
+        return spans;
+    }
+
+    fn segment_span(&self, segment: Segment) -> Option<Span<'static>> {
+        let theme = &self.config.theme;
+
+        return match segment {
+            Segment::File => {
+                let name = self.file_path.as_ref().and_then(|path| path.file_name())?;
+                Some(Span::from(name.to_string_lossy().to_string()))
+            }
+            Segment::Size => Some(Span::from(match &self.file_metadata {
+                Some(metadata) if metadata.len() > 1024 => tr!("{} KB", metadata.len() / 1024),
+                Some(metadata) => tr!("{} Bytes", metadata.len()),
+                None => tr!("N/A"),
+            })),
+            Segment::Length => {
+                let length = match &self.json {
+                    serde_json::Value::Array(values) => values.len(),
+                    serde_json::Value::Object(map) => map.len(),
+                    _ => 0,
+                };
+                Some(Span::from(length.to_string()))
+            }
+            Segment::Lines => Some(Span::from(self.lines_count.to_string())),
+            Segment::Line => Some(Span::from(self.line_at_cursor.saturating_add(1).to_string())),
+            Segment::Path => {
+                let path = get_path_at_line(self.line_at_cursor, &self.json)?;
+                let keys: Vec<String> = path
+                    .iter()
+                    .map(|segment| match segment {
+                        PathSegment::Key(key) => key.clone(),
+                        PathSegment::Index(index) => format!("[{}]", index),
+                    })
+                    .collect();
+                Some(Span::styled(keys.join(" › "), Style::default().fg(theme.accent)))
+            }
+            Segment::Dirty => self.is_dirty.then(|| Span::styled(translate("Unsaved changes"), Style::default().fg(theme.warning))),
+            Segment::Sorted => self.unsorted_json.is_some().then(|| Span::styled(translate("Sorted view"), Style::default().fg(theme.accent))),
+            Segment::Status => match self.file_status {
+                FileStatus::Present | FileStatus::New => None,
+                FileStatus::Deleted => Some(Span::styled(translate("File deleted outside of todd"), Style::default().fg(theme.error))),
+                FileStatus::Truncated => Some(Span::styled(translate("File emptied outside of todd"), Style::default().fg(theme.error))),
+            },
+            Segment::Mode => Some(Span::from(match self.current_screen {
+                CurrentScreen::ViewingFile if self.visual_anchor.is_some() => translate("VISUAL"),
+                CurrentScreen::ViewingFile => translate("VIEW"),
+                CurrentScreen::Editing if self.editing_mode == EditingMode::Editing => translate("EDIT"),
+                CurrentScreen::Editing => translate("INSERT"),
+                CurrentScreen::Searching => translate("SEARCH"),
+                CurrentScreen::Command => translate("COMMAND"),
+                CurrentScreen::Problems => translate("PROBLEMS"),
+                CurrentScreen::Preview => translate("PREVIEW"),
+                CurrentScreen::Table => translate("TABLE"),
+                CurrentScreen::Confirm => translate("CONFIRM"),
+                CurrentScreen::Tutorial => translate("TUTORIAL"),
+            })),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn text(app: &App) -> String {
+        return app.status_line_spans().iter().map(|span| span.content.to_string()).collect();
+    }
+
+    #[test]
+    fn test_parse_status_line() {
+        let status_line = StatusLine::parse("{mode} {{{line}}}").unwrap();
+        assert_eq!(status_line.parts, [
+            Part::Segment(Segment::Mode),
+            Part::Text(" {".to_string()),
+            Part::Segment(Segment::Line),
+            Part::Text("}".to_string()),
+        ]);
+
+        assert!(StatusLine::parse("{line").is_err());
+        assert!(StatusLine::parse("{column}").is_err());
+        assert!(serde_json::from_value::<StatusLine>(json!("{file} | {path}")).is_ok());
+    }
+
+    #[test]
+    fn test_status_line_spans() {
+        let mut app = App::default();
+        app.json = json!({ "users": [{ "name": "Omar" }] });
+        app.lines_count = 3;
+        app.line_at_cursor = 2;
+
+        assert_eq!(text(&app), "File size: N/A, Parent length: 1, Total lines: 3, Current line: 3");
+
+        app.is_dirty = true;
+        assert_eq!(text(&app), "File size: N/A, Parent length: 1, Total lines: 3, Current line: 3, Unsaved changes");
+
+        app.config.status_line = StatusLine::parse("[{mode}] {path} ({line}/{lines}){dirty}").unwrap();
+        assert_eq!(text(&app), "[VIEW] users › [0] › name (3/3)Unsaved changes");
+
+        app.config.status_line = StatusLine::parse("{file} | {path}").unwrap();
+        app.line_at_cursor = 0;
+        assert_eq!(text(&app), " | users");
+    }
+}
@@ -0,0 +1,325 @@
+//
+// Non-interactive subcommands (`todd <subcommand> ...`), for use from scripts.
+//
+
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+use crate::{clipboard::yanked_text, formats::{Concatenation, Decoded, FileFormat, Syntax}, helpers::SourceExcerpt, repair_screen::CONTEXT_LINES, schema::Schema, utils::{duplicates::find_duplicate_keys, json::{parse_path, path_to_pointer, path_to_string}, jsonc::Comments, patch::{get_by_pointer, remove_by_pointer, set_by_pointer}, repair::describe_error}};
+
+pub const CONVERT_USAGE: &str = "Usage: todd convert [--force] [--lenient] <input> <output>";
+pub const GET_USAGE: &str = "Usage: todd get [--json] <file> <path>";
+pub const SET_USAGE: &str = "Usage: todd set <file> <path> <value>";
+pub const DELETE_USAGE: &str = "Usage: todd delete <file> <path>";
+pub const FMT_USAGE: &str = "Usage: todd fmt [--compact] <file>...";
+
+/// A subcommand, run with the arguments after its name.
+pub type Subcommand = fn(&[String]) -> Result<(), String>;
+
+/// The subcommand the first argument names, if it names one.
+pub fn find(name: &str) -> Option<Subcommand> {
+    return match name {
+        "convert" => Some(convert),
+        "get" => Some(get),
+        "set" => Some(set),
+        "delete" => Some(delete),
+        "fmt" => Some(fmt),
+        _ => None,
+    };
+}
+
+/// Reads and parses a document in any of the supported formats, with the format to write it back in.
+fn read_document(path: &str, lenient: bool) -> Result<(Value, FileFormat, Comments), String> {
+    let bytes = fs::read(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+
+    let mut format = FileFormat::detect(Path::new(path), &bytes);
+    format.lenient = lenient;
+    let Decoded { text, comments, .. } = format
+        .decode(bytes)
+        .map_err(|err| format!("Failed to read {}: {}", path, err))?;
+    let json: Value = serde_json::from_str(&text).map_err(|err| {
+        let excerpt = SourceExcerpt::new(&text, err.line(), err.column(), CONTEXT_LINES);
+        return format!("Failed to parse {}: {}\n\n{}\n{}", path, err, excerpt, describe_error(&err));
+    })?;
+
+    return Ok((json, format, comments));
+}
+
+/// Writes the document back to its file in the format it was read in, comments included.
+fn write_document(path: &str, json: &Value, format: &FileFormat, comments: &Comments) -> Result<(), String> {
+    if format.syntax == Syntax::Xml {
+        return Err(format!("{} is XML, which can only be read", path));
+    }
+
+    let content = format.encode(json, comments).map_err(|err| format!("Failed to write {}: {}", path, err))?;
+
+    return fs::write(path, content).map_err(|err| format!("Failed to write {}: {}", path, err));
+}
+
+/// The JSON Pointer of a path given on the command line, written like `.a.b[0]` or as a pointer (`/a/b/0`).
+fn pointer_of(path: &str) -> Result<String, String> {
+    if path.starts_with('/') {
+        return Ok(path.to_string());
+    }
+
+    return parse_path(path).map(|path| path_to_pointer(&path));
+}
+
+/// `todd convert <input> <output>`: converts a document between any of the supported formats,
+/// picked from the extensions (`data.yaml`, `data.json5`, `data.msgpack`, `data.json.gz`...).
+pub fn convert(args: &[String]) -> Result<(), String> {
+    let mut force = false;
+    let mut lenient = false;
+    let mut paths = vec![];
+
+    for argument in args {
+        match argument.as_str() {
+            "--force" | "-f" => force = true,
+            "--lenient" => lenient = true,
+            _ if argument.starts_with('-') => return Err(CONVERT_USAGE.to_string()),
+            _ => paths.push(argument.as_str()),
+        }
+    }
+
+    let [input, output] = paths[..] else {
+        return Err(CONVERT_USAGE.to_string());
+    };
+
+    let (json, _, comments) = read_document(input, lenient)?;
+
+    let output_format = FileFormat::detect(Path::new(output), &[]);
+    if output_format.syntax == Syntax::Xml {
+        return Err("Converting to XML isn't supported. XML can only be read".to_string());
+    }
+
+    if !force && Path::new(output).exists() {
+        return Err(format!("{} already exists. Pass --force to overwrite it", output));
+    }
+
+    let content = output_format
+        .encode(&json, &comments)
+        .map_err(|err| format!("Failed to convert {}: {}", input, err))?;
+
+    return fs::write(output, content).map_err(|err| format!("Failed to write {}: {}", output, err));
+}
+
+/// `todd get <file> <path>`: prints the value at the path, the way `y` copies it: strings without
+/// their quotes, objects and arrays pretty-printed. `--json` prints strings as JSON too.
+pub fn get(args: &[String]) -> Result<(), String> {
+    let mut as_json = false;
+    let mut operands = vec![];
+
+    for argument in args {
+        match argument.as_str() {
+            "--json" => as_json = true,
+            _ => operands.push(argument.as_str()),
+        }
+    }
+
+    let [file, path] = operands[..] else {
+        return Err(GET_USAGE.to_string());
+    };
+
+    let (json, _, _) = read_document(file, false)?;
+    let value = get_by_pointer(&json, &pointer_of(path)?).map_err(|_| format!("No value at {} in {}", path, file))?;
+
+    match (as_json, value) {
+        (true, Value::String(_)) => println!("{}", value),
+        _ => println!("{}", yanked_text(value)),
+    }
+
+    return Ok(());
+}
+
+/// `todd set <file> <path> <value>`: sets the value at the path, adding it when the object or
+/// array doesn't have it yet. The value is read as JSON, and taken as a string when it isn't.
+pub fn set(args: &[String]) -> Result<(), String> {
+    let [file, path, value] = args else {
+        return Err(SET_USAGE.to_string());
+    };
+
+    let (mut json, format, comments) = read_document(file, false)?;
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.clone()));
+    set_by_pointer(&mut json, &pointer_of(path)?, value).map_err(|err| format!("Failed to set {} in {}: {}", path, file, err))?;
+
+    return write_document(file, &json, &format, &comments);
+}
+
+/// `todd delete <file> <path>`: removes the value at the path.
+pub fn delete(args: &[String]) -> Result<(), String> {
+    let [file, path] = args else {
+        return Err(DELETE_USAGE.to_string());
+    };
+
+    let (mut json, format, comments) = read_document(file, false)?;
+    remove_by_pointer(&mut json, &pointer_of(path)?).map_err(|_| format!("No value at {} in {}", path, file))?;
+
+    return write_document(file, &json, &format, &comments);
+}
+
+/// `todd fmt <file>...`: writes the files back the way saving them in todd does, pretty-printed
+/// in their own format with their comments. `--compact` writes JSON files on a single line, or a
+/// line per document when they hold several.
+pub fn fmt(args: &[String]) -> Result<(), String> {
+    let mut compact = false;
+    let mut files = vec![];
+
+    for argument in args {
+        match argument.as_str() {
+            "--compact" => compact = true,
+            _ if argument.starts_with('-') => return Err(FMT_USAGE.to_string()),
+            _ => files.push(argument.as_str()),
+        }
+    }
+
+    if files.is_empty() {
+        return Err(FMT_USAGE.to_string());
+    }
+
+    for file in files {
+        let (json, mut format, comments) = read_document(file, false)?;
+        if compact {
+            if format.syntax != Syntax::Json {
+                return Err(format!("{} isn't a JSON file, --compact only writes JSON", file));
+            }
+            format.compact = true;
+            format.concatenated = format.concatenated.map(|_| Concatenation::OnePerLine);
+        }
+
+        write_document(file, &json, &format, &comments)?;
+    }
+
+    return Ok(());
+}
+
+/// `todd --check <file>`: whether the document parses, has no repeated keys, and matches its
+/// schema (`--schema`, or the `$schema` of the document). The problems are given back one per
+/// line, as `file:line:column: message` where they have a position.
+pub fn check(file: &str, schema_source: Option<&str>, lenient: bool) -> Result<(), String> {
+    let bytes = fs::read(file).map_err(|err| format!("{}: Failed to read: {}", file, err))?;
+
+    let mut format = FileFormat::detect(Path::new(file), &bytes);
+    format.lenient = lenient;
+    format.strict = !lenient;
+    let Decoded { text, .. } = format.decode(bytes).map_err(|err| format!("{}: Failed to read: {}", file, err))?;
+    if text.trim().is_empty() {
+        return Err(format!("{}: File is empty", file));
+    }
+    let json: Value = serde_json::from_str(&text).map_err(|err| {
+        // The position is already in front of the message.
+        let message = err.to_string();
+        let message = message.strip_suffix(&format!(" at line {} column {}", err.line(), err.column())).unwrap_or(&message);
+        return format!("{}:{}:{}: {}. {}", file, err.line(), err.column(), message, describe_error(&err));
+    })?;
+
+    let mut problems: Vec<String> = find_duplicate_keys(&text)
+        .into_iter()
+        .map(|duplicate| format!("{}:{}: Duplicate key {} (first on line {})", file, duplicate.line, path_to_string(&duplicate.path), duplicate.first_line))
+        .collect();
+
+    // A `--schema` is relative to the current directory, a `$schema` to the document.
+    let schema = match schema_source {
+        Some(source) => Some((source, Path::new(""))),
+        None => json.get("$schema").and_then(Value::as_str).map(|source| (source, Path::new(file).parent().unwrap_or(Path::new("")))),
+    };
+    if let Some((schema_source, base_dir)) = schema {
+        let schema = Schema::load(schema_source, base_dir).map_err(|message| format!("{}: {}", file, message))?;
+        problems.extend(schema.errors(&json).into_iter().map(|(pointer, message)| format!("{}: {}: {}", file, if pointer.is_empty() { "/" } else { &pointer }, message)));
+    }
+
+    if !problems.is_empty() {
+        return Err(problems.join("\n"));
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_set_and_delete() {
+        let file_path = std::env::temp_dir().join(format!("todd_subcommands_test_{}.json", std::process::id()));
+        let file = file_path.to_string_lossy().to_string();
+        fs::write(&file_path, r#"{ "a": { "b": [1, 2] } }"#).unwrap();
+
+        let args = |args: &[&str]| args.iter().map(|argument| argument.to_string()).collect::<Vec<String>>();
+        set(&args(&[&file, ".a.b[0]", "42"])).unwrap();
+        set(&args(&[&file, "/a/c", "hello"])).unwrap();
+        delete(&args(&[&file, ".a.b[1]"])).unwrap();
+        assert!(delete(&args(&[&file, ".a.missing"])).is_err());
+        assert!(get(&args(&[&file, ".a.b[5]"])).is_err());
+
+        let (json, _, _) = read_document(&file, false).unwrap();
+        assert_eq!(json, json!({ "a": { "b": [42], "c": "hello" } }));
+
+        fs::remove_file(&file_path).unwrap();
+    }
+
+    #[test]
+    fn test_fmt() {
+        let file_path = std::env::temp_dir().join(format!("todd_fmt_test_{}.json", std::process::id()));
+        let file = file_path.to_string_lossy().to_string();
+        fs::write(&file_path, "{\"a\": [1, 2.50], // kept\n\"b\": {}}").unwrap();
+
+        // Pretty-printed like a save, with the comment of the JSONC file.
+        let args = |args: &[&str]| args.iter().map(|argument| argument.to_string()).collect::<Vec<String>>();
+        fmt(&args(&[&file])).unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "{\n  \"a\": [\n    1,\n    2.50\n  ], // kept\n  \"b\": {}\n}");
+        assert!(fmt(&args(&["--compact", &file])).is_err());
+
+        fs::write(&file_path, "{\n  \"a\": [1, 2.50]\n}").unwrap();
+        fmt(&args(&["--compact", &file])).unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), r#"{"a":[1,2.50]}"#);
+
+        fs::remove_file(&file_path).unwrap();
+    }
+
+    /// The path to `path` from the directory, going up to the root and down again.
+    fn relative_path(from: &Path, path: &Path) -> String {
+        let up = "../".repeat(from.components().count() - 1);
+        return format!("{}{}", up, path.strip_prefix("/").unwrap().display());
+    }
+
+    #[test]
+    fn test_check() {
+        let directory = std::env::temp_dir();
+        let file_path = directory.join(format!("todd_check_test_{}.json", std::process::id()));
+        let file = file_path.to_string_lossy().to_string();
+        // A relative `--schema`, from the current directory and not the document's.
+        let schema_path = directory.join(format!("todd_check_schema_test_{}.json", std::process::id()));
+        fs::write(&schema_path, r#"{ "properties": { "port": { "type": "integer" } } }"#).unwrap();
+        let current_dir = std::env::current_dir().unwrap();
+        let schema = relative_path(&current_dir, &schema_path);
+
+        fs::write(&file_path, r#"{ "port": 80 }"#).unwrap();
+        assert_eq!(check(&file, Some(&schema), false), Ok(()));
+
+        fs::write(&file_path, "{ \"port\": \"80\",\n  \"port\": 81,\n  \"a\": \"b\" }").unwrap();
+        let problems = check(&file, Some(&schema), false).unwrap_err();
+        assert_eq!(problems, format!("{}:2: Duplicate key port (first on line 1)", file));
+
+        fs::write(&file_path, "{\n  \"port\": \"80\",\n}").unwrap();
+        assert!(check(&file, None, false).unwrap_err().starts_with(&format!("{}:3:1: trailing comma", file)));
+        assert!(check(&file, Some(&schema), true).unwrap_err().contains("\"80\" is not of type \"integer\""));
+
+        // Documents back to back and empty files aren't valid JSON.
+        fs::write(&file_path, r#"{"a":1}{"b":2}"#).unwrap();
+        assert!(check(&file, None, false).unwrap_err().starts_with(&format!("{}:1:8: trailing characters", file)));
+        fs::write(&file_path, "1 2").unwrap();
+        assert!(check(&file, None, false).is_err());
+        fs::write(&file_path, "\n").unwrap();
+        assert_eq!(check(&file, None, false), Err(format!("{}: File is empty", file)));
+
+        // A relative `$schema` is found next to the document.
+        let schema_name = schema_path.file_name().unwrap().to_string_lossy().to_string();
+        fs::write(&file_path, format!(r#"{{ "$schema": "{}", "port": "80" }}"#, schema_name)).unwrap();
+        assert!(check(&file, None, false).unwrap_err().contains("\"80\" is not of type \"integer\""));
+
+        fs::remove_file(&file_path).unwrap();
+        fs::remove_file(&schema_path).unwrap();
+    }
+}
@@ -0,0 +1,252 @@
+//
+// The table mode: an array of objects shown as rows and columns, with the cells editable like
+// a small spreadsheet. Edits are written back to the array in the document.
+//
+
+use std::time::Duration;
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::{actions::{Action, AppNavigationAction, InputEdit, TableAction}, app::{delete_input_to, is_secret_key, move_input_cursor, value_from_input, App, CurrentScreen, ReportedMessageKinds}, clipboard::copy_selection, i18n::tr, schema::value_choice_label, utils::{stats::{format_number, Aggregate}, json::{get_line_at_path, get_path_at_line, get_value_at_path_mut, path_to_pointer, PathSegment}}};
+
+#[derive(Debug)]
+pub struct Table {
+    /// Where the array is in the document.
+    pub path: Vec<PathSegment>,
+    /// The keys of the objects, in the order they're first seen.
+    pub columns: Vec<String>,
+    pub row: usize,
+    pub column: usize,
+    /// Whether the selected cell is being edited in the value input.
+    pub is_editing: bool,
+    /// Whether the summary of each column is shown under the rows.
+    pub show_summaries: bool,
+}
+
+/// The keys of all the objects of the array, in the order they're first seen.
+pub fn table_columns(rows: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = vec![];
+    for row in rows.iter().filter_map(Value::as_object) {
+        for key in row.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    return columns;
+}
+
+/// How a cell is shown: strings as they are, other values as JSON. Missing keys are empty.
+pub fn cell_text(row: &Value, column: &str) -> String {
+    return row.get(column).map(value_choice_label).unwrap_or_default();
+}
+
+/// A summary of a column, one line per figure: how many rows have the key, how many different
+/// values they have and, when they're all numbers, their sum.
+pub fn column_summary(rows: &[Value], column: &str) -> Vec<String> {
+    let values: Vec<&Value> = rows.iter().filter_map(|row| row.get(column)).collect();
+    let distinct: HashSet<String> = values.iter().map(|value| value.to_string()).collect();
+
+    let mut lines = vec![tr!("count {}", values.len()), tr!("distinct {}", distinct.len())];
+    if let Some(aggregate) = Aggregate::of(values.iter().copied()) && aggregate.skipped == 0 {
+        lines.push(tr!("sum {}", format_number(aggregate.sum)));
+    }
+
+    return lines;
+}
+
+impl App {
+    /// The rows of the table, if the array is still there.
+    pub fn table_rows(&self) -> Option<&Vec<Value>> {
+        let table = self.table.as_ref()?;
+        return self.json.pointer(&path_to_pointer(&table.path))?.as_array();
+    }
+
+    /// Shows the array of objects under the cursor (or holding the element under it) as a table.
+    pub fn open_table(&mut self) {
+        let Some(path) = self.array_path_at_cursor() else {
+            self.report(tr!("Put the cursor on an array of objects, or on one of its elements"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        };
+
+        let rows = self.json.pointer(&path_to_pointer(&path)).and_then(Value::as_array).cloned().unwrap_or_default();
+        let columns = table_columns(&rows);
+        if columns.is_empty() {
+            self.report(tr!("The array has no objects to show as a table"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        }
+
+        // Start on the element under the cursor.
+        let row = match get_path_at_line(self.line_at_cursor, &self.json).and_then(|cursor_path| cursor_path.get(path.len()).cloned()) {
+            Some(PathSegment::Index(index)) => index,
+            _ => 0,
+        };
+
+        self.table = Some(Table { path, columns, row, column: 0, is_editing: false, show_summaries: false });
+        self.current_screen = CurrentScreen::Table;
+    }
+
+    pub fn handle_table_actions(&mut self, action: TableAction) {
+        let rows_count = self.table_rows().map(Vec::len).unwrap_or_default();
+        let Some(table) = &mut self.table else {
+            return;
+        };
+
+        match action {
+            TableAction::MoveDown => table.row = (table.row + 1).min(rows_count.saturating_sub(1)),
+            TableAction::MoveUp => table.row = table.row.saturating_sub(1),
+            TableAction::MoveRight => table.column = (table.column + 1).min(table.columns.len().saturating_sub(1)),
+            TableAction::MoveLeft => table.column = table.column.saturating_sub(1),
+            TableAction::EditCell => self.start_editing_cell(),
+            TableAction::SubmitCell => self.submit_cell(),
+            TableAction::ToggleSummaries => table.show_summaries = !table.show_summaries,
+            TableAction::CancelEditing => {
+                table.is_editing = false;
+                self.value_input.clear();
+                self.value_input.is_focused = false;
+                self.value_input.set_hidden(false);
+            }
+            TableAction::EditInput(edit) => self.edit_cell_input(edit),
+            TableAction::Close => {
+                // Leave the cursor in the tree on the row that was selected.
+                let mut row_path = table.path.clone();
+                row_path.push(PathSegment::Index(table.row));
+                self.table = None;
+                self.update(Action::AppNavigation(AppNavigationAction::ToViewingScreen));
+                if let Some(line) = get_line_at_path(&row_path, &self.json) {
+                    self.move_cursor_to_line(line);
+                }
+            }
+        }
+    }
+
+    /// Opens the value input on the selected cell. Nested objects and arrays are edited in the tree.
+    fn start_editing_cell(&mut self) {
+        let Some(table) = &self.table else {
+            return;
+        };
+        let Some(row) = self.table_rows().and_then(|rows| rows.get(table.row)) else {
+            return;
+        };
+        let column = &table.columns[table.column];
+
+        if !row.is_object() {
+            self.report(tr!("This element isn't an object"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        }
+        if row.get(column).is_some_and(|value| value.is_object() || value.is_array()) {
+            self.report(tr!("Nested objects and arrays are edited in the tree"), ReportedMessageKinds::Error, Duration::from_secs(3));
+            return;
+        }
+
+        let text = cell_text(row, column);
+        self.value_input.set_hidden(is_secret_key(column));
+        self.value_input.set_content(&text);
+        self.value_input.is_focused = true;
+        if let Some(table) = &mut self.table {
+            table.is_editing = true;
+        }
+    }
+
+    /// Edits the text of the cell like the other inputs, without suggestions or history.
+    fn edit_cell_input(&mut self, edit: InputEdit) {
+        let input = &mut self.value_input;
+        match edit {
+            InputEdit::AppendChar(c) => input.append_char(c),
+            InputEdit::MoveCursor { direction, selecting } => move_input_cursor(input, direction, selecting),
+            InputEdit::PopChar => input.delete_char(),
+            InputEdit::PopWord => input.delete_word(),
+            InputEdit::DeleteForward => input.delete_next_char(),
+            InputEdit::DeleteTo(direction) => delete_input_to(input, direction),
+            InputEdit::TransposeChars => input.transpose_chars(),
+            InputEdit::Undo => {
+                input.undo();
+            }
+            InputEdit::Clear => input.delete_all(),
+            InputEdit::ToggleHidden => input.set_hidden(!input.is_hidden()),
+            InputEdit::CopySelection { cut } => {
+                let (message, kind) = copy_selection(input, cut);
+                self.report(message, kind, Duration::from_secs(2));
+            }
+        }
+    }
+
+    /// Writes the value input to the selected cell, adding the key to the object if it was missing.
+    fn submit_cell(&mut self) {
+        let Some(table) = &mut self.table else {
+            return;
+        };
+        table.is_editing = false;
+
+        let mut row_path = table.path.clone();
+        row_path.push(PathSegment::Index(table.row));
+        let column = table.columns[table.column].clone();
+        let value = value_from_input(self.value_input.content());
+
+        self.value_input.clear();
+        self.value_input.is_focused = false;
+        self.value_input.set_hidden(false);
+
+        let Some(Value::Object(row)) = get_value_at_path_mut(&row_path, &mut self.json) else {
+            return;
+        };
+        if row.get(&column) == Some(&value) {
+            return;
+        }
+
+        row.insert(column, value);
+        self.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_table_columns() {
+        let rows = json!([{ "id": 1, "name": "a" }, { "id": 2, "email": "b@c.d" }, 3]);
+
+        assert_eq!(table_columns(rows.as_array().unwrap()), vec!["id", "name", "email"]);
+        assert_eq!(cell_text(&rows[0], "name"), "a");
+        assert_eq!(cell_text(&rows[1], "name"), "");
+        assert_eq!(cell_text(&rows[1], "id"), "2");
+    }
+
+    #[test]
+    fn test_column_summary() {
+        let rows = json!([{ "id": 1, "price": 2.5, "tag": "a" }, { "id": 2, "price": 2.5, "tag": "a" }, { "id": 3, "tag": 1 }]);
+        let rows = rows.as_array().unwrap();
+
+        assert_eq!(column_summary(rows, "id"), vec!["count 3", "distinct 3", "sum 6"]);
+        assert_eq!(column_summary(rows, "price"), vec!["count 2", "distinct 1", "sum 5"]);
+        assert_eq!(column_summary(rows, "tag"), vec!["count 3", "distinct 2"]);
+        assert_eq!(column_summary(rows, "name"), vec!["count 0", "distinct 0"]);
+    }
+
+    #[test]
+    fn test_editing_cells() {
+        let mut app = App::default();
+        app.json = json!({ "users": [{ "id": 1, "name": "a" }, { "id": 2 }] });
+        app.line_at_cursor = 0;
+        app.open_table();
+
+        app.handle_table_actions(TableAction::MoveDown);
+        app.handle_table_actions(TableAction::MoveRight);
+        app.handle_table_actions(TableAction::EditCell);
+        app.value_input.set_content("b");
+        app.handle_table_actions(TableAction::SubmitCell);
+
+        app.handle_table_actions(TableAction::MoveLeft);
+        app.handle_table_actions(TableAction::EditCell);
+        app.value_input.set_content("20");
+        app.handle_table_actions(TableAction::SubmitCell);
+
+        assert_eq!(app.json, json!({ "users": [{ "id": 1, "name": "a" }, { "id": 20, "name": "b" }] }));
+        assert!(app.is_dirty);
+    }
+}
@@ -0,0 +1,82 @@
+//
+// The title of the terminal's window or tab, showing the opened file and whether it has unsaved
+// changes. The title the terminal had is put back on exit.
+//
+
+use std::io::{self, Write};
+
+use crossterm::{execute, terminal::SetTitle};
+
+use crate::app::App;
+
+/// Pushes the terminal's title on its stack of titles (xterm's `CSI 22;0t`).
+fn push_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b[22;0t")?;
+    return stdout.flush();
+}
+
+/// Clears the title, then pops the one pushed by `push_title` for the terminals that keep a stack.
+fn pop_title() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    execute!(stdout, SetTitle(""))?;
+    write!(stdout, "\x1b[23;0t")?;
+    return stdout.flush();
+}
+
+impl App {
+    /// `todd — config.json ●` while the file has unsaved changes, `todd — config.json` otherwise.
+    pub fn terminal_title(&self) -> String {
+        let Some(name) = self.file_path.as_ref().and_then(|path| path.file_name()) else {
+            return "todd".to_string();
+        };
+
+        return format!("todd — {}{}", name.to_string_lossy(), if self.is_dirty { " ●" } else { "" });
+    }
+
+    /// Sets the terminal's title, if it's enabled and changed since it was last set.
+    pub fn update_terminal_title(&mut self) -> io::Result<()> {
+        if !self.config.terminal_title {
+            return Ok(());
+        }
+
+        let title = self.terminal_title();
+        if self.shown_title.as_ref() == Some(&title) {
+            return Ok(());
+        }
+
+        if self.shown_title.is_none() {
+            push_title()?;
+        }
+        execute!(io::stdout(), SetTitle(&title))?;
+        self.shown_title = Some(title);
+
+        return Ok(());
+    }
+
+    /// Gives the terminal its title back, if todd changed it.
+    pub fn restore_terminal_title(&mut self) -> io::Result<()> {
+        if self.shown_title.take().is_some() {
+            pop_title()?;
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_title() {
+        let mut app = App::default();
+        assert_eq!(app.terminal_title(), "todd");
+
+        app.file_path = Some("/etc/app/config.json".into());
+        assert_eq!(app.terminal_title(), "todd — config.json");
+
+        app.is_dirty = true;
+        assert_eq!(app.terminal_title(), "todd — config.json ●");
+    }
+}
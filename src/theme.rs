@@ -0,0 +1,311 @@
+//
+// The colors todd draws with. A built-in theme is picked with `--theme` or the `theme` of the
+// config file, which can also be an object overriding some of the colors of one.
+//
+
+use std::{collections::HashMap, env};
+
+use ratatui::{buffer::Buffer, style::{Color, Modifier, Style}};
+use serde::Deserialize;
+
+use crate::app::App;
+
+/// The names of the built-in themes. `auto` picks `default` or `basic` for the terminal.
+pub const THEME_NAMES: [&str; 6] = ["auto", "default", "light", "gruvbox", "basic", "high-contrast"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The bar under the cursor, and the selected items of lists.
+    pub cursor_fg: Color,
+    pub cursor_bg: Color,
+    /// The keys of objects. The indexes of arrays are dimmed.
+    pub key: Color,
+    pub string: Color,
+    pub number: Color,
+    pub boolean: Color,
+    pub null: Color,
+    /// The background of the keys and values matching the search.
+    pub search_match: Color,
+    /// Annotations next to values (dates, comments, environment values) and other secondary text.
+    pub dimmed: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub success: Color,
+    /// Paths, and the sorted view indicator.
+    pub accent: Color,
+    /// The headers of the table mode.
+    pub header: Color,
+    /// The border of the focused input.
+    pub focused_border: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            cursor_fg: Color::Black,
+            cursor_bg: Color::Green,
+            key: Color::Reset, // the terminal's own color.
+            string: Color::Green,
+            number: Color::Rgb(212, 188, 125), // yellowish color.
+            boolean: Color::Red,
+            null: Color::Rgb(243, 139, 168), // pinkish color.
+            search_match: Color::Rgb(246, 118, 111), // reddish color.
+            dimmed: Color::DarkGray,
+            error: Color::Red,
+            warning: Color::Yellow,
+            success: Color::Green,
+            accent: Color::Cyan,
+            header: Color::Yellow,
+            focused_border: Color::Yellow,
+        }
+    }
+}
+
+impl Theme {
+    /// A built-in theme by its name.
+    pub fn named(name: &str) -> Option<Theme> {
+        return match name {
+            "default" => Some(Theme::default()),
+            // For terminals with a light background.
+            "light" => Some(Theme {
+                cursor_fg: Color::Rgb(239, 241, 245),
+                cursor_bg: Color::Rgb(30, 102, 245),
+                key: Color::Reset,
+                string: Color::Rgb(64, 160, 43),
+                number: Color::Rgb(254, 100, 11),
+                boolean: Color::Rgb(210, 15, 57),
+                null: Color::Rgb(136, 57, 239),
+                search_match: Color::Rgb(249, 226, 175),
+                dimmed: Color::Rgb(140, 143, 161),
+                error: Color::Rgb(210, 15, 57),
+                warning: Color::Rgb(223, 142, 29),
+                success: Color::Rgb(64, 160, 43),
+                accent: Color::Rgb(4, 165, 229),
+                header: Color::Rgb(223, 142, 29),
+                focused_border: Color::Rgb(30, 102, 245),
+            }),
+            "gruvbox" => Some(Theme {
+                cursor_fg: Color::Rgb(40, 40, 40),
+                cursor_bg: Color::Rgb(250, 189, 47),
+                key: Color::Reset,
+                string: Color::Rgb(184, 187, 38),
+                number: Color::Rgb(211, 134, 155),
+                boolean: Color::Rgb(254, 128, 25),
+                null: Color::Rgb(131, 165, 152),
+                search_match: Color::Rgb(204, 36, 29),
+                dimmed: Color::Rgb(146, 131, 116),
+                error: Color::Rgb(251, 73, 52),
+                warning: Color::Rgb(250, 189, 47),
+                success: Color::Rgb(184, 187, 38),
+                accent: Color::Rgb(142, 192, 124),
+                header: Color::Rgb(250, 189, 47),
+                focused_border: Color::Rgb(250, 189, 47),
+            }),
+            // Only the 16 colors of the terminal's palette, for terminals without RGB colors.
+            "basic" => Some(Theme {
+                cursor_fg: Color::Black,
+                cursor_bg: Color::Green,
+                key: Color::Reset,
+                string: Color::Green,
+                number: Color::Yellow,
+                boolean: Color::Red,
+                null: Color::Magenta,
+                search_match: Color::Red,
+                dimmed: Color::DarkGray,
+                error: Color::Red,
+                warning: Color::Yellow,
+                success: Color::Green,
+                accent: Color::Cyan,
+                header: Color::Yellow,
+                focused_border: Color::Yellow,
+            }),
+            // Bright colors of the palette and no dim text, for readability.
+            "high-contrast" => Some(Theme {
+                cursor_fg: Color::Black,
+                cursor_bg: Color::White,
+                key: Color::Reset,
+                string: Color::LightGreen,
+                number: Color::LightYellow,
+                boolean: Color::LightRed,
+                null: Color::LightMagenta,
+                search_match: Color::Blue,
+                dimmed: Color::Gray,
+                error: Color::LightRed,
+                warning: Color::LightYellow,
+                success: Color::LightGreen,
+                accent: Color::LightCyan,
+                header: Color::White,
+                focused_border: Color::White,
+            }),
+            "auto" => Some(Theme::for_terminal()),
+            _ => None,
+        };
+    }
+
+    /// The default theme if the terminal says it supports RGB colors, the basic one otherwise.
+    pub fn for_terminal() -> Theme {
+        let color_term = env::var("COLORTERM").unwrap_or_default();
+        return Theme::for_color_term(&color_term);
+    }
+
+    fn for_color_term(color_term: &str) -> Theme {
+        return match color_term {
+            "truecolor" | "24bit" => Theme::default(),
+            _ => Theme::named("basic").unwrap(),
+        };
+    }
+
+    /// A built-in theme, or an error listing them.
+    pub fn load(name: &str) -> Result<Theme, String> {
+        return Theme::named(name).ok_or_else(|| format!("Unknown theme `{}`. The themes are {}", name, THEME_NAMES.join(", ")));
+    }
+
+    /// The style of the line under the cursor.
+    pub fn cursor(&self) -> Style {
+        return Style::default().bg(self.cursor_bg).fg(self.cursor_fg);
+    }
+
+    /// Sets one of the colors by its name in the config file.
+    fn set(&mut self, name: &str, color: Color) -> Result<(), String> {
+        let field = match name {
+            "cursor_fg" => &mut self.cursor_fg,
+            "cursor_bg" => &mut self.cursor_bg,
+            "key" => &mut self.key,
+            "string" => &mut self.string,
+            "number" => &mut self.number,
+            "boolean" | "bool" => &mut self.boolean,
+            "null" => &mut self.null,
+            "search_match" => &mut self.search_match,
+            "dimmed" => &mut self.dimmed,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "success" => &mut self.success,
+            "accent" => &mut self.accent,
+            "header" => &mut self.header,
+            "focused_border" => &mut self.focused_border,
+            _ => return Err(format!("Unknown theme color `{}`", name)),
+        };
+        *field = color;
+
+        return Ok(());
+    }
+}
+
+/// A theme in the config file: the name of a built-in one, or an object of colors overriding
+/// those of the `base` theme (`default` if not given).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ConfigTheme {
+    Named(String),
+    Custom(HashMap<String, String>),
+}
+
+impl<'de> Deserialize<'de> for Theme {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut colors = match ConfigTheme::deserialize(deserializer)? {
+            ConfigTheme::Named(name) => return Theme::load(&name).map_err(serde::de::Error::custom),
+            ConfigTheme::Custom(colors) => colors,
+        };
+
+        let base = colors.remove("base").unwrap_or_else(|| "default".to_string());
+        let mut theme = Theme::load(&base).map_err(serde::de::Error::custom)?;
+        for (name, color) in colors {
+            let color = color.parse::<Color>().map_err(|_| serde::de::Error::custom(format!("Unknown color `{}`", color)))?;
+            theme.set(&name, color).map_err(serde::de::Error::custom)?;
+        }
+
+        return Ok(theme);
+    }
+}
+
+/// Whether the user asked for no colors with the `NO_COLOR` environment variable (https://no-color.org).
+pub fn no_color_requested() -> bool {
+    return env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+}
+
+/// Removes the colors of everything drawn. What stood out with a background color, like the
+/// cursor bar, the selected items and the search matches, is reversed and bold instead.
+pub fn strip_colors(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if cell.bg != Color::Reset {
+            cell.modifier.insert(Modifier::REVERSED | Modifier::BOLD);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+impl App {
+    /// Draws with the theme from now on.
+    pub fn set_theme(&mut self, theme: Theme) {
+        let focused_border = Style::default().fg(theme.focused_border);
+        for input in [&mut self.key_input, &mut self.value_input, &mut self.search_widget, &mut self.command_input] {
+            input.set_focused_block_style(focused_border);
+            input.set_invalid_block_style(Style::default().fg(theme.error).add_modifier(Modifier::BOLD));
+            // Still dim once the colors are stripped.
+            input.set_placeholder_style(Style::default().fg(theme.dimmed).add_modifier(Modifier::DIM));
+        }
+
+        self.config.theme = theme;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_themes_for_terminals() {
+        assert_eq!(Theme::for_color_term("truecolor"), Theme::default());
+        assert_eq!(Theme::for_color_term(""), Theme::named("basic").unwrap());
+
+        // The fallbacks never use RGB colors.
+        for name in ["basic", "high-contrast"] {
+            let theme = Theme::named(name).unwrap();
+            let colors = [
+                theme.cursor_fg, theme.cursor_bg, theme.key, theme.string, theme.number, theme.boolean, theme.null, theme.search_match,
+                theme.dimmed, theme.error, theme.warning, theme.success, theme.accent, theme.header, theme.focused_border,
+            ];
+            assert!(colors.iter().all(|color| !matches!(color, Color::Rgb(..))), "{} uses RGB colors", name);
+        }
+    }
+
+    #[test]
+    fn test_strip_colors() {
+        let mut buffer = Buffer::empty(ratatui::layout::Rect::new(0, 0, 3, 1));
+        buffer.set_string(0, 0, "a", Style::default().fg(Color::Green));
+        buffer.set_string(1, 0, "b", Theme::default().cursor());
+        buffer.set_string(2, 0, "c", Style::default().fg(Color::Red).add_modifier(Modifier::ITALIC));
+
+        strip_colors(&mut buffer);
+
+        let mut expected = Buffer::empty(ratatui::layout::Rect::new(0, 0, 3, 1));
+        expected.set_string(0, 0, "a", Style::default());
+        expected.set_string(1, 0, "b", Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD));
+        expected.set_string(2, 0, "c", Style::default().add_modifier(Modifier::ITALIC));
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn test_config_themes() {
+        assert_eq!(serde_json::from_value::<Theme>(json!("gruvbox")).unwrap(), Theme::named("gruvbox").unwrap());
+
+        let theme: Theme = serde_json::from_value(json!({ "base": "light", "number": "#ff8800", "cursor_bg": "magenta" })).unwrap();
+        assert_eq!(theme.number, Color::Rgb(255, 136, 0));
+        assert_eq!(theme.cursor_bg, Color::Magenta);
+        assert_eq!(theme.string, Theme::named("light").unwrap().string);
+
+        let theme: Theme = serde_json::from_value(json!({ "key": "blue", "bool": "yellow" })).unwrap();
+        assert_eq!(theme.key, Color::Blue);
+        assert_eq!(theme.boolean, Color::Yellow);
+
+        let theme: Theme = serde_json::from_value(json!({ "null": "8" })).unwrap();
+        assert_eq!(theme.null, Color::Indexed(8));
+        assert_eq!(theme.string, Theme::default().string);
+
+        assert!(serde_json::from_value::<Theme>(json!("solarized")).is_err());
+        assert!(serde_json::from_value::<Theme>(json!({ "strings": "red" })).is_err());
+        assert!(serde_json::from_value::<Theme>(json!({ "string": "reddish" })).is_err());
+    }
+}
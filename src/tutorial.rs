@@ -0,0 +1,126 @@
+//
+// A short guided tour of the basics, offered on the first launch and reopened with `:tutorial`.
+//
+
+use std::{fs, path::PathBuf};
+
+use crate::{actions::TutorialAction, app::{App, CurrentScreen}, config::state_dir, i18n::{tr, translate}, keymap::{KeyAction, Keymap, KeySequence}};
+
+/// Where the first launch is remembered, so the tutorial is only offered once.
+fn tutorial_seen_file() -> Option<PathBuf> {
+    return state_dir().map(|dir| dir.join("tutorial_seen"));
+}
+
+/// The step of the tutorial being shown.
+#[derive(Debug)]
+pub struct Tutorial {
+    pub step: usize,
+    /// The screen the tutorial was opened from, and goes back to.
+    return_screen: CurrentScreen,
+}
+
+/// The title and the lines of every step, with the keys as they're bound.
+pub fn tutorial_steps(keymap: &Keymap) -> Vec<(&'static str, Vec<String>)> {
+    let keys = |action: KeyAction| {
+        let keys: Vec<String> = keymap.keys_of(action).iter().take(2).map(KeySequence::to_string).collect();
+        return if keys.is_empty() { tr!("(unbound)") } else { keys.join(translate(" or ")) };
+    };
+
+    return vec![
+        (translate("Welcome"), vec![
+            tr!("todd shows a JSON document as a tree of keys and values."),
+            tr!("This tour walks through moving around, searching, inserting and editing."),
+            tr!("It can be reopened any time with :tutorial."),
+        ]),
+        (translate("Moving around"), vec![
+            tr!("{} and {} move the cursor down and up a line.", keys(KeyAction::MoveDown), keys(KeyAction::MoveUp)),
+            tr!("{} and {} go to the top and the bottom.", keys(KeyAction::MoveToTop), keys(KeyAction::MoveToBottom)),
+            tr!("{} and {} move half a page.", keys(KeyAction::HalfPageDown), keys(KeyAction::HalfPageUp)),
+        ]),
+        (translate("Searching"), vec![
+            tr!("{} searches the keys and values for what you type.", keys(KeyAction::Search)),
+            tr!("{} and {} go to the next and the previous match.", keys(KeyAction::NextMatch), keys(KeyAction::PreviousMatch)),
+            tr!("{} clears the search.", keys(KeyAction::ClearSearch)),
+        ]),
+        (translate("Inserting"), vec![
+            tr!("{} opens a popup to add a key and a value after the cursor.", keys(KeyAction::Insert)),
+            tr!("<Tab> switches between the key and the value, <Enter> adds them."),
+            tr!("Values are typed as JSON: 42, true, null, or text for a string."),
+        ]),
+        (translate("Editing"), vec![
+            tr!("{} edits the key and the value under the cursor.", keys(KeyAction::Edit)),
+            tr!("{} deletes it, and {} saves the file.", keys(KeyAction::Delete), keys(KeyAction::Save)),
+            tr!("{} opens the command line, like :w to save or :q to quit.", keys(KeyAction::CommandLine)),
+        ]),
+        (translate("That's it"), vec![
+            tr!("{} lists every key, and :set and the config file change the view.", keys(KeyAction::Help)),
+            tr!("Happy editing!"),
+        ]),
+    ];
+}
+
+impl App {
+    /// Opens the tutorial the first time todd is launched.
+    pub fn offer_tutorial(&mut self) {
+        let Some(seen_file) = tutorial_seen_file() else {
+            return;
+        };
+        if seen_file.exists() {
+            return;
+        }
+
+        // Remembered whether or not the tour is followed to the end.
+        if let Some(dir) = seen_file.parent() && fs::create_dir_all(dir).and_then(|_| fs::write(&seen_file, "")).is_err() {
+            return;
+        }
+
+        self.open_tutorial();
+    }
+
+    pub fn open_tutorial(&mut self) {
+        self.tutorial = Some(Tutorial { step: 0, return_screen: self.current_screen });
+        self.current_screen = CurrentScreen::Tutorial;
+    }
+
+    pub fn handle_tutorial_actions(&mut self, action: TutorialAction) {
+        let steps_count = tutorial_steps(&self.config.keybindings).len();
+        let Some(tutorial) = &mut self.tutorial else {
+            return;
+        };
+
+        match action {
+            TutorialAction::Next if tutorial.step + 1 < steps_count => tutorial.step += 1,
+            TutorialAction::Previous => tutorial.step = tutorial.step.saturating_sub(1),
+            TutorialAction::Next | TutorialAction::Close => {
+                self.current_screen = tutorial.return_screen;
+                self.tutorial = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::Action;
+
+    #[test]
+    fn test_tutorial_steps() {
+        let mut app = App::default();
+        app.open_tutorial();
+        assert!(matches!(app.current_screen, CurrentScreen::Tutorial));
+
+        app.update(Action::Tutorial(TutorialAction::Previous));
+        assert_eq!(app.tutorial.as_ref().unwrap().step, 0);
+
+        let steps = tutorial_steps(&app.config.keybindings);
+        assert_eq!(steps[1].1[0], "j or <Down> and k or <Up> move the cursor down and up a line.");
+
+        // Going past the last step closes it.
+        for _ in 0..steps.len() {
+            app.update(Action::Tutorial(TutorialAction::Next));
+        }
+        assert!(app.tutorial.is_none());
+        assert!(matches!(app.current_screen, CurrentScreen::ViewingFile));
+    }
+}
@@ -0,0 +1,60 @@
+//
+// Small glyphs in front of the lines telling the type of their value, to scan the structure of a
+// document at a glance.
+//
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Which glyphs, if any, are put in front of the lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeIcons {
+    /// No glyphs.
+    #[default]
+    Off,
+    /// Characters every terminal font has.
+    Ascii,
+    /// Icons of a Nerd Font, which the terminal's font has to be patched with.
+    Nerd,
+}
+
+impl TypeIcons {
+    /// The glyph of the value's type, followed by a space. `None` when the glyphs are off.
+    pub fn icon(&self, value: &Value) -> Option<&'static str> {
+        let icon = match (self, value) {
+            (TypeIcons::Off, _) => return None,
+            (TypeIcons::Ascii, Value::Object(_)) => "{ ",
+            (TypeIcons::Ascii, Value::Array(_)) => "[ ",
+            (TypeIcons::Ascii, Value::String(_)) => "\" ",
+            (TypeIcons::Ascii, Value::Number(_)) => "# ",
+            (TypeIcons::Ascii, Value::Bool(_)) => "? ",
+            (TypeIcons::Ascii, Value::Null) => "~ ",
+            (TypeIcons::Nerd, Value::Object(_)) => "\u{ea8b} ",
+            (TypeIcons::Nerd, Value::Array(_)) => "\u{ea8a} ",
+            (TypeIcons::Nerd, Value::String(_)) => "\u{eb8d} ",
+            (TypeIcons::Nerd, Value::Number(_)) => "\u{ea90} ",
+            (TypeIcons::Nerd, Value::Bool(_)) => "\u{ea8f} ",
+            (TypeIcons::Nerd, Value::Null) => "\u{eabd} ",
+        };
+
+        return Some(icon);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_type_icons() {
+        assert_eq!(TypeIcons::Off.icon(&json!({})), None);
+        assert_eq!(TypeIcons::Ascii.icon(&json!([1])), Some("[ "));
+        assert_eq!(TypeIcons::Ascii.icon(&json!(null)), Some("~ "));
+        assert_eq!(TypeIcons::Nerd.icon(&json!(true)), Some("\u{ea8f} "));
+
+        assert_eq!(serde_json::from_value::<TypeIcons>(json!("nerd")).unwrap(), TypeIcons::Nerd);
+        assert!(serde_json::from_value::<TypeIcons>(json!("emoji")).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//
+// Turning CSV files into JSON.
+//
+
+use serde_json::{Map, Number, Value};
+
+/// Parses CSV text into an array of objects, one per row, keyed by the header row. Fields
+/// that look like numbers or booleans are turned into those.
+pub fn csv_to_json(content: &str) -> Result<Value, String> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|err| format!("Invalid CSV header: {}", err))?
+        .clone();
+
+    let mut rows = vec![];
+
+    for record in reader.records() {
+        let record = record.map_err(|err| format!("Invalid CSV row: {}", err))?;
+
+        let mut row = Map::new();
+        for (index, field) in record.iter().enumerate() {
+            // Rows longer than the header get keys from their position.
+            let key = headers
+                .get(index)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("column_{}", index + 1));
+            row.insert(key, sniff_type(field));
+        }
+
+        rows.push(Value::Object(row));
+    }
+
+    return Ok(Value::Array(rows));
+}
+
+/// Turns a CSV field into the JSON value it looks like.
+fn sniff_type(field: &str) -> Value {
+    match field {
+        "true" | "TRUE" | "True" => return Value::Bool(true),
+        "false" | "FALSE" | "False" => return Value::Bool(false),
+        _ => {}
+    }
+
+    // Numbers are kept as written. Things like zip codes and phone numbers ("01234") aren't
+    // valid JSON numbers, so they stay strings.
+    return match field.parse::<Number>() {
+        Ok(number) => Value::Number(number),
+        Err(_) => Value::String(field.to_string()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_to_json() {
+        let content = "name,age,admin,zip,score\nOmar,30,true,01234,9.5\n\"Doe, Jane\",,FALSE,12345,1e3\n";
+
+        assert_eq!(
+            csv_to_json(content).unwrap(),
+            json!([
+                { "name": "Omar", "age": 30, "admin": true, "zip": "01234", "score": 9.5 },
+                { "name": "Doe, Jane", "age": "", "admin": false, "zip": 12345, "score": serde_json::from_str::<Value>("1e3").unwrap() }
+            ])
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//
+// Finding duplicate keys. serde keeps only the last value of a repeated key, so the others are
+// silently lost once the document is parsed. This goes over the text itself to find them.
+//
+
+use std::collections::HashMap;
+
+use super::json::PathSegment;
+
+/// A key that appears more than once in the same object.
+#[derive(Debug, PartialEq)]
+pub struct DuplicateKey {
+    /// The path to the key.
+    pub path: Vec<PathSegment>,
+    /// The line (1-based) of the repeated key.
+    pub line: usize,
+    /// The line of the first occurrence of the key.
+    pub first_line: usize,
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    line: usize,
+    path: Vec<PathSegment>,
+    duplicates: Vec<DuplicateKey>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        return self.bytes.get(self.position).copied();
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            if !byte.is_ascii_whitespace() {
+                break;
+            }
+            if byte == b'\n' {
+                self.line += 1;
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Skips over a string and gives back its raw text, quotes included.
+    fn scan_string(&mut self) -> &[u8] {
+        let start = self.position;
+        self.position += 1;
+
+        while let Some(byte) = self.peek() {
+            self.position += 1;
+            match byte {
+                b'\\' => self.position += 1,
+                b'"' => break,
+                _ => {}
+            }
+        }
+
+        return &self.bytes[start..self.position.min(self.bytes.len())];
+    }
+
+    fn scan_value(&mut self) {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'{') => self.scan_object(),
+            Some(b'[') => self.scan_array(),
+            Some(b'"') => {
+                self.scan_string();
+            }
+            Some(_) => {
+                while self.peek().is_some_and(|byte| !matches!(byte, b',' | b']' | b'}') && !byte.is_ascii_whitespace()) {
+                    self.position += 1;
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn scan_object(&mut self) {
+        self.position += 1;
+
+        let mut first_lines: HashMap<String, usize> = HashMap::new();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b'"') => {}
+                Some(b',') => {
+                    self.position += 1;
+                    continue;
+                }
+                Some(b'}') => {
+                    self.position += 1;
+                    return;
+                }
+                _ => return,
+            }
+
+            let line = self.line;
+            let key: String = serde_json::from_slice(self.scan_string()).unwrap_or_default();
+
+            self.path.push(PathSegment::Key(key.clone()));
+
+            match first_lines.get(&key) {
+                Some(first_line) => self.duplicates.push(DuplicateKey {
+                    path: self.path.clone(),
+                    line,
+                    first_line: *first_line,
+                }),
+                None => {
+                    first_lines.insert(key, line);
+                }
+            }
+
+            self.skip_whitespace();
+            if self.peek() == Some(b':') {
+                self.position += 1;
+            }
+            self.scan_value();
+
+            self.path.pop();
+        }
+    }
+
+    fn scan_array(&mut self) {
+        self.position += 1;
+
+        let mut index = 0;
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => {
+                    self.position += 1;
+                    continue;
+                }
+                Some(b']') => {
+                    self.position += 1;
+                    return;
+                }
+                None => return,
+                Some(_) => {}
+            }
+
+            self.path.push(PathSegment::Index(index));
+            self.scan_value();
+            self.path.pop();
+
+            index += 1;
+        }
+    }
+}
+
+/// Lists the keys of a JSON text that are repeated in their object, in the order they appear.
+pub fn find_duplicate_keys(text: &str) -> Vec<DuplicateKey> {
+    let mut scanner = Scanner {
+        bytes: text.as_bytes(),
+        position: 0,
+        line: 1,
+        path: vec![],
+        duplicates: vec![],
+    };
+
+    scanner.scan_value();
+
+    return scanner.duplicates;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_duplicate_keys() {
+        let text = "{\n  \"name\": \"a\",\n  \"items\": [{ \"id\": 1, \"id\": 2 }],\n  \"na\\u006de\": \"b\"\n}";
+
+        assert_eq!(find_duplicate_keys(text), vec![
+            DuplicateKey {
+                path: vec![PathSegment::Key("items".to_string()), PathSegment::Index(0), PathSegment::Key("id".to_string())],
+                line: 3,
+                first_line: 3,
+            },
+            DuplicateKey {
+                path: vec![PathSegment::Key("name".to_string())],
+                line: 4,
+                first_line: 2,
+            },
+        ]);
+
+        assert!(find_duplicate_keys("{\"a\": {\"a\": 1}, \"b\": [\"a\", \"a\"]}").is_empty());
+    }
+}
@@ -0,0 +1,29 @@
+/// The largest response body that's read. Anything bigger is most likely not meant to be
+/// browsed in a terminal.
+#[cfg(feature = "http")]
+const MAX_BODY_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Tells if the argument given to todd is an http(s) URL rather than a file path.
+pub fn is_url(argument: &str) -> bool {
+    return argument.starts_with("http://") || argument.starts_with("https://");
+}
+
+/// Fetches the body of the given URL.
+#[cfg(feature = "http")]
+pub fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    let mut response = ureq::get(url)
+        .call()
+        .map_err(|err| format!("Failed to fetch {}: {}", url, err))?;
+
+    return response
+        .body_mut()
+        .with_config()
+        .limit(MAX_BODY_SIZE)
+        .read_to_vec()
+        .map_err(|err| format!("Failed to read the response of {}: {}", url, err));
+}
+
+#[cfg(not(feature = "http"))]
+pub fn fetch(_url: &str) -> Result<Vec<u8>, String> {
+    return Err("This build of todd doesn't support opening URLs. Rebuild it with the `http` feature".to_string());
+}
@@ -1,2 +1,16 @@
-pub mod json;
-pub mod logging;
\ No newline at end of file
+pub mod csv;
+pub mod decode;
+pub mod duplicates;
+pub mod env;
+pub mod http;
+pub mod logging;
+pub mod readable;
+pub mod repair;
+pub mod rust_types;
+pub mod shape;
+pub mod stats;
+pub mod timestamp;
+pub mod typescript;
+
+// The document model is shared with the other tools through `todd-core`.
+pub use todd_core::{edit, json, jsonc, patch};
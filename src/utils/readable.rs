@@ -0,0 +1,59 @@
+//
+// Showing strings on a single line of the terminal: line breaks and tabs become visible symbols,
+// and the characters that would move the cursor or reorder the text are escaped.
+//
+
+use serde_json::Value;
+
+/// The string with `↵` for line breaks, `⇥` for tabs, `␍` for carriage returns, and the other
+/// control and invisible formatting characters escaped like in JSON (`\u001b`).
+pub fn readable_string(string: &str) -> String {
+    let mut readable = String::with_capacity(string.len());
+
+    for char in string.chars() {
+        match char {
+            '\n' => readable.push('↵'),
+            '\t' => readable.push('⇥'),
+            '\r' => readable.push('␍'),
+            _ if is_hidden(char) => readable.push_str(&format!("\\u{:04x}", char as u32)),
+            _ => readable.push(char),
+        }
+    }
+
+    return readable;
+}
+
+/// The value as written in JSON, with its strings made readable like `readable_string`.
+pub fn readable_value(value: &Value) -> String {
+    return match value {
+        Value::String(string) => {
+            let escaped = string.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("\"{}\"", readable_string(&escaped))
+        }
+        _ => value.to_string(),
+    };
+}
+
+/// Characters that aren't drawn but change how the rest of the line is: control characters,
+/// zero-width ones, and the bidirectional overrides.
+fn is_hidden(char: char) -> bool {
+    return char.is_control()
+        || matches!(char, '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2060}'..='\u{2064}' | '\u{2066}'..='\u{2069}' | '\u{feff}');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_readable_strings() {
+        assert_eq!(readable_string("line\nbreak\ttab\r"), "line↵break⇥tab␍");
+        assert_eq!(readable_string("\u{1b}[31mred"), "\\u001b[31mred");
+        assert_eq!(readable_string("abc\u{202e}def"), "abc\\u202edef");
+        assert_eq!(readable_string("café 😀"), "café 😀");
+
+        assert_eq!(readable_value(&json!("say \"hi\"\n")), "\"say \\\"hi\\\"↵\"");
+        assert_eq!(readable_value(&json!(12.5)), "12.5");
+    }
+}
@@ -0,0 +1,430 @@
+//
+// Repairing malformed JSON, the way it usually gets broken by hand: missing or extra commas,
+// comments, single quotes, unquoted keys, unclosed strings and brackets, stray quotes...
+//
+
+use serde_json::{Number, Value};
+
+use crate::i18n::translate;
+
+/// A change made to the document to make it valid JSON.
+#[derive(Debug, PartialEq)]
+pub struct Fix {
+    /// The line (1-based) of the original text where the change was made.
+    pub line: usize,
+    pub description: &'static str,
+}
+
+/// Characters that end a value that isn't in quotes.
+const DELIMITERS: [char; 5] = [',', '}', ']', ':', '\n'];
+
+struct Repairer {
+    chars: Vec<char>,
+    position: usize,
+    output: String,
+    fixes: Vec<Fix>,
+}
+
+impl Repairer {
+    fn peek(&self) -> Option<char> {
+        return self.chars.get(self.position).copied();
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        return self.chars.get(self.position + offset).copied();
+    }
+
+    fn fix(&mut self, description: &'static str) {
+        let line = self.chars[..self.position.min(self.chars.len())].iter().filter(|c| **c == '\n').count() + 1;
+        self.fixes.push(Fix { line, description });
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.position += 1;
+            } else if c == '/' && self.peek_at(1) == Some('*') {
+                self.fix(translate("Removed a comment"));
+                self.position += 2;
+                while self.peek().is_some() && !(self.peek() == Some('*') && self.peek_at(1) == Some('/')) {
+                    self.position += 1;
+                }
+                self.position = (self.position + 2).min(self.chars.len());
+            } else if c == '#' || (c == '/' && self.peek_at(1) == Some('/')) {
+                self.fix(translate("Removed a comment"));
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.position += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn repair_value(&mut self) {
+        loop {
+            self.skip_whitespace_and_comments();
+
+            match self.peek() {
+                Some('{') => return self.repair_object(),
+                Some('[') => return self.repair_array(),
+                Some(quote @ ('"' | '\'')) => return self.repair_string(quote),
+                Some(c) if c == '-' || c == '+' || c == '.' || c.is_ascii_digit() => return self.repair_number(),
+                Some(c) if c.is_alphabetic() || c == '_' || c == '$' => return self.repair_word(),
+                None | Some(',' | '}' | ']') => {
+                    self.fix(translate("Added a missing value"));
+                    self.output.push_str("null");
+                    return;
+                }
+                Some(_) => {
+                    self.fix(translate("Removed an unexpected character"));
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    /// Handles what comes after a member or an element: a comma, the end of the container, or a
+    /// missing comma. Gives back `true` when the container is closed.
+    fn repair_separator(&mut self, closing: char, saw_comma: &mut bool) -> bool {
+        self.skip_whitespace_and_comments();
+
+        match self.peek() {
+            None | Some('}' | ']') => {
+                if *saw_comma {
+                    self.fix(translate("Removed a trailing comma"));
+                }
+                match self.peek() {
+                    None if closing == '}' => self.fix(translate("Closed an unclosed object")),
+                    None => self.fix(translate("Closed an unclosed array")),
+                    Some(c) if c != closing => {
+                        self.fix(translate("Replaced a mismatched closing bracket"));
+                        self.position += 1;
+                    }
+                    Some(_) => self.position += 1,
+                }
+                self.output.push(closing);
+                return true;
+            }
+            Some(',') => {
+                if *saw_comma {
+                    self.fix(translate("Removed an extra comma"));
+                }
+                *saw_comma = true;
+                self.position += 1;
+                return self.repair_separator(closing, saw_comma);
+            }
+            Some(_) => return false,
+        }
+    }
+
+    fn repair_object(&mut self) {
+        self.position += 1;
+        self.output.push('{');
+
+        let mut saw_comma = false;
+        let mut is_first = true;
+
+        loop {
+            if self.repair_separator('}', &mut saw_comma) {
+                return;
+            }
+
+            let c = self.peek().unwrap();
+            if !(c == '"' || c == '\'' || c.is_alphanumeric() || c == '_' || c == '$') {
+                self.fix(translate("Removed an unexpected character"));
+                self.position += 1;
+                continue;
+            }
+
+            if is_first && saw_comma {
+                self.fix(translate("Removed an extra comma"));
+            } else if !is_first {
+                if !saw_comma {
+                    self.fix(translate("Added a missing comma"));
+                }
+                self.output.push(',');
+            }
+
+            if c == '"' || c == '\'' {
+                self.repair_string(c);
+            } else {
+                let start = self.position;
+                while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '-') {
+                    self.position += 1;
+                }
+                let key: String = self.chars[start..self.position].iter().collect();
+                self.fix(translate("Quoted a key"));
+                self.output.push_str(&serde_json::to_string(&key).unwrap());
+            }
+
+            self.skip_whitespace_and_comments();
+            if self.peek() == Some(':') {
+                self.position += 1;
+            } else {
+                self.fix(translate("Added a missing colon"));
+            }
+            self.output.push(':');
+
+            self.repair_value();
+
+            is_first = false;
+            saw_comma = false;
+        }
+    }
+
+    fn repair_array(&mut self) {
+        self.position += 1;
+        self.output.push('[');
+
+        let mut saw_comma = false;
+        let mut is_first = true;
+
+        loop {
+            if self.repair_separator(']', &mut saw_comma) {
+                return;
+            }
+
+            let c = self.peek().unwrap();
+            if c == ':' {
+                self.fix(translate("Removed an unexpected character"));
+                self.position += 1;
+                continue;
+            }
+
+            if is_first && saw_comma {
+                self.fix(translate("Removed an extra comma"));
+            } else if !is_first {
+                if !saw_comma {
+                    self.fix(translate("Added a missing comma"));
+                }
+                self.output.push(',');
+            }
+
+            self.repair_value();
+
+            is_first = false;
+            saw_comma = false;
+        }
+    }
+
+    /// Whether the quote at the position closes the string, rather than being a quote that
+    /// should have been escaped. It closes it if what follows can come after a value.
+    fn is_closing_quote(&self) -> bool {
+        let mut offset = 1;
+        while self.peek_at(offset).is_some_and(|c| c == ' ' || c == '\t' || c == '\r') {
+            offset += 1;
+        }
+
+        return match self.peek_at(offset) {
+            None => true,
+            Some(c) => DELIMITERS.contains(&c) || c == '"' || c == '/' || c == '#',
+        };
+    }
+
+    fn repair_string(&mut self, quote: char) {
+        self.position += 1;
+
+        if quote == '\'' {
+            self.fix(translate("Replaced single quotes with double quotes"));
+        }
+
+        let mut string = String::new();
+
+        loop {
+            match self.peek() {
+                None | Some('\n') => {
+                    self.fix(translate("Closed an unterminated string"));
+                    break;
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    match self.peek() {
+                        Some(c @ ('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u')) => {
+                            string.push('\\');
+                            string.push(c);
+                            self.position += 1;
+                        }
+                        Some('\'') => {
+                            string.push('\'');
+                            self.position += 1;
+                        }
+                        _ => {
+                            self.fix(translate("Escaped a backslash"));
+                            string.push_str("\\\\");
+                        }
+                    }
+                }
+                Some(c) if c == quote => {
+                    if self.is_closing_quote() {
+                        self.position += 1;
+                        break;
+                    }
+                    self.fix(translate("Escaped a quote inside a string"));
+                    string.push_str("\\\"");
+                    self.position += 1;
+                }
+                Some('"') => {
+                    string.push_str("\\\"");
+                    self.position += 1;
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    self.fix(translate("Escaped a control character"));
+                    string.push_str(&format!("\\u{:04x}", c as u32));
+                    self.position += 1;
+                }
+                Some(c) => {
+                    string.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+
+        self.output.push('"');
+        self.output.push_str(&string);
+        self.output.push('"');
+
+        // A doubled quote at the end of a string (`"Omar"",`).
+        if self.peek() == Some(quote) && self.is_closing_quote() {
+            self.fix(translate("Removed a stray quote"));
+            self.position += 1;
+        }
+    }
+
+    fn repair_number(&mut self) {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+            self.position += 1;
+        }
+
+        let written: String = self.chars[start..self.position].iter().collect();
+
+        let mut number = written.strip_prefix('+').unwrap_or(&written).to_string();
+        if number.starts_with('.') {
+            number.insert(0, '0');
+        } else if number.starts_with("-.") {
+            number.insert(1, '0');
+        }
+        if number.ends_with('.') {
+            number.push('0');
+        }
+
+        if number.parse::<Number>().is_ok() {
+            if number != written {
+                self.fix(translate("Fixed a number"));
+            }
+            self.output.push_str(&number);
+        } else {
+            self.fix(translate("Quoted an invalid number"));
+            self.output.push_str(&serde_json::to_string(&written).unwrap());
+        }
+    }
+
+    fn repair_word(&mut self) {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+            self.position += 1;
+        }
+
+        let word: String = self.chars[start..self.position].iter().collect();
+        let keyword = match word.as_str() {
+            "true" | "false" | "null" => {
+                self.output.push_str(&word);
+                return;
+            }
+            "True" | "TRUE" => "true",
+            "False" | "FALSE" => "false",
+            "None" | "NULL" | "Null" | "undefined" | "NaN" | "Infinity" => "null",
+            _ => "",
+        };
+
+        if !keyword.is_empty() {
+            self.fix(translate("Replaced a keyword that isn't JSON"));
+            self.output.push_str(keyword);
+            return;
+        }
+
+        // A string that's missing its quotes. It runs until something that can end a value.
+        while self.peek().is_some_and(|c| !DELIMITERS.contains(&c)) {
+            self.position += 1;
+        }
+
+        let string: String = self.chars[start..self.position].iter().collect();
+        self.fix(translate("Quoted a string"));
+        self.output.push_str(&serde_json::to_string(string.trim_end()).unwrap());
+    }
+}
+
+/// Repairs the document and gives back the repaired JSON, pretty-printed, with what was changed.
+pub fn repair(text: &str) -> Result<(String, Vec<Fix>), String> {
+    let mut repairer = Repairer {
+        chars: text.chars().collect(),
+        position: 0,
+        output: String::with_capacity(text.len()),
+        fixes: vec![],
+    };
+
+    repairer.repair_value();
+
+    repairer.skip_whitespace_and_comments();
+    if repairer.peek().is_some() {
+        repairer.fix(translate("Removed content after the end of the document"));
+    }
+
+    let json: Value = serde_json::from_str(&repairer.output).map_err(|err| format!("Couldn't repair the document: {}", err))?;
+    let text = serde_json::to_string_pretty(&json).map_err(|err| err.to_string())?;
+
+    return Ok((text, repairer.fixes));
+}
+
+/// Tells what commonly causes a JSON parsing error, and how to fix it.
+pub fn describe_error(error: &serde_json::Error) -> &'static str {
+    let message = error.to_string();
+
+    return if message.contains("trailing comma") {
+        "There's a comma before a closing bracket. Remove it."
+    } else if message.contains("expected `,` or `}`") || message.contains("expected `,` or `]`") {
+        "A comma may be missing before this point, or a string has a stray quote."
+    } else if message.contains("key must be a string") {
+        "Keys must be in double quotes."
+    } else if message.contains("control character") {
+        "A string may be missing its closing quote."
+    } else if message.contains("EOF while parsing") {
+        "An object, array or string isn't closed."
+    } else if message.contains("trailing characters") {
+        "There's content after the end of the document, like an extra closing bracket."
+    } else if message.contains("expected value") {
+        "A value is missing, or there's a comment, a single-quoted string or a keyword that isn't JSON."
+    } else if message.contains("expected `:`") {
+        "A colon is missing between a key and its value."
+    } else {
+        "The document isn't valid JSON."
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn repaired(text: &str) -> Value {
+        return serde_json::from_str(&repair(text).unwrap().0).unwrap();
+    }
+
+    #[test]
+    fn test_repair() {
+        assert_eq!(repaired("{\n  \"a\": 1\n  \"b\": [1, 2,],\n}"), json!({ "a": 1, "b": [1, 2] }));
+        assert_eq!(repaired("{'name': 'Omar', age: 30, admin: True, // Comment\n}"), json!({ "name": "Omar", "age": 30, "admin": true }));
+        assert_eq!(repaired("{\"a\": {\"b\": [1, 2"), json!({ "a": { "b": [1, 2] } }));
+        assert_eq!(repaired("{\"name\": \"Omar\"\", \"quote\": \"say \"hi\" now\"}"), json!({ "name": "Omar", "quote": "say \"hi\" now" }));
+        assert_eq!(repaired("{\"name\": \"Omar,\n\"age\": .5}"), json!({ "name": "Omar,", "age": 0.5 }));
+        assert_eq!(repaired("[1, 2]]"), json!([1, 2]));
+        assert_eq!(repaired("{\"host\": localhost, \"a\": }"), json!({ "host": "localhost", "a": null }));
+
+        let (_, fixes) = repair("{\n  \"a\": 1\n  \"b\": 2,\n}").unwrap();
+        assert_eq!(fixes, vec![
+            Fix { line: 3, description: "Added a missing comma" },
+            Fix { line: 4, description: "Removed a trailing comma" },
+        ]);
+    }
+}
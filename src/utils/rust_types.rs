@@ -0,0 +1,185 @@
+//
+// Generating Rust structs (with serde derives) from a document.
+//
+
+use serde_json::Value;
+
+use super::shape::{singular, type_name, Shape, TypeNames};
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false", "fn", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct", "trait",
+    "true", "type", "unsafe", "use", "where", "while", "abstract", "box", "gen", "yield",
+];
+
+struct Generator {
+    names: TypeNames,
+    /// The structs generated so far, in the order they were named.
+    structs: Vec<(String, String)>,
+}
+
+impl Generator {
+    /// The Rust type of a shape. Objects get a struct named after `name`.
+    fn type_of(&mut self, shape: &Shape, name: &str) -> String {
+        let kinds = [shape.object.is_some(), shape.array.is_some(), shape.string, shape.integer || shape.number, shape.boolean]
+            .iter()
+            .filter(|seen| **seen)
+            .count();
+
+        let rust_type = match kinds {
+            // Only nulls, or nothing at all.
+            0 => return "Option<serde_json::Value>".to_string(),
+            // Values of different types can only be held as they are.
+            2.. => return "serde_json::Value".to_string(),
+            _ if let Some(object) = &shape.object => {
+                let struct_name = self.names.unique(type_name(name));
+                let index = self.structs.len();
+                self.structs.push((struct_name.clone(), String::new()));
+
+                let mut body = String::new();
+                for (key, (property, _)) in &object.properties {
+                    let mut property_type = self.type_of(property, key);
+                    if !object.is_required(key) && !property_type.starts_with("Option<") {
+                        property_type = format!("Option<{}>", property_type);
+                    }
+
+                    let (field, needs_rename) = field_name(key);
+                    if needs_rename {
+                        body.push_str(&format!("    #[serde(rename = {})]\n", serde_json::to_string(key).unwrap()));
+                    }
+                    body.push_str(&format!("    pub {}: {},\n", field, property_type));
+                }
+
+                self.structs[index].1 = body;
+                struct_name
+            }
+            _ if let Some(items) = &shape.array => {
+                if items.is_empty() {
+                    "Vec<serde_json::Value>".to_string()
+                } else {
+                    format!("Vec<{}>", self.type_of(items, &singular(name)))
+                }
+            }
+            _ if shape.string => "String".to_string(),
+            _ if shape.number => "f64".to_string(),
+            _ if shape.integer => "i64".to_string(),
+            _ => "bool".to_string(),
+        };
+
+        if shape.null {
+            return format!("Option<{}>", rust_type);
+        }
+
+        return rust_type;
+    }
+}
+
+/// Turns a key into a field name, and tells whether serde needs to be told the original key.
+fn field_name(key: &str) -> (String, bool) {
+    let mut field = String::new();
+    let mut previous_is_lowercase = false;
+
+    for c in key.chars() {
+        if c.is_uppercase() {
+            if previous_is_lowercase {
+                field.push('_');
+            }
+            field.extend(c.to_lowercase());
+            previous_is_lowercase = false;
+        } else if c.is_alphanumeric() {
+            field.push(c);
+            previous_is_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        } else {
+            if !field.is_empty() && !field.ends_with('_') {
+                field.push('_');
+            }
+            previous_is_lowercase = false;
+        }
+    }
+
+    let field = field.trim_end_matches('_').to_string();
+    let field = match field.chars().next() {
+        None => "field".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("field_{}", field),
+        // These can't be raw identifiers.
+        Some(_) if matches!(field.as_str(), "self" | "super" | "crate") => format!("{}_", field),
+        Some(_) if KEYWORDS.contains(&field.as_str()) => return (format!("r#{}", field), field != key),
+        Some(_) => field,
+    };
+
+    let needs_rename = field != key;
+    return (field, needs_rename);
+}
+
+/// Generates Rust structs deriving serde's `Serialize` and `Deserialize` for the document, the root
+/// one named `root_name`. Keys that some of the objects at their place don't have are `Option`s.
+pub fn rust_types(json: &Value, root_name: &str) -> String {
+    let mut generator = Generator {
+        names: TypeNames::default(),
+        structs: vec![],
+    };
+
+    let shape = Shape::of(json);
+    let root_type = generator.type_of(&shape, root_name);
+
+    let mut output = String::from("use serde::{Deserialize, Serialize};\n\n");
+
+    // Roots that aren't objects get an alias, so there's still a type to deserialize into.
+    if shape.object.is_none() {
+        output.push_str(&format!("pub type {} = {};\n\n", type_name(root_name), root_type));
+    }
+
+    for (name, body) in &generator.structs {
+        output.push_str(&format!("#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n{}}}\n\n", name, body));
+    }
+
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+
+    return output;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rust_types() {
+        let json = json!({
+            "name": "todd",
+            "users": [
+                { "id": 1, "displayName": "Omar", "type": "admin", "score": 1.5, "settings": { "dark-mode": true } },
+                { "id": 2, "displayName": null, "type": "user", "score": 2 }
+            ],
+            "extra": [1, "a"]
+        });
+
+        assert_eq!(rust_types(&json, "Root"), "\
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Root {
+    pub name: String,
+    pub users: Vec<User>,
+    pub extra: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: i64,
+    #[serde(rename = \"displayName\")]
+    pub display_name: Option<String>,
+    pub r#type: String,
+    pub score: f64,
+    pub settings: Option<Settings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(rename = \"dark-mode\")]
+    pub dark_mode: bool,
+}
+");
+    }
+}
@@ -0,0 +1,235 @@
+//
+// Inferring the shape of a document (the types of its values, the keys of its objects, and what
+// its arrays hold) to describe it as a JSON Schema or generate types for it.
+//
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+
+/// Everything seen at one place of the document. Arrays merge the shapes of all their elements,
+/// so a place can have seen several types.
+#[derive(Debug, Default)]
+pub struct Shape {
+    pub null: bool,
+    pub boolean: bool,
+    pub integer: bool,
+    /// Numbers with a fraction or an exponent.
+    pub number: bool,
+    pub string: bool,
+    pub object: Option<ObjectShape>,
+    /// The shape of the elements of the arrays seen, if any arrays were.
+    pub array: Option<Box<Shape>>,
+}
+
+#[derive(Debug, Default)]
+pub struct ObjectShape {
+    /// The shape of each key, with how many of the objects had it.
+    pub properties: IndexMap<String, (Shape, usize)>,
+    /// How many objects were seen.
+    pub count: usize,
+}
+
+impl ObjectShape {
+    /// Whether every object seen had the key.
+    pub fn is_required(&self, key: &str) -> bool {
+        return self.properties.get(key).is_some_and(|(_, count)| *count == self.count);
+    }
+}
+
+impl Shape {
+    pub fn of(value: &Value) -> Shape {
+        let mut shape = Shape::default();
+        shape.add(value);
+
+        return shape;
+    }
+
+    /// Merges a value into the shape.
+    pub fn add(&mut self, value: &Value) {
+        match value {
+            Value::Null => self.null = true,
+            Value::Bool(_) => self.boolean = true,
+            Value::Number(number) if number.is_i64() || number.is_u64() => self.integer = true,
+            Value::Number(_) => self.number = true,
+            Value::String(_) => self.string = true,
+            Value::Object(map) => {
+                let object = self.object.get_or_insert_with(ObjectShape::default);
+                object.count += 1;
+
+                for (key, value) in map {
+                    let (shape, count) = object.properties.entry(key.clone()).or_default();
+                    shape.add(value);
+                    *count += 1;
+                }
+            }
+            Value::Array(values) => {
+                let items = self.array.get_or_insert_with(Box::default);
+                for value in values {
+                    items.add(value);
+                }
+            }
+        }
+    }
+
+    /// Whether nothing was seen, like the elements of empty arrays.
+    pub fn is_empty(&self) -> bool {
+        return !self.null && !self.boolean && !self.integer && !self.number && !self.string && self.object.is_none() && self.array.is_none();
+    }
+
+    /// The JSON Schema names of the types seen. Integers are numbers too, so they're left out when both were seen.
+    fn type_names(&self) -> Vec<&'static str> {
+        let mut names = vec![];
+
+        if self.object.is_some() { names.push("object"); }
+        if self.array.is_some() { names.push("array"); }
+        if self.string { names.push("string"); }
+        if self.number { names.push("number"); } else if self.integer { names.push("integer"); }
+        if self.boolean { names.push("boolean"); }
+        if self.null { names.push("null"); }
+
+        return names;
+    }
+
+    pub fn to_json_schema(&self) -> Value {
+        let mut schema = Map::new();
+
+        let type_names = self.type_names();
+        match type_names[..] {
+            [] => {}
+            [name] => {
+                schema.insert("type".to_string(), json!(name));
+            }
+            _ => {
+                schema.insert("type".to_string(), json!(type_names));
+            }
+        }
+
+        if let Some(object) = &self.object {
+            let properties: Map<String, Value> = object.properties
+                .iter()
+                .map(|(key, (shape, _))| (key.clone(), shape.to_json_schema()))
+                .collect();
+            let required: Vec<&String> = object.properties
+                .keys()
+                .filter(|key| object.is_required(key))
+                .collect();
+
+            schema.insert("properties".to_string(), Value::Object(properties));
+            if !required.is_empty() {
+                schema.insert("required".to_string(), json!(required));
+            }
+        }
+
+        if let Some(items) = &self.array && !items.is_empty() {
+            schema.insert("items".to_string(), items.to_json_schema());
+        }
+
+        return Value::Object(schema);
+    }
+}
+
+/// Turns a key into a type name: `user_settings` and `user-settings` become `UserSettings`.
+pub fn type_name(key: &str) -> String {
+    let name: String = key
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap();
+            return first.to_uppercase().chain(chars).collect::<String>();
+        })
+        .collect();
+
+    return match name.chars().next() {
+        None => "Value".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("Value{}", name),
+        Some(_) => name,
+    };
+}
+
+/// The name of an element of an array from the name of the array: `users` gives `user`.
+pub fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        return format!("{}y", stem);
+    }
+    if name.ends_with('s') && !name.ends_with("ss") && name.len() > 1 {
+        return name[..name.len() - 1].to_string();
+    }
+
+    return format!("{}Item", name);
+}
+
+/// Gives out unique type names, numbering the ones that were taken (`User`, `User2`...).
+#[derive(Default)]
+pub struct TypeNames {
+    taken: HashSet<String>,
+}
+
+impl TypeNames {
+    pub fn unique(&mut self, name: String) -> String {
+        let mut unique = name.clone();
+        let mut number = 2;
+        while self.taken.contains(&unique) {
+            unique = format!("{}{}", name, number);
+            number += 1;
+        }
+
+        self.taken.insert(unique.clone());
+        return unique;
+    }
+}
+
+/// Infers a JSON Schema describing the document. Keys are required when all the objects at
+/// their place have them, and array items are described by the merged shape of all elements.
+pub fn json_schema(json: &Value) -> Value {
+    let mut schema = Map::new();
+    schema.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+
+    if let Value::Object(inferred) = Shape::of(json).to_json_schema() {
+        schema.extend(inferred);
+    }
+
+    return Value::Object(schema);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_schema() {
+        let json = json!({
+            "name": "todd",
+            "users": [
+                { "id": 1, "email": "a@b.c", "score": 1 },
+                { "id": 2, "score": 2.5, "tags": [] }
+            ],
+            "parent": null
+        });
+
+        assert_eq!(json_schema(&json), json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "users": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "email": { "type": "string" },
+                            "score": { "type": "number" },
+                            "tags": { "type": "array" }
+                        },
+                        "required": ["id", "score"]
+                    }
+                },
+                "parent": { "type": "null" }
+            },
+            "required": ["name", "users", "parent"]
+        }));
+    }
+}
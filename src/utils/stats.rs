@@ -0,0 +1,322 @@
+//
+// Measuring parts of a document: how big and how deep they are, to find what bloats it.
+//
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use super::json::{path_to_pointer, PathSegment};
+use crate::i18n::tr;
+
+/// How many of the largest arrays and objects are listed.
+const LARGEST_LISTED: usize = 5;
+
+/// A summary of a whole document.
+#[derive(Debug, Default, PartialEq)]
+pub struct DocumentStats {
+    /// How many values of each type there are, the root included.
+    pub type_counts: IndexMap<&'static str, usize>,
+    pub max_depth: usize,
+    /// The arrays with the most elements, as JSON Pointers with their lengths, largest first.
+    pub largest_arrays: Vec<(String, usize)>,
+    /// The objects with the most keys, as JSON Pointers with their key counts, largest first.
+    pub largest_objects: Vec<(String, usize)>,
+    /// The compact serialized size of each top-level value, largest first.
+    pub top_level_sizes: Vec<(String, usize)>,
+    pub size: usize,
+}
+
+/// Walks the whole document to summarize it.
+pub fn document_stats(json: &Value) -> DocumentStats {
+    let mut stats = DocumentStats::default();
+    for name in ["object", "array", "string", "number", "boolean", "null"] {
+        stats.type_counts.insert(name, 0);
+    }
+
+    let mut path = vec![];
+    walk(json, &mut path, &mut stats);
+
+    stats.max_depth = nesting_depth(json);
+    stats.size = serialized_size(json);
+
+    let top_level: Vec<(PathSegment, &Value)> = match json {
+        Value::Object(map) => map.iter().map(|(key, value)| (PathSegment::Key(key.clone()), value)).collect(),
+        Value::Array(values) => values.iter().enumerate().map(|(index, value)| (PathSegment::Index(index), value)).collect(),
+        _ => vec![],
+    };
+    stats.top_level_sizes = top_level
+        .into_iter()
+        .map(|(segment, value)| (path_to_pointer(&[segment]), serialized_size(value)))
+        .collect();
+    stats.top_level_sizes.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    for list in [&mut stats.largest_arrays, &mut stats.largest_objects] {
+        list.sort_by(|(_, a), (_, b)| b.cmp(a));
+        list.truncate(LARGEST_LISTED);
+    }
+
+    return stats;
+}
+
+fn walk(value: &Value, path: &mut Vec<PathSegment>, stats: &mut DocumentStats) {
+    *stats.type_counts.entry(type_of(value)).or_default() += 1;
+
+    match value {
+        Value::Array(values) => {
+            stats.largest_arrays.push((path_to_pointer(path), values.len()));
+            for (index, value) in values.iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                walk(value, path, stats);
+                path.pop();
+            }
+        }
+        Value::Object(map) => {
+            stats.largest_objects.push((path_to_pointer(path), map.len()));
+            for (key, value) in map {
+                path.push(PathSegment::Key(key.clone()));
+                walk(value, path, stats);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+impl DocumentStats {
+    /// The summary as lines of text, for the stats popup.
+    pub fn to_lines(&self) -> Vec<String> {
+        let pointer = |pointer: &str| if pointer.is_empty() { tr!("(root)") } else { pointer.to_string() };
+
+        let mut lines = vec![
+            tr!("Size: {} compact", human_size(self.size)),
+            tr!("Max depth: {}", self.max_depth),
+            String::new(),
+            tr!("Values per type"),
+        ];
+        lines.extend(self.type_counts.iter().map(|(name, count)| format!("  {:<8} {}", name, count)));
+
+        lines.push(String::new());
+        lines.push(tr!("Largest arrays"));
+        lines.extend(self.largest_arrays.iter().map(|(path, len)| tr!("  {} ({} elements)", pointer(path), len)));
+
+        lines.push(String::new());
+        lines.push(tr!("Largest objects"));
+        lines.extend(self.largest_objects.iter().map(|(path, len)| tr!("  {} ({} keys)", pointer(path), len)));
+
+        lines.push(String::new());
+        lines.push(tr!("Size of the top-level values"));
+        lines.extend(self.top_level_sizes.iter().map(|(path, size)| {
+            let share = if self.size > 0 { *size as f64 * 100.0 / self.size as f64 } else { 0.0 };
+            return format!("  {} {} ({:.1}%)", path, human_size(*size), share);
+        }));
+
+        return lines;
+    }
+}
+
+/// Sums and extremes of a list of numbers.
+#[derive(Debug, PartialEq)]
+pub struct Aggregate {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    /// How many of the values weren't numbers and were left out.
+    pub skipped: usize,
+}
+
+impl Aggregate {
+    /// Aggregates the numbers among the values. `None` if there are none.
+    pub fn of<'a>(values: impl IntoIterator<Item = &'a Value>) -> Option<Aggregate> {
+        let mut aggregate = Aggregate { count: 0, sum: 0.0, min: f64::INFINITY, max: f64::NEG_INFINITY, skipped: 0 };
+
+        for value in values {
+            let Some(number) = value.as_f64() else {
+                aggregate.skipped += 1;
+                continue;
+            };
+
+            aggregate.count += 1;
+            aggregate.sum += number;
+            aggregate.min = aggregate.min.min(number);
+            aggregate.max = aggregate.max.max(number);
+        }
+
+        return (aggregate.count > 0).then_some(aggregate);
+    }
+
+    pub fn average(&self) -> f64 {
+        return self.sum / self.count as f64;
+    }
+
+    /// The aggregate on one line, for the footer.
+    pub fn summary(&self) -> String {
+        let mut summary = tr!(
+            "count {} · sum {} · avg {} · min {} · max {}",
+            self.count,
+            format_number(self.sum),
+            format_number(self.average()),
+            format_number(self.min),
+            format_number(self.max),
+        );
+        if self.skipped > 0 {
+            summary.push_str(&tr!(" ({} non-numeric skipped)", self.skipped));
+        }
+
+        return summary;
+    }
+}
+
+/// Formats a number without a fraction when it has none, and with at most 4 decimals otherwise.
+pub fn format_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1e15 {
+        return format!("{}", number as i64);
+    }
+
+    let formatted = format!("{:.4}", number);
+    return formatted.trim_end_matches('0').trim_end_matches('.').to_string();
+}
+
+/// The group of the elements that don't have the key.
+pub const MISSING_GROUP: &str = "(missing)";
+
+/// Groups the elements of an array by the value they have at a key, in the order the groups are
+/// first seen. Strings are their own group names, other values are named after their JSON.
+pub fn group_by(elements: &[Value], key: &str) -> IndexMap<String, Vec<Value>> {
+    let mut groups: IndexMap<String, Vec<Value>> = IndexMap::new();
+
+    for element in elements {
+        let name = match element.get(key) {
+            Some(Value::String(string)) => string.clone(),
+            Some(value) => value.to_string(),
+            None => MISSING_GROUP.to_string(),
+        };
+        groups.entry(name).or_default().push(element.clone());
+    }
+
+    return groups;
+}
+
+/// The JSON name of the type of a value.
+pub fn type_of(value: &Value) -> &'static str {
+    return match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+}
+
+/// How many values are nested in the value, at any depth.
+pub fn descendants_count(value: &Value) -> usize {
+    return match value {
+        Value::Array(values) => values.iter().map(|value| 1 + descendants_count(value)).sum(),
+        Value::Object(map) => map.values().map(|value| 1 + descendants_count(value)).sum(),
+        _ => 0,
+    };
+}
+
+/// How many levels of objects and arrays are nested in the value. Scalars and empty containers have none.
+pub fn nesting_depth(value: &Value) -> usize {
+    let children: Box<dyn Iterator<Item = &Value>> = match value {
+        Value::Array(values) => Box::new(values.iter()),
+        Value::Object(map) => Box::new(map.values()),
+        _ => return 0,
+    };
+
+    return children.map(|child| 1 + nesting_depth(child)).max().unwrap_or(0);
+}
+
+/// The size of the value serialized as compact JSON.
+pub fn serialized_size(value: &Value) -> usize {
+    return serde_json::to_vec(value).map(|bytes| bytes.len()).unwrap_or_default();
+}
+
+/// Formats a number of bytes for people: `512 B`, `1.5 KB`, `3.2 MB`.
+pub fn human_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    return format!("{:.1} {}", size, UNITS[unit]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_node_stats() {
+        let value = json!({ "a": [1, { "b": null }], "c": "d" });
+
+        assert_eq!(descendants_count(&value), 5);
+        assert_eq!(nesting_depth(&value), 3);
+        assert_eq!(nesting_depth(&json!([])), 0);
+        assert_eq!(serialized_size(&value), r#"{"a":[1,{"b":null}],"c":"d"}"#.len());
+        assert_eq!(type_of(&value["a"]), "array");
+
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(1536), "1.5 KB");
+        assert_eq!(human_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let values = json!([100, 200.5, "n/a", 300, -0.25]);
+        let aggregate = Aggregate::of(values.as_array().unwrap()).unwrap();
+
+        assert_eq!(aggregate, Aggregate { count: 4, sum: 600.25, min: -0.25, max: 300.0, skipped: 1 });
+        assert_eq!(aggregate.summary(), "count 4 · sum 600.25 · avg 150.0625 · min -0.25 · max 300 (1 non-numeric skipped)");
+        assert_eq!(Aggregate::of(json!(["a"]).as_array().unwrap()), None);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let elements = json!([
+            { "status": "active", "id": 1 },
+            { "status": "archived", "id": 2 },
+            { "status": "active", "id": 3 },
+            { "status": 1 },
+            { "id": 5 }
+        ]);
+
+        let groups = group_by(elements.as_array().unwrap(), "status");
+        let counts: Vec<(&str, usize)> = groups.iter().map(|(name, elements)| (name.as_str(), elements.len())).collect();
+
+        assert_eq!(counts, vec![("active", 2), ("archived", 1), ("1", 1), (MISSING_GROUP, 1)]);
+        assert_eq!(groups["active"][1], json!({ "status": "active", "id": 3 }));
+    }
+
+    #[test]
+    fn test_document_stats() {
+        let json = json!({
+            "users": [{ "id": 1, "tags": ["a", "b", "c"] }, { "id": 2, "tags": [] }],
+            "name": "todd",
+            "active": true
+        });
+
+        let stats = document_stats(&json);
+
+        assert_eq!(stats.type_counts.get("object"), Some(&3));
+        assert_eq!(stats.type_counts.get("array"), Some(&3));
+        assert_eq!(stats.type_counts.get("string"), Some(&4));
+        assert_eq!(stats.type_counts.get("number"), Some(&2));
+        assert_eq!(stats.type_counts.get("null"), Some(&0));
+        assert_eq!(stats.max_depth, 4);
+        assert_eq!(stats.largest_arrays[0], ("/users/0/tags".to_string(), 3));
+        assert_eq!(stats.largest_objects[0], ("".to_string(), 3));
+        assert_eq!(stats.top_level_sizes.iter().map(|(path, _)| path.as_str()).collect::<Vec<_>>(), vec!["/users", "/name", "/active"]);
+    }
+}
@@ -0,0 +1,146 @@
+//
+// Recognizing timestamps (epoch seconds or milliseconds, and ISO 8601 date-times) to show them
+// as readable UTC dates.
+//
+
+use serde_json::Value;
+
+/// Epoch seconds from 2001-09-09 to 2096-10-02. Numbers outside of it are more likely ids or counts.
+const EPOCH_SECONDS_RANGE: std::ops::RangeInclusive<i64> = 1_000_000_000..=4_000_000_000;
+/// The same range in milliseconds.
+const EPOCH_MILLIS_RANGE: std::ops::RangeInclusive<i64> = 1_000_000_000_000..=4_000_000_000_000;
+
+/// The timestamp a value holds as seconds since the epoch, if it looks like one.
+pub fn timestamp_of(value: &Value) -> Option<i64> {
+    return match value {
+        Value::Number(number) => {
+            let number = number.as_i64()?;
+            if EPOCH_SECONDS_RANGE.contains(&number) {
+                Some(number)
+            } else if EPOCH_MILLIS_RANGE.contains(&number) {
+                Some(number / 1000)
+            } else {
+                None
+            }
+        }
+        Value::String(string) => parse_iso_8601(string),
+        _ => None,
+    };
+}
+
+/// Parses an ISO 8601 date-time like `2024-03-02T14:05:00+02:00` into seconds since the epoch.
+/// The time is required. Without a zone, it's taken as UTC.
+pub fn parse_iso_8601(text: &str) -> Option<i64> {
+    let bytes = text.as_bytes();
+    if bytes.len() < 16 || bytes[4] != b'-' || bytes[7] != b'-' || !matches!(bytes[10], b'T' | b't' | b' ') || bytes[13] != b':' {
+        return None;
+    }
+
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = text.get(range)?;
+        if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        return digits.parse().ok();
+    };
+
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute) = (number(11..13)?, number(14..16)?);
+
+    let mut rest = &text[16..];
+    let mut second = 0;
+    if let Some(after_colon) = rest.strip_prefix(':') {
+        second = number(17..19)?;
+        rest = after_colon.get(2..)?;
+
+        // Fractions of a second are dropped.
+        if let Some(fraction) = rest.strip_prefix(['.', ',']) {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return None;
+            }
+            rest = &fraction[digits..];
+        }
+    }
+
+    let offset = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let zone = rest[1..].replace(':', "");
+            if zone.len() != 4 || !zone.bytes().all(|byte| byte.is_ascii_digit()) {
+                return None;
+            }
+            sign * (zone[..2].parse::<i64>().ok()? * 3600 + zone[2..].parse::<i64>().ok()? * 60)
+        }
+    };
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    return Some(days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset);
+}
+
+/// Formats seconds since the epoch as `2024-03-02 14:05 UTC`.
+pub fn format_utc(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86400);
+    let seconds = timestamp.rem_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+
+    return format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, seconds / 3600, seconds % 3600 / 60);
+}
+
+/// The days since 1970-01-01 of a date of the proleptic Gregorian calendar (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    return era * 146097 + day_of_era - 719468;
+}
+
+/// The date of a number of days since 1970-01-01, the inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return (year, month, day);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_timestamps() {
+        let utc = |value: Value| timestamp_of(&value).map(format_utc);
+
+        assert_eq!(utc(json!(1709388300)), Some("2024-03-02 14:05 UTC".to_string()));
+        assert_eq!(utc(json!(1709388300123_i64)), Some("2024-03-02 14:05 UTC".to_string()));
+        assert_eq!(utc(json!("2024-03-02T14:05:59Z")), Some("2024-03-02 14:05 UTC".to_string()));
+        assert_eq!(utc(json!("2024-03-02T16:05:00.123+02:00")), Some("2024-03-02 14:05 UTC".to_string()));
+        assert_eq!(utc(json!("2024-03-01 20:05-1800")), Some("2024-03-02 14:05 UTC".to_string()));
+        assert_eq!(utc(json!("1969-12-31T23:59:00Z")), Some("1969-12-31 23:59 UTC".to_string()));
+
+        assert_eq!(utc(json!(42)), None);
+        assert_eq!(utc(json!(1.5e9)), None);
+        assert_eq!(utc(json!("2024-03-02")), None);
+        assert_eq!(utc(json!("2024-13-02T14:05Z")), None);
+        assert_eq!(utc(json!("2024-03-02T14:05 tomorrow")), None);
+    }
+}
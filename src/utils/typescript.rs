@@ -0,0 +1,133 @@
+//
+// Generating TypeScript type definitions from a document.
+//
+
+use serde_json::Value;
+
+use super::shape::{singular, type_name, Shape, TypeNames};
+
+struct Generator {
+    names: TypeNames,
+    /// The interfaces generated so far, in the order they were named.
+    interfaces: Vec<(String, String)>,
+}
+
+impl Generator {
+    /// The TypeScript type of a shape. Objects get an interface named after `name`.
+    fn type_of(&mut self, shape: &Shape, name: &str) -> String {
+        let mut types = vec![];
+
+        if let Some(object) = &shape.object {
+            let interface_name = self.names.unique(type_name(name));
+            let index = self.interfaces.len();
+            self.interfaces.push((interface_name.clone(), String::new()));
+
+            let mut body = String::new();
+            for (key, (property, _)) in &object.properties {
+                let optional = if object.is_required(key) { "" } else { "?" };
+                let property_type = self.type_of(property, key);
+                body.push_str(&format!("  {}{}: {};\n", property_name(key), optional, property_type));
+            }
+
+            self.interfaces[index].1 = body;
+            types.push(interface_name);
+        }
+
+        if let Some(items) = &shape.array {
+            let item_type = if items.is_empty() { "unknown".to_string() } else { self.type_of(items, &singular(name)) };
+            if item_type.contains(' ') {
+                types.push(format!("({})[]", item_type));
+            } else {
+                types.push(format!("{}[]", item_type));
+            }
+        }
+
+        if shape.string { types.push("string".to_string()); }
+        if shape.integer || shape.number { types.push("number".to_string()); }
+        if shape.boolean { types.push("boolean".to_string()); }
+        if shape.null { types.push("null".to_string()); }
+
+        if types.is_empty() {
+            return "unknown".to_string();
+        }
+
+        return types.join(" | ");
+    }
+}
+
+/// Quotes keys that aren't valid identifiers.
+fn property_name(key: &str) -> String {
+    let is_identifier = key.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        return key.to_string();
+    }
+    return serde_json::to_string(key).unwrap();
+}
+
+/// Generates TypeScript interfaces for the document, the root one named `root_name`. Keys that
+/// some of the objects at their place don't have are optional.
+pub fn typescript_types(json: &Value, root_name: &str) -> String {
+    let mut generator = Generator {
+        names: TypeNames::default(),
+        interfaces: vec![],
+    };
+
+    let shape = Shape::of(json);
+    let root_type = generator.type_of(&shape, root_name);
+
+    let mut output = String::new();
+
+    // Roots that aren't objects get an alias, so there's still a type to import.
+    if shape.object.is_none() {
+        output.push_str(&format!("export type {} = {};\n\n", type_name(root_name), root_type));
+    }
+
+    for (name, body) in &generator.interfaces {
+        output.push_str(&format!("export interface {} {{\n{}}}\n\n", name, body));
+    }
+
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+
+    return output;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typescript_types() {
+        let json = json!({
+            "name": "todd",
+            "users": [
+                { "id": 1, "display-name": "Omar", "address": { "city": "Cairo" } },
+                { "id": 2, "display-name": null }
+            ],
+            "tags": []
+        });
+
+        assert_eq!(typescript_types(&json, "Root"), "\
+export interface Root {
+  name: string;
+  users: User[];
+  tags: unknown[];
+}
+
+export interface User {
+  id: number;
+  \"display-name\": string | null;
+  address?: Address;
+}
+
+export interface Address {
+  city: string;
+}
+");
+
+        assert_eq!(typescript_types(&json!([1, "a"]), "Root"), "export type Root = (string | number)[];\n");
+    }
+}
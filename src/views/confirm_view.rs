@@ -0,0 +1,52 @@
+//
+// The confirmation popup: a question, and the keys answering it.
+//
+
+use ratatui::{
+    layout::{Alignment, Rect}, style::{Modifier, Style}, text::{Line, Span}, widgets::{Block, Borders, Clear, Padding, Paragraph}, Frame
+};
+
+use crate::app::App;
+
+impl App {
+    pub fn draw_confirm_dialog(&self, frame: &mut Frame) {
+        let Some(confirm) = &self.confirm else {
+            return;
+        };
+
+        let mut choices = vec![];
+        for (index, choice) in confirm.choices.iter().enumerate() {
+            if index > 0 {
+                choices.push(Span::from("   "));
+            }
+            choices.push(Span::styled(format!("({})", choice.key), Style::default().fg(self.config.theme.accent).add_modifier(Modifier::BOLD)));
+            choices.push(Span::from(format!(" {}", choice.label)));
+        }
+        let choices = Line::from(choices);
+
+        // Sized to the text, plus the borders and the padding, and centered.
+        let area = frame.area();
+        let text_width = confirm.message.chars().count().max(choices.width()).max(confirm.title.chars().count() + 2);
+        let width = (text_width as u16 + 4).min(area.width);
+        let height = 5.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let dialog = Paragraph::new(vec![Line::from(confirm.message.as_str()), Line::default(), choices])
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .title(format!(" {} ", confirm.title))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.config.theme.warning))
+                    .padding(Padding::horizontal(1))
+            );
+
+        frame.render_widget(Clear, dialog_area);
+        frame.render_widget(dialog, dialog_area);
+    }
+}
@@ -2,16 +2,28 @@
 // The main view.
 //
 
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
+
+use serde_json::Value;
+use unicode_width::UnicodeWidthStr;
 
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect}, style::{Color, Style}, symbols::scrollbar, text::{Line, Span}, widgets::{Block, Borders, Padding, Paragraph, Scrollbar, ScrollbarOrientation}, Frame
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect}, style::{Color, Modifier, Style}, symbols::scrollbar, text::{Line, Span}, widgets::{Block, Borders, Clear, Padding, Paragraph, Scrollbar, ScrollbarOrientation}, Frame
 };
 
-use crate::{app::{App, CurrentScreen, CurrentlyEditing, ReportedMessageKinds}, helpers::get_centered_rect};
+use crate::{app::{input_feedback, App, CurrentScreen, CurrentlyEditing, EditingMode, ReportedMessageKinds}, helpers::{get_centered_rect, get_centered_rect_of_size}, highlight_rules::highlight_styles, i18n::{fill, tr, translate}, mouse::Drag, schema::value_choice_label, utils::{env::resolve_placeholders, json::{entries_by_line, get_line_at_path, get_path_to_insert_into, path_to_pointer, path_to_string, PathSegment}, readable::{readable_string, readable_value}, timestamp::{format_utc, timestamp_of}}};
+
+/// How many problems the problems panel shows at once.
+const MAX_PROBLEMS_PANEL_ROWS: usize = 8;
+/// How many items the dropdowns of the edit popup list at once.
+const MAX_DROPDOWN_ROWS: usize = 6;
+/// The narrowest the edit popup gets, unless the terminal is narrower.
+const MIN_POPUP_WIDTH: u16 = 30;
+/// The most rows the inputs of the edit popup grow to for long content, before scrolling.
+const MAX_POPUP_INPUT_ROWS: u16 = 8;
 
 
-impl<'a> App<'a> {
+impl App {
     pub fn draw_main_view(&mut self, frame: &mut Frame) {
         let layout = Layout::default()
             .direction(Direction::Vertical)
@@ -20,15 +32,88 @@ impl<'a> App<'a> {
                 Constraint::Length(3),
             ])
             .split(frame.area());
+
+        // The problems panel takes the bottom of the tree's space.
+        let layout = if matches!(self.current_screen, CurrentScreen::Problems) {
+            let body_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(1),
+                    Constraint::Length(self.problems.len().min(MAX_PROBLEMS_PANEL_ROWS) as u16 + 2),
+                ])
+                .split(layout[0]);
+
+            self.draw_problems_panel(frame, body_layout[1]);
+
+            Rc::from([body_layout[0], layout[1]])
+        } else {
+            layout
+        };
         
         self.viewport_lines_count = layout[0].height as usize;
         
-        self.draw_pairs_widget(frame, &layout);
+        if matches!(self.current_screen, CurrentScreen::Table) {
+            self.draw_table_widget(frame, layout[0]);
+        } else {
+            self.draw_pairs_widget(frame, &layout);
+        }
         self.draw_footer_widget(frame, &layout);
 
         if self.currently_editing.is_some() {
             self.draw_insert_popup_widget(frame);
         }
+
+        if let Some(preview) = &self.preview {
+            let area = get_centered_rect(70, 60, frame.area());
+            let lines: Vec<Line> = preview.lines.iter().map(|line| Line::from(line.as_str())).collect();
+
+            let popup = Paragraph::new(lines)
+                .scroll((preview.scroll as u16, 0))
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", preview.title))
+                        .borders(Borders::ALL)
+                        .padding(Padding::horizontal(1))
+                );
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(popup, area);
+        }
+
+        self.draw_confirm_dialog(frame);
+        self.draw_tutorial(frame);
+    }
+
+    fn draw_problems_panel(&self, frame: &mut Frame, area: Rect) {
+        // Keep the selected problem in view.
+        let first = self.selected_problem.saturating_sub(MAX_PROBLEMS_PANEL_ROWS - 1);
+
+        let lines: Vec<Line> = self.problems
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(MAX_PROBLEMS_PANEL_ROWS)
+            .map(|(index, problem)| {
+                let line = Line::from(vec![
+                    Span::from(format!("{}  ", path_to_pointer(&problem.path))).style(Style::default().fg(self.config.theme.accent)),
+                    Span::from(problem.message.as_str()),
+                ]);
+
+                if index == self.selected_problem {
+                    return line.style(self.config.theme.cursor());
+                }
+                return line;
+            })
+            .collect();
+
+        let panel = Paragraph::new(lines).block(
+            Block::default()
+                .title(tr!(" Problems ({}) ", self.problems.len()))
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1))
+        );
+
+        frame.render_widget(panel, area);
     }
 
     fn draw_pairs_widget(&mut self, frame: &mut Frame, layout: &Rc<[Rect]>) {
@@ -41,7 +126,7 @@ impl<'a> App<'a> {
         if json_length == 0 {
             let list_paragraph_widget = Paragraph::new(
                 vec![
-                    Line::from("Object is empty."),
+                    Line::from(tr!("Object is empty.")),
                 ],
             );
 
@@ -52,16 +137,53 @@ impl<'a> App<'a> {
             let mut pairs = vec![];
             let lines_count = self.insert_data_to_tree(&mut pairs, &self.json, 0);
             self.lines_count = lines_count;
+            // The document may have shrunk under the cursor (e.g. after applying a patch).
+            self.line_at_cursor = self.line_at_cursor.min(lines_count.saturating_sub(1));
             self.json_pairs = pairs.clone();
 
-            let focused_pair_style = Style::default().bg(Color::Green).fg(Color::Black);
+            let theme = self.config.theme.clone();
+            let focused_pair_style = theme.cursor();
 
             let mut lines: Vec<Line> = vec![];
-            let mut array_key_index = 0;
-            let mut last_indentation = 0;
 
             self.search_matches.clear();
 
+            // Comments are shown at the end of the line of the value they belong to.
+            let mut comments_by_line: HashMap<usize, Vec<&str>> = HashMap::new();
+            let leading = self.comments.leading.iter().flat_map(|(path, comments)| comments.iter().map(move |comment| (path, comment)));
+            let trailing = self.comments.trailing.iter();
+            for (path, comment) in leading.chain(trailing) {
+                if let Some(line) = get_line_at_path(path, &self.json) {
+                    comments_by_line.entry(line).or_default().push(comment);
+                }
+            }
+
+            // With a schema, a gutter marks the lines holding values that don't match it.
+            let schema_error_lines = self.schema.as_ref().map(|_| self.schema_error_lines());
+            // Keys the schema requires get a `*`, and objects missing some list them.
+            let (required_key_lines, missing_keys) = self.required_keys_marks();
+            // The width of the text, inside the horizontal padding of the view.
+            let wrap_width = (layout[0].width as usize).saturating_sub(4).max(1);
+            let number_width = lines_count.to_string().len();
+            // The key or index and the value of every line, which the pairs don't tell for the elements
+            // of arrays and for objects and arrays.
+            let line_entries = entries_by_line(&self.json);
+            let exchange_mark_line = self.exchange_mark.as_ref().and_then(|path| get_line_at_path(path, &self.json));
+            let move_mark_lines: Vec<usize> = match &self.move_mark {
+                Some((path, count)) => (0..*count)
+                    .filter_map(|offset| {
+                        let mut path = path.clone();
+                        if let Some(PathSegment::Index(index)) = path.last_mut() {
+                            *index += offset;
+                        }
+                        get_line_at_path(&path, &self.json)
+                    })
+                    .collect(),
+                None => vec![],
+            };
+            let visual_lines = self.visual_lines();
+            let drop_line = self.drag.as_ref().and_then(Drag::drop_path).and_then(|path| get_line_at_path(&path, &self.json));
+
             for (current_line, mut pair) in pairs.into_iter().enumerate() {
                 let indentation_padding: String = (0..pair.indentation - 1).map(|_| "    ").collect();
                 
@@ -72,97 +194,189 @@ impl<'a> App<'a> {
                     highlight_key = true;
                 }
 
-                // If the current indentation is smaller, then we went up in the json. Reset the array index.
-                if last_indentation > pair.indentation {
-                    last_indentation = pair.indentation;
-                    array_key_index = 0;
+                let (rule_key_style, rule_value_style) = highlight_styles(&self.config.highlight_rules, &pair.key, pair.value.as_ref());
+                // The key's color of the theme, unless a highlight rule styles it.
+                let mut key_style = rule_key_style.unwrap_or(Style::default().fg(theme.key));
+
+                // The elements of arrays are labeled `[index]`, dimmed and without a colon, to tell
+                // them apart from keys.
+                let (segment, line_value) = line_entries.get(current_line).map(|(segment, value)| (segment, *value)).unzip();
+                let mut separator = ":";
+                if let Some(PathSegment::Index(index)) = segment {
+                    pair.key = format!("[{}]", index);
+                    key_style = Style::default().fg(theme.dimmed);
+                    separator = "";
                 }
-                
-                // If the current indentation is equal, then we are in the same array. Increment the array index.
-                if pair.key == "" {
-                    if last_indentation != pair.indentation {
-                        last_indentation = pair.indentation;
-                        array_key_index = 0;
-                    }
 
-                    array_key_index += 1;
-                    pair.key = format!("{}", array_key_index);
+                if required_key_lines.contains(&current_line) {
+                    pair.key.push('*');
                 }
 
                 let is_line_focused = self.line_at_cursor == current_line;
 
-                let mut line = Line::from(
-                    match &pair.value { // A Line is returned here.
-                        Some(value) => {
-                            // Colorize the value part of the line/pair based on the type of the value. Kinda like syntax highlighting.
-                            let mut value_span = Span::from(format!("{}", value));
-                            if !is_line_focused { // Do not set the colored text if the we are hovering over this line because there's a bg color applied in that case.
-                                if value.is_boolean() {
-                                    value_span = value_span.style(Style::default().fg(Color::Red));
-                                } else if value.is_number() {
-                                    value_span = value_span.style(Style::default().fg(Color::Rgb(212, 188, 125))); // yellowish color.
-                                } else if value.is_null() {
-                                    value_span = value_span.style(Style::default().fg(Color::Rgb(243, 139, 168))); // pinkish color.
-                                } else {
-                                    value_span = value_span.style(Style::default().fg(Color::Green));
-                                }
+                let mut line = match &pair.value { // A Line is returned here.
+                    Some(value) => {
+                        // Colorize the value part of the line/pair based on the type of the value. Kinda like syntax highlighting.
+                        let mut value_span = Span::from(readable_value(value));
+                        if !is_line_focused { // Do not set the colored text if the we are hovering over this line because there's a bg color applied in that case.
+                            if value.is_boolean() {
+                                value_span = value_span.style(Style::default().fg(theme.boolean));
+                            } else if value.is_number() {
+                                value_span = value_span.style(Style::default().fg(theme.number));
+                            } else if value.is_null() {
+                                value_span = value_span.style(Style::default().fg(theme.null));
+                            } else {
+                                value_span = value_span.style(Style::default().fg(theme.string));
                             }
-                            
-                            // Highlight search matches if found for the value.
-                            if !self.search_widget.content().is_empty() && value.to_string().to_lowercase().contains(self.search_widget.content()) {
-                                // Check if this current wasn't already added by matching the key of the pair. If not, 
-                                // save it to the matches.
-                                match self.search_matches.last() {
-                                    Some(last) => {
-                                        if *last != current_line {
-                                            self.search_matches.push(current_line);
-                                        }
-                                    }
-                                    None => {
+
+                            if let Some(style) = rule_value_style {
+                                value_span = value_span.patch_style(style);
+                            }
+                        }
+                        
+                        // Highlight search matches if found for the value.
+                        if !self.search_widget.content().is_empty() && value.to_string().to_lowercase().contains(self.search_widget.content()) {
+                            // Check if this current wasn't already added by matching the key of the pair. If not, 
+                            // save it to the matches.
+                            match self.search_matches.last() {
+                                Some(last) => {
+                                    if *last != current_line {
                                         self.search_matches.push(current_line);
                                     }
                                 }
-                                
-                                value_span.style.bg = Some(Color::Rgb(246, 118, 111));
-                                value_span.style.fg = Some(Color::default());
-                            }
-                            
-                            // Highlight search matches if found for the key.
-                            let mut key_span = Span::from(pair.key);
-                            if highlight_key {
-                                key_span = key_span.style(Style::default().bg(Color::Rgb(246, 118, 111))); // Reddish
-                                key_span.style.fg = Some(Color::default());
+                                None => {
+                                    self.search_matches.push(current_line);
+                                }
                             }
                             
-                            Span::from(indentation_padding) + key_span + Span::from(": ") + value_span // Concatenating two `Span`s makeup a `Line`.
-                        },
-                        None => {
-                            // Match against if this key's value is an array or another object.
-                            match pair.is_array_value {
-                                true => {
-                                    let text = format!("{}{}", indentation_padding, pair.key);
-
-                                    Line::from(Span::from(text).style(Style::default()))
-                                },
-                                false => {
-                                    let text = format!("{}{}:", indentation_padding, pair.key);
-
-                                    Line::from(Span::from(text).style(Style::default()))
-                                },
-                            }
+                            value_span.style.bg = Some(theme.search_match);
+                            value_span.style.fg = Some(Color::default());
+                        }
+                        
+                        // Highlight search matches if found for the key.
+                        let mut key_span = Span::from(readable_string(&pair.key));
+                        if !is_line_focused {
+                            key_span = key_span.style(key_style);
                         }
+                        if highlight_key {
+                            key_span = key_span.style(Style::default().bg(theme.search_match));
+                            key_span.style.fg = Some(Color::default());
+                        }
+                        
+                        Span::from(indentation_padding) + key_span + Span::from(format!("{} ", separator)) + value_span // Concatenating two `Span`s makeup a `Line`.
+                    },
+                    None => {
+                        // Match against if this key's value is an array or another object.
+                        match pair.is_array_value {
+                            true => {
+                                let text = readable_string(&pair.key);
+
+                                Span::from(indentation_padding) + Span::from(text).style(if is_line_focused { Style::default() } else { key_style })
+                            },
+                            false => {
+                                let text = format!("{}{}", readable_string(&pair.key), separator);
+                                let mut line = Span::from(indentation_padding) + Span::from(text).style(if is_line_focused { Style::default() } else { key_style });
+
+                                // Empty objects and arrays, and arrays, are told apart from objects.
+                                let shape = match line_value {
+                                    Some(Value::Object(map)) if map.is_empty() => " {}",
+                                    Some(Value::Array(values)) if values.is_empty() => " []",
+                                    Some(Value::Array(_)) => " [...]",
+                                    _ => "",
+                                };
+                                if !shape.is_empty() {
+                                    line.push_span(Span::styled(shape, if is_line_focused { Style::default() } else { Style::default().fg(theme.dimmed) }));
+                                }
+
+                                line
+                            },
+                        }
+                    }
+                }
+                .style(
+                    if is_line_focused {
+                        focused_pair_style
+                    } else if visual_lines.as_ref().is_some_and(|lines| lines.contains(&current_line)) {
+                        // The elements selected in visual mode.
+                        Style::default().add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::default()
                     }
-                    .style(
-                        if is_line_focused { focused_pair_style } else { Style::default() },
-                    ),
                 );
 
+                // The type's glyph goes after the indentation.
+                if let Some(value) = line_value && let Some(icon) = self.config.type_icons.icon(value) {
+                    let icon_style = if is_line_focused {
+                        focused_pair_style
+                    } else {
+                        Style::default().fg(match value {
+                            Value::Bool(_) => theme.boolean,
+                            Value::Number(_) => theme.number,
+                            Value::Null => theme.null,
+                            Value::String(_) => theme.string,
+                            Value::Object(_) | Value::Array(_) => theme.accent,
+                        })
+                    };
+                    line.spans.insert(1, Span::styled(icon, icon_style));
+                }
+
+                if self.show_timestamps && let Some(timestamp) = pair.value.as_ref().and_then(timestamp_of) {
+                    let annotation_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.dimmed) };
+                    line.push_span(Span::styled(format!("  → {}", format_utc(timestamp)), annotation_style));
+                }
+
+                if self.show_env_values && let Some(resolved) = pair.value.as_ref().and_then(serde_json::Value::as_str).and_then(resolve_placeholders) {
+                    let annotation_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.dimmed) };
+                    line.push_span(Span::styled(format!("  = {}", resolved), annotation_style));
+                }
+
+                if exchange_mark_line == Some(current_line) {
+                    let mark_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.accent) };
+                    line.push_span(Span::styled(tr!("  ⇄ marked to swap"), mark_style));
+                }
+                if move_mark_lines.contains(&current_line) {
+                    let mark_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.accent) };
+                    line.push_span(Span::styled(tr!("  ↳ marked to move"), mark_style));
+                }
+                if drop_line == Some(current_line) {
+                    let mark_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.accent) };
+                    line.push_span(Span::styled(tr!("  ← drop here"), mark_style));
+                }
+
+                if let Some(missing) = missing_keys.get(&current_line) {
+                    let missing_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.error) };
+                    line.push_span(Span::styled(tr!("  missing: {}", missing.join(", ")), missing_style));
+                }
+
+                if let Some(comments) = comments_by_line.get(&current_line) {
+                    let comment_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.dimmed) };
+                    line.push_span(Span::styled(format!("  {}", comments.join(" ")), comment_style));
+                }
+
+                if self.config.number {
+                    let number_style = if is_line_focused { focused_pair_style } else { Style::default().fg(theme.dimmed) };
+                    line.spans.insert(0, Span::styled(format!("{:>width$} ", current_line + 1, width = number_width), number_style));
+                }
+
+                if let Some(schema_error_lines) = &schema_error_lines {
+                    let marker = if schema_error_lines.contains(&current_line) {
+                        Span::styled("● ", Style::default().fg(theme.error))
+                    } else {
+                        Span::from("  ")
+                    };
+                    line.spans.insert(0, marker);
+                }
+
                 // Fill up the line till the end of the terminal's width to have the hover background
                 // span the entire line in the terminal and not just cover the text characters.
                 // Purely cosmetic.
                 if is_line_focused {
-                    let terminal_width = self.size.width as usize;
                     let content_length: usize = line.iter().map(|span| span.width()).sum(); 
+                    // A wrapped line is filled up to the end of its last row, not past it.
+                    let terminal_width = match self.config.wrap {
+                        true => content_length.div_ceil(wrap_width).max(1) * wrap_width,
+                        false => self.size.width as usize,
+                    };
 
                     if content_length < terminal_width {
                         let padding = " ".repeat(terminal_width - content_length);
@@ -175,10 +389,21 @@ impl<'a> App<'a> {
             }
 
             self.vertical_scroll_state = self.vertical_scroll_state.content_length(lines.len());
+
+            let (lines, scroll, row_lines) = match self.config.wrap {
+                true => self.wrap_lines(lines, wrap_width, layout[0].height as usize),
+                false => {
+                    let row_lines = (0..lines.len()).collect();
+                    (lines, self.vertical_scroll, row_lines)
+                }
+            };
+            // For the mouse to find the line under a row.
+            self.tree_area = layout[0];
+            self.row_lines = row_lines.into_iter().skip(scroll).take(layout[0].height as usize).collect();
             
             let list_paragraph_widget = Paragraph::new(lines)
                 .block(Block::default().padding(Padding::horizontal(2)))
-                .scroll((self.vertical_scroll as u16, 0));
+                .scroll((scroll as u16, 0));
             
             frame.render_widget(list_paragraph_widget, layout[0]);
         };
@@ -198,6 +423,30 @@ impl<'a> App<'a> {
         );
     }
     
+    /// Breaks the lines into rows of the given width, and gives back the row to scroll to for the
+    /// first line in view, and the line of each row. The line under the cursor is kept in view when
+    /// the rows above it push it down.
+    fn wrap_lines<'a>(&self, lines: Vec<Line<'a>>, width: usize, height: usize) -> (Vec<Line<'a>>, usize, Vec<usize>) {
+        let mut rows = vec![];
+        let mut first_rows = vec![];
+        let mut row_lines = vec![];
+        for (index, line) in lines.into_iter().enumerate() {
+            first_rows.push(rows.len());
+            rows.extend(wrap_line(line, width));
+            row_lines.resize(rows.len(), index);
+        }
+        first_rows.push(rows.len());
+
+        let last = first_rows.len() - 1;
+        let mut scroll = first_rows[self.vertical_scroll.min(last)];
+        let cursor_end = first_rows[(self.line_at_cursor + 1).min(last)];
+        if cursor_end > scroll + height {
+            scroll = cursor_end - height;
+        }
+
+        return (rows, scroll, row_lines);
+    }
+
     fn draw_footer_widget(&mut self, frame: &mut Frame, layout: &Rc<[Rect]>) {
         let footer_layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -210,7 +459,7 @@ impl<'a> App<'a> {
         match self.current_screen {
             CurrentScreen::ViewingFile => {
                 let span = Span::from(
-                    "(q) to quit, (i) to make new pair, (/) to search",
+                    tr!("(q) to quit, (i) to make new pair, (/) to search, (:) for commands, (?) for help"),
                 );
                 
                 let paragraph = Paragraph::new(
@@ -221,7 +470,13 @@ impl<'a> App<'a> {
             },
             CurrentScreen::Editing => {
                 let span = Span::from(
-                    "(ESC) to cancel/(Tab) to switch boxes/enter to complete",
+                    if self.is_choosing_value() {
+                        tr!("(ESC) to cancel/(Tab) to switch boxes/(j/k) to choose/enter to complete")
+                    } else if self.value_input.is_hidden() {
+                        tr!("(ESC) to cancel/(Tab) to switch boxes/(C-r) to show the value/enter to complete")
+                    } else {
+                        tr!("(ESC) to cancel/(Tab) to switch boxes/enter to complete")
+                    },
                 );
                 
                 let paragraph = Paragraph::new(
@@ -234,34 +489,84 @@ impl<'a> App<'a> {
                 self.search_widget.is_focused = true;
                 self.search_widget.render_to_frame(frame, footer_layout[0]);
             },
+            CurrentScreen::Command => {
+                self.command_input.is_focused = true;
+                self.command_input.render_to_frame(frame, footer_layout[0]);
+            },
+            CurrentScreen::Preview => {
+                let replacement = self.preview
+                    .as_ref()
+                    .and_then(|preview| preview.replacement.as_ref())
+                    .map(|(_, label)| tr!(", (r) to {}", label))
+                    .unwrap_or_default();
+                let span = Span::from(
+                    tr!("(j/k) to scroll{}, (ESC) to close", replacement),
+                );
+
+                let paragraph = Paragraph::new(
+                    Line::from(span)
+                ).block(Block::default().borders(Borders::ALL).padding(Padding::left(1)));
+
+                frame.render_widget(paragraph, footer_layout[0]);
+            },
+            CurrentScreen::Problems => {
+                let span = Span::from(
+                    tr!("(j/k) to select, (enter) to jump to it, (ESC) to close"),
+                );
+
+                let paragraph = Paragraph::new(
+                    Line::from(span)
+                ).block(Block::default().borders(Borders::ALL).padding(Padding::left(1)));
+
+                frame.render_widget(paragraph, footer_layout[0]);
+            },
+            CurrentScreen::Table if self.table.as_ref().is_some_and(|table| table.is_editing) => {
+                let area = footer_layout[0];
+                self.value_input.render_to_frame(frame, area);
+                // On the bottom border, like a title, for the footer has no room under it.
+                self.draw_value_feedback(frame, Rect::new(area.x + 1, area.bottom().saturating_sub(1), area.width.saturating_sub(2), 1));
+            },
+            CurrentScreen::Table => {
+                let span = Span::from(
+                    tr!("(h/j/k/l) to move, (enter) to edit, (s) for summaries, (ESC) to close"),
+                );
+
+                let paragraph = Paragraph::new(
+                    Line::from(span)
+                ).block(Block::default().borders(Borders::ALL).padding(Padding::left(1)));
+
+                frame.render_widget(paragraph, footer_layout[0]);
+            },
+            CurrentScreen::Confirm => {
+                let span = Span::from(
+                    tr!("(ESC) to cancel"),
+                );
+
+                let paragraph = Paragraph::new(
+                    Line::from(span)
+                ).block(Block::default().borders(Borders::ALL).padding(Padding::left(1)));
+
+                frame.render_widget(paragraph, footer_layout[0]);
+            },
+            CurrentScreen::Tutorial => {
+                let span = Span::from(
+                    tr!("(ESC) to close the tutorial"),
+                );
+
+                let paragraph = Paragraph::new(
+                    Line::from(span)
+                ).block(Block::default().borders(Borders::ALL).padding(Padding::left(1)));
+
+                frame.render_widget(paragraph, footer_layout[0]);
+            },
         };
         
         // Check if we have a fresh (unexpired) message to report to the user.
         // that message is displayed in the footer in place of the usual keymap footer.
         
-        let root_len = match &self.json {
-            serde_json::Value::Array(values) => values.len(),
-            serde_json::Value::Object(map) => map.len(),
-            _ => 0,
-        };
         let file_info_footer = if self.message_to_report.borrow().show_time.elapsed() >= self.message_to_report.borrow().show_duration { 
             Paragraph::new(
-                Line::from(vec![
-                    if let Some(metadata) = &self.file_metadata {
-                        let size = if metadata.len() > 1024 {
-                            format!("{} KB", metadata.len() / 1024)
-                        } else {
-                            format!("{} Bytes", metadata.len())
-                        };
-                        
-                        Span::from(format!("File size: {} Bytes", size))
-                    } else {
-                        Span::from("File size: N/A")
-                    },
-                    Span::from(format!(", Parent length: {}", root_len)),
-                    Span::from(format!(", Total lines: {}", self.lines_count)),
-                    Span::from(format!(", Current line: {}", self.line_at_cursor.saturating_add(1))),
-                ])
+                Line::from(self.status_line_spans())
             )
             .block(
                 Block::default()
@@ -276,11 +581,11 @@ impl<'a> App<'a> {
                 .style(
                     Style::default().fg({
                         match self.message_to_report.borrow().kind {
-                            ReportedMessageKinds::Error => Color::Red,
+                            ReportedMessageKinds::Error => self.config.theme.error,
                             ReportedMessageKinds::Info => Color::default(),
-                            ReportedMessageKinds::Debug => Color::Yellow,
-                            ReportedMessageKinds::Warning => Color::Yellow,
-                            ReportedMessageKinds::Success => Color::Green,
+                            ReportedMessageKinds::Debug => self.config.theme.warning,
+                            ReportedMessageKinds::Warning => self.config.theme.warning,
+                            ReportedMessageKinds::Success => self.config.theme.success,
                         }
                     })
                 )
@@ -297,31 +602,67 @@ impl<'a> App<'a> {
     
     fn draw_insert_popup_widget(&mut self, frame: &mut Frame) {
         let title_text = if !self.is_inside_array() {
-            "Enter a new key-value pair"
+            tr!("Enter a new key-value pair")
         } else {
-            "Add a new value"
+            tr!("Add a new value")
         };
         
+        // Half the terminal's width, widened for long content up to most of the terminal. The
+        // inputs grow a row per wrapped row of content, up to `MAX_POPUP_INPUT_ROWS` or what the
+        // terminal has room for (after which they scroll), and the popup has rows for the title,
+        // what the value is stored as and the target around them.
+        let area = frame.area();
+        let content_width = self.key_input.content().width().max(self.value_input.content().width()).min(u16::MAX as usize) as u16;
+        let width = content_width.saturating_add(5).saturating_mul(2).min(area.width * 9 / 10).max((area.width / 2).max(MIN_POPUP_WIDTH));
+        let input_width = match self.is_inside_array() {
+            true => width.saturating_sub(2),
+            false => width.saturating_sub(2) / 2,
+        };
+        let content_rows = self.key_input.content_rows(input_width).max(self.value_input.content_rows(input_width));
+        let input_height = content_rows.min(MAX_POPUP_INPUT_ROWS).min(area.height.saturating_sub(5)).max(1) + 2;
+        let centered_area = get_centered_rect_of_size(width, input_height + 3, area);
+        let inner_area = centered_area.inner(Margin::new(1, 1));
+        let inputs_area = Rect { height: input_height.min(inner_area.height), ..inner_area };
+
+        // Where the pair lands, under the inputs. A long path keeps its end, the closest to the pair.
+        let (parent, index) = match self.is_root_empty() {
+            true => (Some(vec![]), 0),
+            false => get_path_to_insert_into(self.line_at_cursor_without_empty_lines(), &self.json),
+        };
+        let (action, target) = match parent {
+            Some(mut path) if self.editing_mode == EditingMode::Editing => {
+                path.push(match self.json.pointer(&path_to_pointer(&path)) {
+                    Some(Value::Array(_)) => PathSegment::Index(index),
+                    _ => PathSegment::Key(self.key_input.content().to_string()),
+                });
+                (translate("Editing {}"), path_to_string(&path))
+            }
+            Some(path) if path.is_empty() => (translate("Inserting into {}"), tr!("the root")),
+            Some(path) => (translate("Inserting into {}"), path_to_string(&path)),
+            None => ("{}", String::new()),
+        };
+        let action_width = fill(action, &[String::new()]).chars().count();
+        let target = fill(action, &[keep_end(&target, (centered_area.width as usize).saturating_sub(action_width))]);
+
         let editing_popup = Block::default()
             .title(title_text)
             .title_alignment(Alignment::Center)
+            .title_bottom(Line::styled(target, Style::default().fg(self.config.theme.dimmed)))
             .borders(Borders::NONE)
             .style(Style::default().fg(Color::default()).bg(Color::default()));
-        
-        let centered_area = get_centered_rect(50, 9, frame.area());
 
         if !self.is_inside_array() {
             let layout = Layout::default()
                 .direction(Direction::Horizontal)
-                .margin(1)
                 .constraints([
                     Constraint::Percentage(50), 
                     Constraint::Percentage(50),
                 ])
-                .split(centered_area);
+                .split(inputs_area);
             
             // Update focus based on currently_editing
             if let Some(editing) = &self.currently_editing {
+                frame.render_widget(Clear, centered_area);
                 frame.render_widget(editing_popup, centered_area);
 
                 self.key_input.is_focused = *editing == CurrentlyEditing::Key;
@@ -329,22 +670,154 @@ impl<'a> App<'a> {
                 
                 self.key_input.render_to_frame(frame, layout[0]);
                 self.value_input.render_to_frame(frame, layout[1]);
+                self.draw_value_choices(frame, layout[1]);
+                self.draw_value_feedback(frame, Rect { y: layout[1].bottom(), height: 1, ..layout[1] });
+                self.draw_suggestions(frame, layout[0], layout[1]);
             }
         } else {
             let layout = Layout::default()
-                .margin(1)
                 .constraints([
                     Constraint::Percentage(100), 
                 ])
-                .split(centered_area);
+                .split(inputs_area);
             
             if let Some(editing) = &self.currently_editing {
+                frame.render_widget(Clear, centered_area);
                 frame.render_widget(editing_popup, centered_area);
                 
                 self.value_input.is_focused = *editing == CurrentlyEditing::Value;
                 
                 self.value_input.render_to_frame(frame, layout[0]);
+                self.draw_value_choices(frame, layout[0]);
+                self.draw_value_feedback(frame, Rect { y: layout[0].bottom(), height: 1, ..layout[0] });
             }
         }
     }
+
+    /// Tells on the row, under the value input or on its border, what the typed value will be
+    /// stored as, and what looks wrong about it.
+    fn draw_value_feedback(&self, frame: &mut Frame, row_area: Rect) {
+        let text = self.value_input.content();
+        if text.is_empty() || self.is_choosing_value() || row_area.bottom() > frame.area().bottom() {
+            return;
+        }
+
+        let feedback = input_feedback(text);
+        let stored_as = match feedback.stored_as {
+            "number" => tr!("Stored as a number"),
+            "boolean" => tr!("Stored as a boolean"),
+            "null" => tr!("Stored as null"),
+            _ => tr!("Stored as a string"),
+        };
+        let mut spans = vec![Span::styled(stored_as, Style::default().fg(self.config.theme.dimmed))];
+        if let Some(warning) = feedback.warning {
+            spans.push(Span::styled(format!(" · {}", warning), Style::default().fg(self.config.theme.warning)));
+        }
+
+        // Only over the text, to leave the rest of a border drawn.
+        let line = Line::from(spans);
+        let area = Rect { width: row_area.width.min(line.width() as u16), ..row_area };
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(line), area);
+    }
+
+    /// Lists the values the schema allows under the value input, when it restricts them to a list.
+    fn draw_value_choices(&self, frame: &mut Frame, value_input_area: Rect) {
+        if !self.is_choosing_value() {
+            return;
+        }
+
+        let choices: Vec<String> = self.value_choices.iter().map(value_choice_label).collect();
+        draw_dropdown(frame, value_input_area, translate("Allowed values"), &choices, self.selected_choice, self.config.theme.cursor());
+    }
+
+    /// Lists the suggestions for the focused input under it.
+    fn draw_suggestions(&self, frame: &mut Frame, key_input_area: Rect, value_input_area: Rect) {
+        let suggestions = self.suggestions();
+        if suggestions.is_empty() {
+            return;
+        }
+
+        let selected = self.selected_suggestion.min(suggestions.len() - 1);
+        match self.currently_editing {
+            Some(CurrentlyEditing::Key) => draw_dropdown(frame, key_input_area, translate("Keys in sibling objects (Tab)"), &suggestions, selected, self.config.theme.cursor()),
+            Some(CurrentlyEditing::Value) => draw_dropdown(frame, value_input_area, translate("Values used elsewhere (Tab)"), &suggestions, selected, self.config.theme.cursor()),
+            None => {}
+        }
+    }
+}
+
+/// Lists items under an input, with the selected one highlighted and kept in view.
+fn draw_dropdown(frame: &mut Frame, input_area: Rect, title: &str, items: &[String], selected: usize, selected_style: Style) {
+    let rows = items.len().min(MAX_DROPDOWN_ROWS);
+    let top = input_area.bottom().min(frame.area().bottom());
+    let area = Rect {
+        x: input_area.x,
+        y: top,
+        width: input_area.width,
+        height: (rows as u16 + 2).min(frame.area().bottom() - top),
+    };
+
+    let lines: Vec<Line> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let style = if index == selected { selected_style } else { Style::default() };
+            return Line::from(Span::styled(readable_string(item), style));
+        })
+        .collect();
+
+    let scroll = (selected + 1).saturating_sub(rows);
+
+    let list = Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .block(Block::default().title(title).borders(Borders::ALL));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(list, area);
+}
+
+/// The end of the text fitting in `width` columns, after a `…` when some of it is cut.
+fn keep_end(text: &str, width: usize) -> String {
+    if Span::raw(text).width() <= width {
+        return text.to_string();
+    }
+
+    let mut kept = String::new();
+    let mut kept_width = 1;
+    for char in text.chars().rev() {
+        let char_width = Span::raw(char.to_string()).width();
+        if kept_width + char_width > width {
+            break;
+        }
+        kept_width += char_width;
+        kept.insert(0, char);
+    }
+
+    return format!("…{}", kept);
+}
+
+/// Breaks a line into rows of at most `width` columns.
+fn wrap_line<'a>(line: Line<'a>, width: usize) -> Vec<Line<'a>> {
+    let line_style = line.style;
+    let mut rows = vec![Line::default().style(line_style)];
+    let mut row_width = 0;
+
+    for grapheme in line.styled_graphemes(Style::default()) {
+        let grapheme_width = Span::raw(grapheme.symbol).width();
+        if row_width + grapheme_width > width && row_width > 0 {
+            rows.push(Line::default().style(line_style));
+            row_width = 0;
+        }
+        row_width += grapheme_width;
+
+        let row = rows.last_mut().unwrap();
+        match row.spans.last_mut() {
+            Some(span) if span.style == grapheme.style => span.content.to_mut().push_str(grapheme.symbol),
+            _ => row.push_span(Span::styled(grapheme.symbol.to_string(), grapheme.style)),
+        }
+    }
+
+    return rows;
 }
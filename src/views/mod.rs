@@ -1 +1,4 @@
-pub mod main_view;
\ No newline at end of file
+pub mod confirm_view;
+pub mod main_view;
+pub mod table_view;
+pub mod tutorial_view;
\ No newline at end of file
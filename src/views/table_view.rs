@@ -0,0 +1,83 @@
+//
+// The table mode's view: the rows of an array of objects under a header of their keys.
+//
+
+use ratatui::{
+    layout::{Constraint, Rect}, style::{Modifier, Style}, text::{Span, Text}, widgets::{Block, Borders, Cell, Padding, Row, Table, TableState}, Frame
+};
+
+use crate::{app::App, i18n::tr, table::{cell_text, column_summary}, utils::{json::path_to_pointer, readable::readable_string}};
+
+/// The widest a column gets. Longer cells are cut.
+const MAX_COLUMN_WIDTH: usize = 30;
+/// The space between two columns.
+const COLUMN_SPACING: u16 = 2;
+
+impl App {
+    pub fn draw_table_widget(&self, frame: &mut Frame, area: Rect) {
+        let (Some(table), Some(rows)) = (&self.table, self.table_rows()) else {
+            return;
+        };
+
+        let widths: Vec<usize> = table.columns
+            .iter()
+            .map(|column| {
+                let widest_cell = rows.iter().map(|row| Span::from(readable_string(&cell_text(row, column))).width()).max().unwrap_or(0);
+                let widest_summary = if table.show_summaries {
+                    column_summary(rows, column).iter().map(|line| line.chars().count()).max().unwrap_or(0)
+                } else {
+                    0
+                };
+                return widest_cell.max(widest_summary).max(column.chars().count()).min(MAX_COLUMN_WIDTH);
+            })
+            .collect();
+
+        // Scroll the columns so the selected one is on screen.
+        let available_width = area.width.saturating_sub(4) as usize;
+        let mut first_column = 0;
+        while first_column < table.column
+            && widths[first_column..=table.column].iter().map(|width| width + COLUMN_SPACING as usize).sum::<usize>() > available_width
+        {
+            first_column += 1;
+        }
+        let shown_columns = first_column..table.columns.len();
+
+        let header = Row::new(table.columns[shown_columns.clone()].iter().map(|column| Cell::from(column.as_str())))
+            .style(Style::default().fg(self.config.theme.header).add_modifier(Modifier::BOLD));
+
+        let selected_cell_style = self.config.theme.cursor();
+        let table_rows = rows.iter().enumerate().map(|(row_index, row)| {
+            let cells = shown_columns.clone().map(|column_index| {
+                let text: String = readable_string(&cell_text(row, &table.columns[column_index])).chars().take(MAX_COLUMN_WIDTH).collect();
+                let cell = Cell::from(Text::from(text));
+
+                return if row_index == table.row && column_index == table.column {
+                    cell.style(selected_cell_style)
+                } else {
+                    cell
+                };
+            });
+
+            return Row::new(cells);
+        });
+
+        let title = tr!(" {} ({} rows) ", if table.path.is_empty() { tr!("(root)") } else { path_to_pointer(&table.path) }, rows.len());
+        let mut widget = Table::new(table_rows, widths[first_column..].iter().map(|width| Constraint::Length(*width as u16)))
+            .header(header)
+            .column_spacing(COLUMN_SPACING)
+            .block(Block::default().title(title).borders(Borders::ALL).padding(Padding::horizontal(1)));
+
+        if table.show_summaries {
+            let summaries: Vec<Vec<String>> = table.columns[first_column..].iter().map(|column| column_summary(rows, column)).collect();
+            let height = summaries.iter().map(Vec::len).max().unwrap_or(0);
+            let footer = Row::new(summaries.into_iter().map(|lines| Cell::from(Text::from(lines.join("\n")))))
+                .height(height as u16)
+                .top_margin(1)
+                .style(Style::default().fg(self.config.theme.dimmed));
+            widget = widget.footer(footer);
+        }
+
+        let mut state = TableState::default().with_selected(Some(table.row));
+        frame.render_stateful_widget(widget, area, &mut state);
+    }
+}
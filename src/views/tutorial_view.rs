@@ -0,0 +1,63 @@
+//
+// The tutorial popup: one step of the tour at a time, over the tree.
+//
+
+use ratatui::{
+    layout::Rect, style::{Modifier, Style}, text::{Line, Span}, widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap}, Frame
+};
+
+use crate::{app::App, i18n::{tr, translate}, tutorial::tutorial_steps};
+
+/// The widest the popup gets.
+const MAX_WIDTH: u16 = 72;
+
+impl App {
+    pub fn draw_tutorial(&self, frame: &mut Frame) {
+        let Some(tutorial) = &self.tutorial else {
+            return;
+        };
+
+        let steps = tutorial_steps(&self.config.keybindings);
+        let Some((title, text)) = steps.get(tutorial.step) else {
+            return;
+        };
+
+        let mut lines: Vec<Line> = text.iter().map(|line| Line::from(line.as_str())).collect();
+        lines.push(Line::default());
+        lines.push(Line::from(vec![
+            Span::styled("(enter)", Style::default().fg(self.config.theme.accent).add_modifier(Modifier::BOLD)),
+            Span::from(format!(" {}   ", if tutorial.step + 1 < steps.len() { translate("next") } else { translate("done") })),
+            Span::styled("(backspace)", Style::default().fg(self.config.theme.accent).add_modifier(Modifier::BOLD)),
+            Span::from(format!(" {}   ", translate("back"))),
+            Span::styled("(esc)", Style::default().fg(self.config.theme.accent).add_modifier(Modifier::BOLD)),
+            Span::from(format!(" {}", translate("close"))),
+        ]));
+
+        // Over the lower part of the tree, above the footer, so the tree stays in sight.
+        let area = frame.area();
+        let width = MAX_WIDTH.min(area.width);
+        // The rows of the wrapped lines, inside the borders and the padding.
+        let inner_width = (width as usize).saturating_sub(4).max(1);
+        let rows: usize = lines.iter().map(|line| line.width().div_ceil(inner_width).max(1)).sum();
+        let height = (rows as u16 + 2).min(area.height);
+        let popup_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.bottom().saturating_sub(height + 3).max(area.y),
+            width,
+            height,
+        );
+
+        let popup = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title(tr!(" Tutorial {}/{} · {} ", tutorial.step + 1, steps.len(), title))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.config.theme.accent))
+                    .padding(Padding::horizontal(1))
+            );
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+}
@@ -0,0 +1,179 @@
+//
+// Visual mode: a contiguous range of the elements of an array, from the one it started on to the
+// one under the cursor, yanked, deleted or moved to another array at once.
+//
+
+use std::{ops::RangeInclusive, time::Duration};
+
+use serde_json::Value;
+
+use crate::{actions::{Action, MainViewActions}, app::{App, ReportedMessageKinds}, clipboard::{copy_to_clipboard, yanked_text}, confirm::Confirm, i18n::{tr, translate}, registers::Yanked, utils::{json::{get_line_at_path, get_path_at_line, path_to_pointer, PathSegment}, stats::descendants_count}};
+
+impl App {
+    /// Starts selecting the elements of the array the cursor is in, or stops.
+    pub fn toggle_visual(&mut self) {
+        if self.visual_anchor.take().is_some() {
+            return;
+        }
+
+        match get_path_at_line(self.line_at_cursor, &self.json) {
+            Some(path) if matches!(path.last(), Some(PathSegment::Index(_))) => self.visual_anchor = Some(path),
+            _ => self.report(tr!("Visual mode selects the elements of an array, move onto one first"), ReportedMessageKinds::Error, Duration::from_secs(3)),
+        }
+    }
+
+    /// The array of the selection and the indexes of its first and last elements. The cursor picks
+    /// the element it's in, and stops at the first or last one once out of the array.
+    pub fn visual_range(&self) -> Option<(Vec<PathSegment>, RangeInclusive<usize>)> {
+        let anchor = self.visual_anchor.as_ref()?;
+        let (Some(PathSegment::Index(start)), parent) = (anchor.last(), &anchor[..anchor.len() - 1]) else {
+            return None;
+        };
+        let Some(Value::Array(values)) = self.json.pointer(&path_to_pointer(parent)) else {
+            return None;
+        };
+
+        let cursor_path = get_path_at_line(self.line_at_cursor, &self.json).unwrap_or_default();
+        let end = match cursor_path.get(parent.len()) {
+            Some(PathSegment::Index(index)) if cursor_path.starts_with(parent) => *index,
+            _ if get_line_at_path(anchor, &self.json).is_some_and(|line| self.line_at_cursor < line) => 0,
+            _ => values.len().saturating_sub(1),
+        };
+
+        return Some((parent.to_vec(), *start.min(&end)..=*start.max(&end)));
+    }
+
+    /// The lines of the selected elements, with the values nested in them.
+    pub fn visual_lines(&self) -> Option<RangeInclusive<usize>> {
+        let (parent, range) = self.visual_range()?;
+        let mut last = parent.clone();
+        last.push(PathSegment::Index(*range.end()));
+        let mut first = parent;
+        first.push(PathSegment::Index(*range.start()));
+
+        let last_value = self.json.pointer(&path_to_pointer(&last))?;
+        let end = get_line_at_path(&last, &self.json)? + descendants_count(last_value);
+
+        return Some(get_line_at_path(&first, &self.json)?..=end);
+    }
+
+    /// The selected elements, in order.
+    fn visual_elements(&self) -> Option<(Vec<PathSegment>, RangeInclusive<usize>, Vec<Value>)> {
+        let (parent, range) = self.visual_range()?;
+        let Some(Value::Array(values)) = self.json.pointer(&path_to_pointer(&parent)) else {
+            return None;
+        };
+
+        let elements = values[range.clone()].to_vec();
+
+        return Some((parent, range, elements));
+    }
+
+    /// Puts the selected elements in the registers, and in the clipboard when no register is named.
+    pub fn yank_visual_range(&mut self) {
+        let register = self.selected_register.take();
+        let Some((_, _, elements)) = self.visual_elements() else {
+            return;
+        };
+        self.visual_anchor = None;
+
+        let count = elements.len();
+        let value = Value::Array(elements);
+        if register.is_none() && let Err(err) = copy_to_clipboard(&yanked_text(&value)) {
+            self.report(tr!("Failed to copy to the clipboard: {}", err), ReportedMessageKinds::Error, Duration::from_secs(3));
+        } else {
+            self.report(tr!("Yanked {} elements", count), ReportedMessageKinds::Success, Duration::from_secs(2));
+        }
+        self.registers.store(register, Yanked { key: None, value, is_range: true });
+    }
+
+    /// Deletes the selected elements into the registers. Ones holding other values ask first,
+    /// unless `confirmed` or the config turns the confirmations off.
+    pub fn delete_visual_range(&mut self, confirmed: bool) {
+        let Some((parent, range, elements)) = self.visual_elements() else {
+            return;
+        };
+
+        let nested_count: usize = elements.iter().map(descendants_count).sum();
+        if nested_count > 0 && !confirmed && self.config.confirm_destructive {
+            self.open_confirm(Confirm::yes_no(
+                translate("Delete"),
+                tr!("Delete {} elements and the {} values inside them?", elements.len(), nested_count),
+                Action::MainView(MainViewActions::Delete { confirmed: true }),
+            ));
+            return;
+        }
+
+        if let Some(Value::Array(values)) = self.json.pointer_mut(&path_to_pointer(&parent)) {
+            values.drain(range);
+        }
+        self.visual_anchor = None;
+
+        let count = elements.len();
+        self.registers.store(self.selected_register.take(), Yanked { key: None, value: Value::Array(elements), is_range: true });
+        self.mark_dirty();
+        self.report(tr!("Deleted {} elements", count), ReportedMessageKinds::Success, Duration::from_secs(2));
+    }
+
+    /// Marks the selected elements to be moved into the array `m` is pressed on next.
+    pub fn mark_visual_range_to_move(&mut self) {
+        let Some((mut parent, range)) = self.visual_range() else {
+            return;
+        };
+        self.visual_anchor = None;
+
+        let count = range.end() - range.start() + 1;
+        parent.push(PathSegment::Index(*range.start()));
+        self.move_mark = Some((parent, count));
+        self.report(
+            tr!("Marked {} elements to move, do it again on the array to move them into", count),
+            ReportedMessageKinds::Info,
+            Duration::from_secs(3),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_visual_range() {
+        let mut app = App::default();
+        app.json = json!({ "a": [1, { "b": 2 }, 3, 4], "c": [] });
+
+        // From `a[2]` up to the nested `b`, which selects the element holding it.
+        app.line_at_cursor = 4;
+        app.update(Action::MainView(MainViewActions::ToggleVisual));
+        app.line_at_cursor = 3;
+        assert_eq!(app.visual_range(), Some((vec![PathSegment::Key("a".to_string())], 1..=2)));
+        assert_eq!(app.visual_lines(), Some(2..=4));
+
+        app.update(Action::MainView(MainViewActions::Yank));
+        assert_eq!(app.visual_anchor, None);
+        assert_eq!(app.registers.get(None).unwrap().value, json!([{ "b": 2 }, 3]));
+
+        // Out of the array, the selection stops at its last element.
+        app.line_at_cursor = 2;
+        app.update(Action::MainView(MainViewActions::ToggleVisual));
+        app.line_at_cursor = 6;
+        app.config.confirm_destructive = false;
+        app.update(Action::MainView(MainViewActions::Delete { confirmed: false }));
+        assert_eq!(app.json, json!({ "a": [1], "c": [] }));
+
+        // Pasted back one by one, after the cursor.
+        app.line_at_cursor = 1;
+        app.update(Action::MainView(MainViewActions::Paste));
+        assert_eq!(app.json, json!({ "a": [1, { "b": 2 }, 3, 4], "c": [] }));
+
+        // Moved together into another array.
+        app.line_at_cursor = 2;
+        app.update(Action::MainView(MainViewActions::ToggleVisual));
+        app.line_at_cursor = 4;
+        app.update(Action::MainView(MainViewActions::MoveInto));
+        app.line_at_cursor = 6;
+        app.update(Action::MainView(MainViewActions::MoveInto));
+        assert_eq!(app.json, json!({ "a": [1, 4], "c": [{ "b": 2 }, 3] }));
+    }
+}
@@ -2,11 +2,27 @@
 
 use ratatui::{
     buffer::Buffer,
-    layout::{Position, Rect},
-    style::{Color, Style},
-    text::Span,
+    layout::{Margin, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
+use regex::Regex;
+use unicode_width::UnicodeWidthChar;
+
+use crate::i18n::tr;
+
+/// How many edits of an input can be undone.
+const MAX_UNDO_STEPS: usize = 100;
+
+/// What can be typed into an input.
+#[derive(Debug, Clone)]
+pub enum InputMask {
+    /// Only the characters the function accepts can be typed.
+    Chars(fn(char) -> bool),
+    /// Content the pattern isn't found in is flagged with the invalid style.
+    Pattern(Regex),
+}
 
 /// A reusable text input widget that handles cursor movement and text editing
 #[derive(Debug, Clone)]
@@ -15,18 +31,40 @@ pub struct TextInput {
     pub is_focused: bool,
     /// The title of the input block
     title: Option<String>,
+    /// Shown while the input is empty, to tell what goes in it
+    placeholder: Option<String>,
+    /// What can be typed into the input, or is flagged when typed
+    mask: Option<InputMask>,
+    /// Whether the characters are drawn as `•`, for secrets
+    is_hidden: bool,
     /// Current value of the input box
     content: String,
     /// Position of cursor in the editor area (character index, not byte index)
     character_index: usize,
+    /// Where the selection started. What's between it and the cursor is selected.
+    selection_anchor: Option<usize>,
+    /// The width the text was last drawn at, to move the cursor between its wrapped rows. 0 when
+    /// it wasn't wrapped.
+    drawn_width: usize,
+    /// The first character shown when the input is one row high, and scrolls sideways.
+    horizontal_offset: usize,
+    /// The content and the cursor before each edit, the most recent last.
+    undo_stack: Vec<(String, usize)>,
+    /// Where the cursor was left by the last typed character, for the characters typed after it
+    /// to be undone with it.
+    typed_at: Option<usize>,
     /// The style to apply to the text input when not focused
     text_style: Style,
     /// The style to apply to the text input when focused
     focused_text_style: Style,
+    /// The style of the placeholder
+    placeholder_style: Style,
     /// The style to apply to the block input when not focused
     block_style: Style,
     /// The style to apply to the block input when focused
     focused_block_style: Style,
+    /// The style to apply to the block input when the content doesn't match the mask
+    invalid_block_style: Style,
     /// Show the block around the text input field.
     show_block: bool,
     /// Whether to show the cursor
@@ -37,13 +75,23 @@ impl TextInput {
     pub fn new(title: Option<&str>) -> Self {
         Self {
             title: title.map(|s| s.to_string()),
+            placeholder: None,
+            mask: None,
+            is_hidden: false,
             content: String::new(),
             character_index: 0,
+            selection_anchor: None,
+            drawn_width: 0,
+            horizontal_offset: 0,
+            undo_stack: vec![],
+            typed_at: None,
             is_focused: false,
             text_style: Style::default().fg(Color::default()),
             focused_text_style: Style::default().fg(Color::default()),
+            placeholder_style: Style::default().add_modifier(Modifier::DIM),
             block_style: Style::default(),
             focused_block_style: Style::default().fg(Color::Yellow),
+            invalid_block_style: Style::default().fg(Color::Red),
             show_block: true,
             show_cursor: true,
         }
@@ -53,9 +101,23 @@ impl TextInput {
         return &self.content;
     }
 
+    /// Fills the input, with nothing to undo.
     pub fn set_content(&mut self, content: impl Into<String>) {
         self.content = content.into();
         self.character_index = self.content.chars().count();
+        self.selection_anchor = None;
+        self.undo_stack.clear();
+        self.typed_at = None;
+    }
+
+    /// Replaces the content, like typing it over, so it can be undone.
+    pub fn replace_content(&mut self, content: impl Into<String>) {
+        let content = content.into();
+        self.edit(false, |input| {
+            input.character_index = content.chars().count();
+            input.content = content;
+            input.selection_anchor = None;
+        });
     }
 
     pub fn with_content(mut self, content: impl Into<String>) -> Self {
@@ -64,9 +126,48 @@ impl TextInput {
         return self;
     }
 
+    /// Empties the input, with nothing to undo.
     pub fn clear(&mut self) {
-        self.content.clear();
-        self.character_index = 0;
+        self.set_content("");
+    }
+
+    /// Deletes all of the content, which can be undone.
+    pub fn delete_all(&mut self) {
+        self.replace_content("");
+    }
+
+    /// Makes an edit that can be undone. The characters typed one after the other are undone
+    /// together.
+    fn edit(&mut self, typing: bool, edit: impl FnOnce(&mut Self)) {
+        let before = (self.content.clone(), self.character_index);
+        edit(self);
+        if self.content == before.0 {
+            return;
+        }
+
+        // An edit made of others, like typing over the selection, is undone at once.
+        let is_typing_on = typing && self.typed_at == Some(before.1);
+        if !is_typing_on && self.undo_stack.last() != Some(&before) {
+            self.undo_stack.push(before);
+            if self.undo_stack.len() > MAX_UNDO_STEPS {
+                self.undo_stack.remove(0);
+            }
+        }
+        self.typed_at = typing.then_some(self.character_index);
+    }
+
+    /// Puts back the content as it was before the last edit. Whether there was one.
+    pub fn undo(&mut self) -> bool {
+        let Some((content, character_index)) = self.undo_stack.pop() else {
+            return false;
+        };
+
+        self.content = content;
+        self.character_index = character_index;
+        self.selection_anchor = None;
+        self.typed_at = None;
+
+        return true;
     }
 
     pub fn with_focus(mut self, is_focused: bool) -> Self {
@@ -75,6 +176,16 @@ impl TextInput {
         return self;
     }
 
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+
+        return self;
+    }
+
+    pub fn set_placeholder_style(&mut self, style: Style) {
+        self.placeholder_style = style;
+    }
+
     pub fn with_text_style(mut self, style: Style) -> Self {
         self.text_style = style;
         
@@ -87,6 +198,34 @@ impl TextInput {
         return self;
     }
 
+    pub fn set_focused_block_style(&mut self, style: Style) {
+        self.focused_block_style = style;
+    }
+
+    pub fn set_invalid_block_style(&mut self, style: Style) {
+        self.invalid_block_style = style;
+    }
+
+    pub fn set_mask(&mut self, mask: Option<InputMask>) {
+        self.mask = mask;
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        return self.is_hidden;
+    }
+
+    pub fn set_hidden(&mut self, is_hidden: bool) {
+        self.is_hidden = is_hidden;
+    }
+
+    /// Whether the content matches the pattern of the mask. Empty content always does.
+    pub fn is_valid(&self) -> bool {
+        return match &self.mask {
+            Some(InputMask::Pattern(pattern)) => self.content.is_empty() || pattern.is_match(&self.content),
+            _ => true,
+        };
+    }
+
     pub fn move_cursor_left(&mut self) {
         let cursor_moved_left = self.character_index.saturating_sub(1);
         self.character_index = self.clamp_cursor(cursor_moved_left);
@@ -97,27 +236,205 @@ impl TextInput {
         self.character_index = self.clamp_cursor(cursor_moved_right);
     }
 
+    /// Starts a selection at the cursor when `selecting`, or drops the selection, before moving
+    /// the cursor.
+    pub fn set_selecting(&mut self, selecting: bool) {
+        if !selecting {
+            self.selection_anchor = None;
+        } else if self.selection_anchor.is_none() {
+            self.selection_anchor = Some(self.character_index);
+        }
+    }
+
+    /// The start and the end of the selected characters, when any are.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.selection_anchor?;
+        let range = (anchor.min(self.character_index), anchor.max(self.character_index));
+
+        return if range.0 == range.1 { None } else { Some(range) };
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+
+        return Some(self.content.chars().skip(start).take(end - start).collect());
+    }
+
+    /// Deletes the selected characters. Whether there were any.
+    pub fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.selection_anchor = None;
+            return false;
+        };
+
+        self.edit(false, |input| {
+            input.content = input.content.chars().take(start).chain(input.content.chars().skip(end)).collect();
+            input.character_index = start;
+            input.selection_anchor = None;
+        });
+
+        return true;
+    }
+
+    /// Types the character at the cursor, in place of the selection if there's one. Characters
+    /// the mask doesn't accept are left out.
     pub fn append_char(&mut self, new_char: char) {
-        let index = self.byte_index();
-        self.content.insert(index, new_char);
-        self.move_cursor_right();
+        if let Some(InputMask::Chars(accepts)) = self.mask && !accepts(new_char) {
+            return;
+        }
+
+        self.edit(true, |input| {
+            input.delete_selection();
+            let index = input.byte_index();
+            input.content.insert(index, new_char);
+            input.move_cursor_right();
+        });
     }
 
     pub fn delete_char(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+
         let is_not_cursor_leftmost = self.character_index != 0;
         if is_not_cursor_leftmost {
-            let current_index = self.character_index;
-            let from_left_to_current_index = current_index - 1;
+            self.edit(false, |input| {
+                let current_index = input.character_index;
+                let from_left_to_current_index = current_index - 1;
+
+                // Getting all characters before the selected character.
+                let before_char_to_delete = input.content.chars().take(from_left_to_current_index);
+                // Getting all characters after selected character.
+                let after_char_to_delete = input.content.chars().skip(current_index);
+
+                // Put all characters together except the selected one.
+                input.content = before_char_to_delete.chain(after_char_to_delete).collect();
+                input.move_cursor_left();
+            });
+        }
+    }
+
+    /// Deletes the character under the cursor, the one `delete_char` would delete next.
+    pub fn delete_next_char(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+
+        let index = self.byte_index();
+        if index < self.content.len() {
+            self.edit(false, |input| {
+                input.content.remove(index);
+            });
+        }
+    }
+
+    /// Swaps the character before the cursor with the one under it and moves past them, like in
+    /// readline. At the end, the last two characters are swapped.
+    pub fn transpose_chars(&mut self) {
+        let mut chars: Vec<char> = self.content.chars().collect();
+        let index = self.character_index.min(chars.len().saturating_sub(1));
+        if index == 0 {
+            return;
+        }
 
-            // Getting all characters before the selected character.
-            let before_char_to_delete = self.content.chars().take(from_left_to_current_index);
-            // Getting all characters after selected character.
-            let after_char_to_delete = self.content.chars().skip(current_index);
+        chars.swap(index - 1, index);
+        self.edit(false, |input| {
+            input.content = chars.into_iter().collect();
+            input.character_index = index + 1;
+            input.selection_anchor = None;
+        });
+    }
+
+    /// Moves the cursor to the start of the word before it.
+    pub fn move_cursor_word_left(&mut self) {
+        self.character_index = self.previous_word_start();
+    }
+
+    /// Moves the cursor to the end of the word after it.
+    pub fn move_cursor_word_right(&mut self) {
+        let chars: Vec<char> = self.content.chars().collect();
+        let mut index = self.character_index;
+        while index < chars.len() && !is_word_char(chars[index]) {
+            index += 1;
+        }
+        while index < chars.len() && is_word_char(chars[index]) {
+            index += 1;
+        }
 
-            // Put all characters together except the selected one.
-            self.content = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
+        self.character_index = index;
+    }
+
+    /// Deletes from the start of the word before the cursor up to the cursor.
+    pub fn delete_word(&mut self) {
+        if self.delete_selection() {
+            return;
         }
+
+        let word_start = self.previous_word_start();
+        self.edit(false, |input| {
+            input.content = input
+                .content
+                .chars()
+                .take(word_start)
+                .chain(input.content.chars().skip(input.character_index))
+                .collect();
+            input.character_index = word_start;
+        });
+    }
+
+    /// Where the word before the cursor starts, skipping what separates it from the cursor.
+    fn previous_word_start(&self) -> usize {
+        let chars: Vec<char> = self.content.chars().collect();
+        let mut index = self.character_index;
+        while index > 0 && !is_word_char(chars[index - 1]) {
+            index -= 1;
+        }
+        while index > 0 && is_word_char(chars[index - 1]) {
+            index -= 1;
+        }
+
+        return index;
+    }
+
+    /// Moves the cursor to the wrapped row below, or above, as close to its column as the row
+    /// allows. Whether there was a row to move to.
+    pub fn move_cursor_vertically(&mut self, down: bool) -> bool {
+        if self.drawn_width == 0 {
+            return false;
+        }
+
+        let rows = self.wrapped_rows(self.drawn_width);
+        let (row, column) = self.cell_of(self.character_index, self.drawn_width);
+        let target_row = match down {
+            true => row + 1,
+            false => match row.checked_sub(1) {
+                Some(target_row) => target_row,
+                None => return false,
+            },
+        };
+        let Some(target_chars) = rows.get(target_row) else {
+            return false;
+        };
+
+        let row_start: usize = rows[..target_row].iter().map(Vec::len).sum();
+        let mut index = row_start;
+        let mut row_width = 0;
+        for char in target_chars {
+            row_width += char.width().unwrap_or(0);
+            if row_width > column {
+                break;
+            }
+            index += 1;
+        }
+        // Past the last character of a row but the last is the start of the next one.
+        if target_row + 1 < rows.len() {
+            index = index.min(row_start + target_chars.len().saturating_sub(1));
+        }
+
+        self.set_selecting(false);
+        self.character_index = index;
+
+        return true;
     }
 
     pub fn move_cursor_to_start(&mut self) {
@@ -144,17 +461,108 @@ impl TextInput {
         return new_cursor_pos.clamp(0, self.content.chars().count());
     }
 
+    /// The space the text takes inside the block.
+    fn text_area(&self, area: Rect) -> Rect {
+        return if self.show_block { area.inner(Margin::new(1, 1)) } else { area };
+    }
+
+    /// The characters as they're drawn: all `•` while the content is hidden.
+    fn shown_chars(&self) -> impl Iterator<Item = char> + '_ {
+        return self.content.chars().map(|char| if self.is_hidden { '•' } else { char });
+    }
+
+    /// The content wrapped at a width in columns, as the characters of each row. Wide characters,
+    /// like emoji or CJK ones, take two columns and go to the next row when only one is left.
+    fn wrapped_rows(&self, width: usize) -> Vec<Vec<char>> {
+        let mut rows = vec![vec![]];
+        let mut row_width = 0;
+
+        for char in self.shown_chars() {
+            let char_width = char.width().unwrap_or(0);
+            if row_width + char_width > width && row_width > 0 {
+                rows.push(vec![]);
+                row_width = 0;
+            }
+            rows.last_mut().unwrap().push(char);
+            row_width += char_width;
+        }
+
+        return rows;
+    }
+
+    /// The row and the column of the wrapped content a cursor before the character at the index
+    /// is on. Past the end of a full row, it's at the start of the next one.
+    fn cell_of(&self, character_index: usize, width: usize) -> (usize, usize) {
+        let mut characters_before = 0;
+        let mut cell = (0, 0);
+
+        for (row, chars) in self.wrapped_rows(width).iter().enumerate() {
+            let chars_on_row = (character_index - characters_before).min(chars.len());
+            let column: usize = chars[..chars_on_row].iter().map(|char| char.width().unwrap_or(0)).sum();
+            cell = (row, column);
+
+            if characters_before + chars.len() > character_index {
+                return cell;
+            }
+            characters_before += chars.len();
+        }
+
+        return if cell.1 >= width { (cell.0 + 1, 0) } else { cell };
+    }
+
+    /// How many rows the content takes when wrapped at the width of the area, the cursor past its
+    /// end included.
+    pub fn content_rows(&self, area_width: u16) -> u16 {
+        let width = if self.show_block { area_width.saturating_sub(2) } else { area_width }.max(1) as usize;
+        let (last_row, _) = self.cell_of(self.content.chars().count(), width);
+
+        return (last_row + 1) as u16;
+    }
+
+    /// The row and the column of the wrapped content the cursor is on, and the first row shown so
+    /// the cursor's row stays in the area.
+    fn rows_scroll(&self, area: Rect) -> (usize, usize, usize) {
+        let text_area = self.text_area(area);
+        if text_area.height <= 1 {
+            let column = self.shown_chars().take(self.character_index).skip(self.horizontal_offset).map(|char| char.width().unwrap_or(0)).sum();
+            return (0, column, 0);
+        }
+
+        let (cursor_row, column) = self.cell_of(self.character_index, text_area.width.max(1) as usize);
+        let first_row = cursor_row.saturating_sub(text_area.height.max(1) as usize - 1);
+
+        return (cursor_row, column, first_row);
+    }
+
+    /// The first character to show on a single row of the width for the cursor to be in sight.
+    /// The text only moves when the cursor goes past an edge, or to show more of it once it
+    /// doesn't fill the row anymore.
+    fn scrolled_offset(&self, width: usize) -> usize {
+        let widths: Vec<usize> = self.shown_chars().map(|char| char.width().unwrap_or(0)).collect();
+        let width_between = |start: usize, end: usize| widths[start..end].iter().sum::<usize>();
+        let mut offset = self.horizontal_offset.min(self.character_index);
+
+        // The cursor takes a column of its own past the last character.
+        while offset < self.character_index && width_between(offset, self.character_index) + 1 > width {
+            offset += 1;
+        }
+        while offset > 0 && width_between(offset - 1, widths.len()) < width {
+            offset -= 1;
+        }
+
+        return offset;
+    }
+
     /// Get the cursor position for the frame renderer
     pub fn cursor_position(&self, area: Rect) -> Position {
-        // Calculate the block border offset if we have a block
-        let x_offset = if self.show_block { 1 } else { 0 };
-        let y_offset = if self.show_block { 1 } else { 0 };
+        let text_area = self.text_area(area);
+        let (cursor_row, column, first_row) = self.rows_scroll(area);
 
         return Position::new(
             // Draw the cursor at the current position in the input field
-            area.x + self.character_index as u16 + x_offset,
-            // Position vertically
-            area.y + y_offset,
+            text_area.x + column as u16,
+            // On the row of the wrapped content it's on, counted from the first one shown
+            text_area.y + (cursor_row - first_row) as u16,
         );
     }
 
@@ -164,7 +572,14 @@ impl TextInput {
     /// the fact by the frame/ratatui, so it's not really part of this TextInput struct.
     ///
     /// This function handles that.
-    pub fn render_to_frame(&self, frame: &mut ratatui::Frame, area: Rect) {
+    pub fn render_to_frame(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        let text_area = self.text_area(area);
+        if text_area.height <= 1 {
+            self.drawn_width = 0;
+            self.horizontal_offset = self.scrolled_offset(text_area.width as usize);
+        } else {
+            self.drawn_width = text_area.width as usize;
+        }
         frame.render_widget(self.clone(), area);
 
         // Set cursor position if focused and showing cursor
@@ -174,6 +589,11 @@ impl TextInput {
     }
 }
 
+/// Whether the character is part of a word, for moving and deleting by words.
+fn is_word_char(char: char) -> bool {
+    return char.is_alphanumeric() || char == '_';
+}
+
 impl Default for TextInput {
     fn default() -> Self {
         Self::new(Some("Text Input"))
@@ -188,25 +608,334 @@ impl Widget for TextInput {
             self.text_style
         };
         
-        let block_style = if self.is_focused {
+        let block_style = if !self.is_valid() {
+            self.invalid_block_style
+        } else if self.is_focused {
             self.focused_block_style
         } else {
             self.block_style
         };
 
+        // The content is wrapped at the width of the input, and scrolled to the cursor's row. On a
+        // single row, it scrolls sideways instead.
+        let text_area = self.text_area(area);
+        let (rows, mut characters_before) = match text_area.height <= 1 {
+            true => (vec![self.shown_chars().skip(self.horizontal_offset).collect()], self.horizontal_offset),
+            false => (self.wrapped_rows(text_area.width.max(1) as usize), 0),
+        };
+        let selection = self.selection().filter(|_| self.is_focused);
+        // The placeholder takes the place of the empty row of an empty input.
+        let placeholder = self.placeholder.clone().filter(|_| self.content.is_empty());
+        let mut lines: Vec<Line> = placeholder.map(|placeholder| Line::styled(placeholder, self.placeholder_style)).into_iter().collect();
+        for row in rows.into_iter().skip(lines.len()) {
+            let row_start = characters_before;
+            characters_before += row.len();
+
+            // The selected part of the row is shown reversed.
+            let Some((start, end)) = selection.filter(|(start, end)| *start < characters_before && *end > row_start) else {
+                lines.push(Line::from(row.into_iter().collect::<String>()));
+                continue;
+            };
+            let (start, end) = (start.saturating_sub(row_start), (end - row_start).min(row.len()));
+            lines.push(Line::from(vec![
+                Span::from(row[..start].iter().collect::<String>()),
+                Span::styled(row[start..end].iter().collect::<String>(), Style::default().add_modifier(Modifier::REVERSED)),
+                Span::from(row[end..].iter().collect::<String>()),
+            ]));
+        }
+        let (_, _, first_row) = self.rows_scroll(area);
+
         // Create a paragraph widget for the text content
-        let paragraph = Paragraph::new(
-                Span::raw(&self.content)
-            )
+        let paragraph = Paragraph::new(lines)
+            .scroll((first_row as u16, 0))
             .style(text_style)
             .block(
                 Block::default()
                     .style(block_style)
                     .borders(Borders::ALL)
-                    .title(self.title.unwrap_or(String::from("Text Input")))
+                    .title(self.title.unwrap_or_else(|| tr!("Text Input")))
             );
 
         // Render the paragraph
         paragraph.render(area, buf);
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masks() {
+        let mut input = TextInput::new(None);
+        input.set_mask(Some(InputMask::Chars(|char| char.is_ascii_digit())));
+        for char in "4a2".chars() {
+            input.append_char(char);
+        }
+        assert_eq!(input.content(), "42");
+        assert!(input.is_valid());
+
+        input.set_mask(Some(InputMask::Pattern(Regex::new("^[a-z_]+$").unwrap())));
+        assert!(!input.is_valid());
+        input.set_content("snake_case");
+        assert!(input.is_valid());
+        input.clear();
+        assert!(input.is_valid());
+    }
+
+    #[test]
+    fn test_hidden_content() {
+        let area = Rect::new(0, 0, 7, 4);
+        let mut input = TextInput::new(None).with_content("日本語");
+        input.set_hidden(true);
+        assert_eq!(input.content_rows(area.width), 1);
+        assert_eq!(input.cursor_position(area), Position::new(4, 1));
+
+        let mut buffer = Buffer::empty(area);
+        input.render(area, &mut buffer);
+        assert_eq!(buffer.cell((1, 1)).unwrap().symbol(), "•");
+        assert_eq!(buffer.cell((3, 1)).unwrap().symbol(), "•");
+    }
+
+    #[test]
+    fn test_placeholder() {
+        let area = Rect::new(0, 0, 12, 3);
+        let mut input = TextInput::new(None).with_placeholder("new key…");
+        let mut buffer = Buffer::empty(area);
+        input.clone().render(area, &mut buffer);
+        assert_eq!(buffer.cell((1, 1)).unwrap().symbol(), "n");
+        assert!(buffer.cell((1, 1)).unwrap().modifier.contains(Modifier::DIM));
+        assert_eq!(input.cursor_position(area), Position::new(1, 1));
+
+        input.append_char('a');
+        let mut buffer = Buffer::empty(area);
+        input.render(area, &mut buffer);
+        assert_eq!(buffer.cell((1, 1)).unwrap().symbol(), "a");
+        assert_eq!(buffer.cell((2, 1)).unwrap().symbol(), " ");
+    }
+
+    #[test]
+    fn test_wrapped_content() {
+        // 5 columns inside the borders.
+        let area = Rect::new(0, 0, 7, 4);
+        let mut input = TextInput::new(None).with_content("abcdefghijkl");
+        assert_eq!(input.content_rows(area.width), 3);
+
+        // Two rows are shown, the last two, with the cursor at the end.
+        assert_eq!(input.cursor_position(area), Position::new(3, 2));
+
+        input.move_cursor_to_start();
+        assert_eq!(input.cursor_position(area), Position::new(1, 1));
+    }
+
+    #[test]
+    fn test_horizontal_scrolling() {
+        // 5 columns and a single row inside the borders.
+        let area = Rect::new(0, 0, 7, 3);
+        let mut input = TextInput::new(None).with_content("abcdefghij");
+        input.horizontal_offset = input.scrolled_offset(5);
+        assert_eq!(input.horizontal_offset, 6);
+        assert_eq!(input.cursor_position(area), Position::new(5, 1));
+
+        // The text stays in place until the cursor goes past the left edge.
+        input.move_cursor_word_left();
+        input.move_cursor_right();
+        input.move_cursor_right();
+        input.move_cursor_right();
+        input.move_cursor_right();
+        input.move_cursor_right();
+        input.move_cursor_right();
+        input.move_cursor_right();
+        assert_eq!(input.cursor_position(area), Position::new(2, 1));
+        input.horizontal_offset = input.scrolled_offset(5);
+        assert_eq!(input.horizontal_offset, 6);
+        input.move_cursor_to_start();
+        input.horizontal_offset = input.scrolled_offset(5);
+        assert_eq!(input.horizontal_offset, 0);
+
+        // Shortened, it shows as much as fits.
+        input.move_cursor_to_end();
+        input.horizontal_offset = input.scrolled_offset(5);
+        input.delete_word();
+        input.append_char('x');
+        input.horizontal_offset = input.scrolled_offset(5);
+        assert_eq!(input.horizontal_offset, 0);
+
+        // Wide characters take two columns.
+        let mut input = TextInput::new(None).with_content("日本語");
+        input.horizontal_offset = input.scrolled_offset(5);
+        assert_eq!(input.horizontal_offset, 1);
+        assert_eq!(input.cursor_position(area), Position::new(5, 1));
+    }
+
+    #[test]
+    fn test_vertical_movement() {
+        let mut input = TextInput::new(None).with_content("abcdefghijkl");
+        assert!(!input.move_cursor_vertically(false));
+
+        // Drawn 5 columns wide: "abcde", "fghij", "kl".
+        input.drawn_width = 5;
+        assert!(input.move_cursor_vertically(false));
+        assert_eq!(input.character_index, 7);
+        assert!(input.move_cursor_vertically(false));
+        assert_eq!(input.character_index, 2);
+        assert!(!input.move_cursor_vertically(false));
+
+        input.character_index = 4;
+        assert!(input.move_cursor_vertically(true));
+        assert_eq!(input.character_index, 9);
+        // The last row is shorter: the cursor goes to its end.
+        assert!(input.move_cursor_vertically(true));
+        assert_eq!(input.character_index, 12);
+        assert!(!input.move_cursor_vertically(true));
+    }
+
+    #[test]
+    fn test_words() {
+        let mut input = TextInput::new(None).with_content("user.first_name = \"Omar\"");
+        input.move_cursor_word_left();
+        assert_eq!(input.character_index, 19);
+        input.move_cursor_word_left();
+        assert_eq!(input.character_index, 5);
+
+        input.move_cursor_word_right();
+        assert_eq!(input.character_index, 15);
+
+        input.delete_word();
+        assert_eq!(input.content(), "user. = \"Omar\"");
+        input.delete_word();
+        assert_eq!(input.content(), " = \"Omar\"");
+        input.delete_word();
+        assert_eq!(input.content(), " = \"Omar\"");
+    }
+
+    #[test]
+    fn test_selection() {
+        let mut input = TextInput::new(None).with_content("hello world");
+        input.set_selecting(true);
+        input.move_cursor_word_left();
+        assert_eq!(input.selected_text().as_deref(), Some("world"));
+
+        // Typing replaces the selection.
+        input.append_char('t');
+        assert_eq!(input.content(), "hello t");
+        assert_eq!(input.selection(), None);
+
+        input.move_cursor_to_start();
+        input.set_selecting(true);
+        input.move_cursor_right();
+        input.move_cursor_right();
+        assert_eq!(input.selection(), Some((0, 2)));
+        input.delete_char();
+        assert_eq!(input.content(), "llo t");
+        assert_eq!(input.character_index, 0);
+
+        // Selecting to where the cursor moves and deleting it, like <C-u> and <C-k>.
+        input.move_cursor_right();
+        input.move_cursor_right();
+        input.set_selecting(true);
+        input.move_cursor_to_end();
+        input.delete_selection();
+        assert_eq!(input.content(), "ll");
+        assert_eq!(input.character_index, 2);
+
+        // Moving without selecting drops it.
+        input.set_selecting(true);
+        input.move_cursor_right();
+        input.set_selecting(false);
+        input.move_cursor_right();
+        assert_eq!(input.selected_text(), None);
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut input = TextInput::new(None).with_content("name");
+        assert!(!input.undo());
+
+        // What's typed in a row is undone at once.
+        input.append_char(' ');
+        input.append_char('i');
+        input.append_char('s');
+        input.delete_char();
+        input.move_cursor_to_start();
+        input.append_char('a');
+        assert_eq!(input.content(), "aname i");
+
+        assert!(input.undo());
+        assert_eq!(input.content(), "name i");
+        assert!(input.undo());
+        assert_eq!(input.content(), "name is");
+        assert_eq!(input.character_index, 7);
+        assert!(input.undo());
+        assert_eq!(input.content(), "name");
+
+        // A clear, and typing over a selection.
+        input.delete_all();
+        input.append_char('x');
+        input.undo();
+        input.undo();
+        assert_eq!(input.content(), "name");
+
+        input.set_selecting(true);
+        input.move_cursor_word_left();
+        input.append_char('N');
+        input.append_char('o');
+        assert_eq!(input.content(), "No");
+        input.undo();
+        assert_eq!(input.content(), "name");
+        assert!(!input.undo());
+    }
+
+    #[test]
+    fn test_transpose_chars() {
+        let mut input = TextInput::new(None).with_content("tset");
+        input.move_cursor_to_start();
+        input.transpose_chars();
+        assert_eq!(input.content(), "tset");
+
+        input.move_cursor_right();
+        input.transpose_chars();
+        assert_eq!(input.content(), "stet");
+        assert_eq!(input.character_index, 2);
+
+        input.move_cursor_to_end();
+        input.transpose_chars();
+        assert_eq!(input.content(), "stte");
+        assert_eq!(input.character_index, 4);
+    }
+
+    #[test]
+    fn test_delete_next_char() {
+        let mut input = TextInput::new(None).with_content("café");
+        input.delete_next_char();
+        assert_eq!(input.content(), "café");
+
+        input.move_cursor_left();
+        input.delete_next_char();
+        assert_eq!(input.content(), "caf");
+        assert_eq!(input.character_index, 3);
+
+        input.move_cursor_to_start();
+        input.delete_next_char();
+        assert_eq!(input.content(), "af");
+        assert_eq!(input.character_index, 0);
+    }
+
+    #[test]
+    fn test_wide_characters() {
+        // 5 columns inside the borders: two wide characters take four, the third goes to the next row.
+        let area = Rect::new(0, 0, 7, 4);
+        let mut input = TextInput::new(None).with_content("日本語");
+        assert_eq!(input.content_rows(area.width), 2);
+        assert_eq!(input.cursor_position(area), Position::new(3, 2));
+
+        input.move_cursor_left();
+        assert_eq!(input.cursor_position(area), Position::new(1, 2));
+        input.move_cursor_left();
+        assert_eq!(input.cursor_position(area), Position::new(3, 1));
+
+        // A full row puts the cursor at the start of the next one.
+        let input = TextInput::new(None).with_content("ab😀c");
+        assert_eq!(input.content_rows(area.width), 2);
+        assert_eq!(input.cursor_position(area), Position::new(1, 2));
+    }
+}
@@ -0,0 +1,200 @@
+//
+// Structural edits of a document: inserting after a child, swapping two children, moving a node
+// into another container and removing a slice of an array. They only change the JSON data, and
+// leave the messages about what went wrong to the interface.
+//
+
+use serde_json::{Map, Value};
+
+use crate::{json::{get_value_at_path_mut, path_to_pointer, PathSegment}, patch::remove_by_pointer};
+
+/// The key, or the first of `key_copy`, `key_copy2`... that the object doesn't have yet.
+pub fn free_key(map: &Map<String, Value>, base_key: &str) -> String {
+    let mut key = base_key.to_string();
+    let mut copy = 1;
+    while map.contains_key(&key) {
+        key = if copy == 1 { format!("{}_copy", base_key) } else { format!("{}_copy{}", base_key, copy) };
+        copy += 1;
+    }
+
+    return key;
+}
+
+/// Inserts a value after the child at `index` of an object or an array, or first in an empty one.
+/// The key is ignored in arrays. A key the object already has is replaced, and moved after the
+/// child like a new one. `false` when the container is neither an object nor an array.
+pub fn insert_after(container: &mut Value, index: usize, key: String, value: Value) -> bool {
+    match container {
+        Value::Object(map) => {
+            let child_key = map.keys().nth(index).cloned();
+            if child_key.as_ref() == Some(&key) {
+                map.insert(key, value);
+            } else {
+                // Taking the replaced pair out first shifts the pairs after it.
+                map.shift_remove(&key);
+                let at = child_key
+                    .and_then(|child_key| map.keys().position(|existing| *existing == child_key))
+                    .map_or(map.len(), |position| position + 1);
+                map.shift_insert(at, key, value);
+            }
+        }
+        Value::Array(values) => values.insert((index + 1).min(values.len()), value),
+        _ => return false,
+    }
+
+    return true;
+}
+
+/// Swaps the places of two children of an object or an array. `false` when one of them isn't in it.
+pub fn swap_children(container: &mut Value, first: &PathSegment, second: &PathSegment) -> bool {
+    return match (container, first, second) {
+        (Value::Object(map), PathSegment::Key(first), PathSegment::Key(second)) => {
+            let mut pairs: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            let first = pairs.iter().position(|(key, _)| key == first);
+            let second = pairs.iter().position(|(key, _)| key == second);
+            if let (Some(first), Some(second)) = (first, second) {
+                pairs.swap(first, second);
+            }
+            *map = pairs.into_iter().collect::<Map<String, Value>>();
+
+            first.is_some() && second.is_some()
+        }
+        (Value::Array(values), PathSegment::Index(first), PathSegment::Index(second)) if *first < values.len() && *second < values.len() => {
+            values.swap(*first, *second);
+            true
+        }
+        _ => false,
+    };
+}
+
+/// Why a node couldn't be moved with `move_node`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveError {
+    /// The node is the document itself.
+    Root,
+    /// There's no node at the path.
+    NotFound,
+    /// The target is the node, or inside it.
+    IntoItself,
+    /// The node is already a child of the target.
+    AlreadyThere,
+    /// The target object already has the key of the node.
+    KeyTaken(String),
+    /// The node is an element of an array, with no key to go into an object.
+    NoKey,
+    /// The target isn't an object or an array.
+    NotAContainer,
+}
+
+/// Moves the node at `from` to the end of the object or array at `into`, and gives back the path
+/// it's moved to. Pairs keep their keys in objects, and only their values go into arrays.
+pub fn move_node(json: &mut Value, from: &[PathSegment], into: &[PathSegment]) -> Result<Vec<PathSegment>, MoveError> {
+    let Some((segment, parent)) = from.split_last() else {
+        return Err(MoveError::Root);
+    };
+    if into.starts_with(from) {
+        return Err(MoveError::IntoItself);
+    }
+    if into == parent {
+        return Err(MoveError::AlreadyThere);
+    }
+
+    let key = match (json.pointer(&path_to_pointer(into)), segment) {
+        (Some(Value::Object(map)), PathSegment::Key(key)) if map.contains_key(key) => return Err(MoveError::KeyTaken(key.clone())),
+        (Some(Value::Object(_)), PathSegment::Key(key)) => Some(key.clone()),
+        (Some(Value::Object(_)), PathSegment::Index(_)) => return Err(MoveError::NoKey),
+        (Some(Value::Array(_)), _) => None,
+        _ => return Err(MoveError::NotAContainer),
+    };
+
+    // Taking an element out of an array shifts the ones after it, which the target may be in.
+    let mut into = into.to_vec();
+    if into.starts_with(parent)
+        && let (PathSegment::Index(removed), Some(PathSegment::Index(index))) = (segment, into.get_mut(parent.len()))
+        && *index > *removed
+    {
+        *index -= 1;
+    }
+
+    let value = remove_by_pointer(json, &path_to_pointer(from)).map_err(|_| MoveError::NotFound)?;
+    let moved_segment = match get_value_at_path_mut(&into, json) {
+        Some(Value::Object(map)) => {
+            let key = key.unwrap_or_default();
+            map.insert(key.clone(), value);
+            PathSegment::Key(key)
+        }
+        Some(Value::Array(values)) => {
+            values.push(value);
+            PathSegment::Index(values.len() - 1)
+        }
+        _ => return Err(MoveError::NotAContainer),
+    };
+    into.push(moved_segment);
+
+    return Ok(into);
+}
+
+/// Removes the elements of an array from `start` up to `end` (excluded), or to its end without
+/// one or past it. `None` when there's no element at `start`.
+pub fn remove_elements(values: &mut Vec<Value>, start: usize, end: Option<usize>) -> Option<Vec<Value>> {
+    if start >= values.len() {
+        return None;
+    }
+
+    let end = end.unwrap_or(values.len()).min(values.len());
+
+    return Some(values.drain(start..end).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_insert_after() {
+        let mut json = json!({ "a": 1, "b": 2, "c": 3 });
+        assert!(insert_after(&mut json, 1, "d".to_string(), json!(4)));
+        // A key the object has is replaced after the child, wherever it was.
+        assert!(insert_after(&mut json, 1, "a".to_string(), json!(5)));
+        assert_eq!(serde_json::to_string(&json).unwrap(), r#"{"b":2,"a":5,"d":4,"c":3}"#);
+
+        let mut json = json!([]);
+        assert!(insert_after(&mut json, 0, String::new(), json!(1)));
+        assert!(insert_after(&mut json, 0, String::new(), json!(2)));
+        assert_eq!(json, json!([1, 2]));
+
+        assert!(!insert_after(&mut json!(1), 0, String::new(), json!(2)));
+    }
+
+    #[test]
+    fn test_move_node() {
+        let key = |key: &str| PathSegment::Key(key.to_string());
+        let mut json = json!({ "a": 1, "list": [{ "b": 2 }, [3]], "c": {} });
+
+        assert_eq!(move_node(&mut json, &[key("a")], &[key("c")]), Ok(vec![key("c"), key("a")]));
+        // The target comes after the moved element in the same array.
+        assert_eq!(
+            move_node(&mut json, &[key("list"), PathSegment::Index(0)], &[key("list"), PathSegment::Index(1)]),
+            Ok(vec![key("list"), PathSegment::Index(0), PathSegment::Index(1)]),
+        );
+        assert_eq!(json, json!({ "list": [[3, { "b": 2 }]], "c": { "a": 1 } }));
+
+        assert_eq!(move_node(&mut json, &[key("list")], &[key("list"), PathSegment::Index(0)]), Err(MoveError::IntoItself));
+        assert_eq!(move_node(&mut json, &[key("c"), key("a")], &[key("c")]), Err(MoveError::AlreadyThere));
+        assert_eq!(move_node(&mut json, &[key("list"), PathSegment::Index(0)], &[key("c")]), Err(MoveError::NoKey));
+        assert_eq!(move_node(&mut json, &[key("c"), key("a")], &[key("c"), key("a")]), Err(MoveError::IntoItself));
+        assert_eq!(move_node(&mut json, &[key("nope")], &[key("list")]), Err(MoveError::NotFound));
+        assert_eq!(json, json!({ "list": [[3, { "b": 2 }]], "c": { "a": 1 } }));
+    }
+
+    #[test]
+    fn test_remove_elements() {
+        let mut values = vec![json!(0), json!(1), json!(2), json!(3)];
+
+        assert_eq!(remove_elements(&mut values, 1, Some(3)), Some(vec![json!(1), json!(2)]));
+        assert_eq!(remove_elements(&mut values, 1, Some(100)), Some(vec![json!(3)]));
+        assert_eq!(remove_elements(&mut values, 1, None), None);
+        assert_eq!(values, vec![json!(0)]);
+    }
+}
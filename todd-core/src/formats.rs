@@ -0,0 +1,607 @@
+//
+// How documents are stored on disk, and how to read and write them.
+//
+
+use std::{io::{self, Read, Write}, path::Path};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::de::IgnoredAny;
+use serde_json::Value;
+
+use crate::{hjson, json::PlainNumbers, jsonc::{strip_comments, to_string_pretty, Comments}, xml::xml_to_json};
+
+/// The two bytes every gzip stream starts with.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The syntax of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Syntax {
+    /// Plain JSON.
+    #[default]
+    Json,
+    /// JSON5: unquoted keys, single quotes, trailing commas, comments... Comments are lost on save.
+    Json5,
+    /// JSON with comments (and trailing commas), like `tsconfig.json`. Comments are kept.
+    Jsonc,
+    /// Hjson, "human JSON". Comments are lost on save.
+    Hjson,
+    /// YAML. Comments and anchors are lost on save.
+    Yaml,
+    /// MessagePack, a binary encoding of the same data model as JSON.
+    MessagePack,
+    /// XML, mapped to JSON (see `xml_to_json`). It can only be read: the document is saved as JSON.
+    Xml,
+}
+
+impl Syntax {
+    /// The syntax of a file extension or a `:format` name.
+    pub fn parse(name: &str) -> Option<Syntax> {
+        return match name.to_lowercase().as_str() {
+            "json" => Some(Syntax::Json),
+            "json5" => Some(Syntax::Json5),
+            "jsonc" => Some(Syntax::Jsonc),
+            "hjson" => Some(Syntax::Hjson),
+            "yaml" | "yml" => Some(Syntax::Yaml),
+            "msgpack" | "mpk" => Some(Syntax::MessagePack),
+            _ => None,
+        };
+    }
+}
+
+/// The character encoding of a text file.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TextEncoding {
+    /// UTF-8, without a byte order mark.
+    #[default]
+    Utf8,
+    /// UTF-8 starting with a byte order mark, as some Windows tools write it.
+    Utf8Bom,
+    /// UTF-16, little-endian, as Windows writes it.
+    Utf16Le,
+    /// UTF-16, big-endian.
+    Utf16Be,
+}
+
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+const UTF16_LE_BOM: [u8; 2] = [0xff, 0xfe];
+const UTF16_BE_BOM: [u8; 2] = [0xfe, 0xff];
+
+impl TextEncoding {
+    /// Figures out the encoding from the byte order mark, or from where the zero bytes are when
+    /// UTF-16 comes without one (JSON text starts with an ASCII character).
+    fn detect(bytes: &[u8]) -> TextEncoding {
+        if bytes.starts_with(&UTF8_BOM) {
+            return TextEncoding::Utf8Bom;
+        }
+
+        return match bytes {
+            [0xff, 0xfe, ..] | [_, 0, _, 0, ..] => TextEncoding::Utf16Le,
+            [0xfe, 0xff, ..] | [0, _, 0, _, ..] => TextEncoding::Utf16Be,
+            _ => TextEncoding::Utf8,
+        };
+    }
+
+    /// Decodes the text. With `lossy`, invalid byte sequences are replaced with `�` instead of
+    /// failing, and the lines (1-based) where that happened are given back.
+    fn decode(&self, bytes: Vec<u8>, lossy: bool) -> io::Result<(String, Vec<usize>)> {
+        let invalid_data = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut text = String::with_capacity(bytes.len());
+        let mut replaced_lines: Vec<usize> = vec![];
+        let mut line = 1;
+
+        let mut replace = |text: &mut String, line: usize| {
+            text.push(char::REPLACEMENT_CHARACTER);
+            if replaced_lines.last() != Some(&line) {
+                replaced_lines.push(line);
+            }
+        };
+
+        let (is_little_endian, bytes) = match self {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+                let bytes = bytes.strip_prefix(&UTF8_BOM).unwrap_or(&bytes);
+
+                for chunk in bytes.utf8_chunks() {
+                    text.push_str(chunk.valid());
+                    line += chunk.valid().matches('\n').count();
+
+                    if !chunk.invalid().is_empty() {
+                        if !lossy {
+                            return Err(invalid_data(format!("Invalid UTF-8 on line {}. Pass --lossy to open the file anyway", line)));
+                        }
+                        replace(&mut text, line);
+                    }
+                }
+
+                return Ok((text, replaced_lines));
+            }
+            TextEncoding::Utf16Le => (true, bytes.strip_prefix(&UTF16_LE_BOM).unwrap_or(&bytes)),
+            TextEncoding::Utf16Be => (false, bytes.strip_prefix(&UTF16_BE_BOM).unwrap_or(&bytes)),
+        };
+
+        let units = bytes
+            .chunks(2)
+            .map(|pair| match pair {
+                [first, second] if is_little_endian => u16::from_le_bytes([*first, *second]),
+                [first, second] => u16::from_be_bytes([*first, *second]),
+                // A dangling byte at the end. Not a valid code unit on its own.
+                _ => 0xdc00,
+            });
+
+        for unit in char::decode_utf16(units) {
+            match unit {
+                Ok(c) => {
+                    text.push(c);
+                    if c == '\n' {
+                        line += 1;
+                    }
+                }
+                Err(_) if lossy => replace(&mut text, line),
+                Err(_) => return Err(invalid_data(format!("Invalid UTF-16 on line {}. Pass --lossy to open the file anyway", line))),
+            }
+        }
+
+        return Ok((text, replaced_lines));
+    }
+
+    fn encode(&self, text: &str) -> Vec<u8> {
+        return match self {
+            TextEncoding::Utf8 => text.as_bytes().to_vec(),
+            TextEncoding::Utf8Bom => UTF8_BOM.iter().copied().chain(text.bytes()).collect(),
+            TextEncoding::Utf16Le => UTF16_LE_BOM.iter().copied().chain(text.encode_utf16().flat_map(u16::to_le_bytes)).collect(),
+            TextEncoding::Utf16Be => UTF16_BE_BOM.iter().copied().chain(text.encode_utf16().flat_map(u16::to_be_bytes)).collect(),
+        };
+    }
+}
+
+/// The last resort of `--lenient`. The Hjson parser takes about anything hand-written: comments
+/// of any kind, trailing or missing commas, unquoted keys and strings. Only objects and arrays
+/// are accepted, since any line of text is a valid Hjson string.
+fn parse_leniently(text: &str) -> Option<Value> {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+        return None;
+    }
+
+    return hjson::from_str(text).ok().filter(|json| json.is_object() || json.is_array());
+}
+
+/// A file read into JSON text.
+#[derive(Debug, Default)]
+pub struct Decoded {
+    /// The document as strict JSON text.
+    pub text: String,
+    /// The comments of a JSONC file.
+    pub comments: Comments,
+    /// The lines where invalid bytes were replaced, when reading lossily.
+    pub replaced_lines: Vec<usize>,
+    /// Only the forgiving parser of `--lenient` could make sense of it.
+    pub parsed_leniently: bool,
+}
+
+/// How the documents of a file holding several JSON documents back to back are laid out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Concatenation {
+    /// One compact document per line, like JSON Lines logs.
+    OnePerLine,
+    /// Pretty-printed documents, one after the other.
+    Pretty,
+}
+
+/// How a file is stored, to read it and write it back the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FileFormat {
+    /// The file is compressed with gzip (e.g. `data.json.gz`). It's decompressed on load and
+    /// compressed again on save.
+    pub gzip: bool,
+    /// The syntax of the (decompressed) content.
+    pub syntax: Syntax,
+    /// The encoding of text files. They're written back in the encoding they were read in.
+    pub encoding: TextEncoding,
+    /// Replace invalid byte sequences when reading text instead of failing.
+    pub lossy: bool,
+    /// Fall back to a forgiving parser when the file isn't valid in any supported syntax.
+    pub lenient: bool,
+    /// Only take strict JSON for JSON files, instead of switching to JSONC or JSON5 when that's
+    /// what they're written in, for `--check`.
+    pub strict: bool,
+    /// Write JSON on a single line instead of pretty-printed, for `todd fmt --compact`.
+    pub compact: bool,
+    /// The file holds several JSON documents back to back. They're shown as the elements of a
+    /// top-level array, and written back one after the other.
+    pub concatenated: Option<Concatenation>,
+}
+
+impl FileFormat {
+    /// Figures out the format of a file from its extension, or its content when the extension
+    /// doesn't tell.
+    pub fn detect(path: &Path, bytes: &[u8]) -> FileFormat {
+        let has_gz_extension = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"));
+
+        // Look through the compression extension for the syntax (e.g. `data.json5.gz`).
+        let syntax_path = if has_gz_extension { Path::new(path.file_stem().unwrap_or_default()) } else { path };
+        let syntax = match syntax_path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("xml") => Syntax::Xml,
+            Some(extension) => Syntax::parse(extension).unwrap_or_default(),
+            None => Syntax::default(),
+        };
+
+        return FileFormat {
+            gzip: has_gz_extension || bytes.starts_with(&GZIP_MAGIC),
+            syntax,
+            encoding: TextEncoding::default(),
+            lossy: false,
+            lenient: false,
+            strict: false,
+            compact: false,
+            concatenated: None,
+        };
+    }
+
+    /// Turns the raw bytes of a file into (strict) JSON text.
+    pub fn decode(&mut self, bytes: Vec<u8>) -> io::Result<Decoded> {
+        let bytes = if self.gzip {
+            let mut decompressed = vec![];
+            GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+            decompressed
+        } else {
+            bytes
+        };
+
+        if self.syntax == Syntax::MessagePack {
+            let json: Value = rmp_serde::from_slice(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            return Ok(Decoded {
+                text: serde_json::to_string(&json)?,
+                ..Decoded::default()
+            });
+        }
+
+        self.encoding = TextEncoding::detect(&bytes);
+        let (text, replaced_lines) = self.encoding.decode(bytes, self.lossy)?;
+
+        return Ok(Decoded {
+            replaced_lines,
+            ..self.text_to_json(text)?
+        });
+    }
+
+    /// Turns text in the syntax of the file into JSON text, and the comments it had if it's JSONC.
+    ///
+    /// JSONC and JSON5 are accepted even when the extension doesn't say so, in which case the
+    /// syntax is switched to them so the file is saved back the way it was written.
+    fn text_to_json(&mut self, text: String) -> io::Result<Decoded> {
+        if self.syntax == Syntax::Xml {
+            let json = xml_to_json(&text).map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))?;
+            return Ok(Decoded { text: serde_json::to_string(&json)?, ..Decoded::default() });
+        }
+
+        if self.syntax == Syntax::Yaml {
+            let json: Value = serde_yaml_ng::from_str(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            return Ok(Decoded { text: serde_json::to_string(&json)?, ..Decoded::default() });
+        }
+
+        if self.syntax == Syntax::Hjson {
+            let json = hjson::from_str(&text).map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))?;
+            return Ok(Decoded { text: serde_json::to_string(&json)?, ..Decoded::default() });
+        }
+
+        if self.syntax == Syntax::Json && serde_json::from_str::<IgnoredAny>(&text).is_ok() {
+            return Ok(Decoded { text, ..Decoded::default() });
+        }
+
+        // Documents back to back aren't a JSON document, for a strict check.
+        if self.syntax == Syntax::Json && !self.strict && let Some(documents) = split_documents(&text) {
+            let lines_count = text.lines().filter(|line| !line.trim().is_empty()).count();
+            self.concatenated = Some(if lines_count == documents.len() { Concatenation::OnePerLine } else { Concatenation::Pretty });
+
+            return Ok(Decoded { text: serde_json::to_string(&Value::Array(documents))?, ..Decoded::default() });
+        }
+
+        // Leave it to the JSON parser to report what's wrong with it.
+        if self.syntax == Syntax::Json && self.strict {
+            return Ok(Decoded { text, ..Decoded::default() });
+        }
+
+        if self.syntax != Syntax::Json5 {
+            let (stripped, comments) = strip_comments(&text);
+            if serde_json::from_str::<IgnoredAny>(&stripped).is_ok() {
+                self.syntax = Syntax::Jsonc;
+                return Ok(Decoded { text: stripped, comments, ..Decoded::default() });
+            }
+        }
+
+        let json = match json5::from_str::<Value>(&text) {
+            Ok(json) => json,
+            Err(_) if self.lenient && let Some(json) = parse_leniently(&text) => {
+                // It's in no syntax in particular, so it's saved as strict JSON.
+                self.syntax = Syntax::Json;
+                return Ok(Decoded { text: serde_json::to_string(&json)?, parsed_leniently: true, ..Decoded::default() });
+            }
+            // Not JSON5 either. Leave it to the JSON parser to report what's wrong with it.
+            Err(_) if self.syntax != Syntax::Json5 => return Ok(Decoded { text, ..Decoded::default() }),
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+
+        self.syntax = Syntax::Json5;
+
+        return Ok(Decoded { text: serde_json::to_string(&json)?, ..Decoded::default() });
+    }
+
+    /// Turns the JSON data into the bytes to write to the file. The comments are only written in JSONC.
+    pub fn encode(&self, json: &Value, comments: &Comments) -> io::Result<Vec<u8>> {
+        let content = match (self.concatenated, json) {
+            (Some(concatenation), Value::Array(documents)) if self.syntax == Syntax::Json => {
+                let mut content = vec![];
+                for document in documents {
+                    match concatenation {
+                        Concatenation::OnePerLine => serde_json::to_writer(&mut content, document)?,
+                        Concatenation::Pretty => serde_json::to_writer_pretty(&mut content, document)?,
+                    }
+                    content.push(b'\n');
+                }
+                content
+            }
+            _ => self.encode_document(json, comments)?,
+        };
+
+        let content = match (self.syntax, self.encoding) {
+            (Syntax::MessagePack, _) | (_, TextEncoding::Utf8) => content,
+            // The documents are always written as UTF-8 text first.
+            (_, encoding) => encoding.encode(&String::from_utf8_lossy(&content)),
+        };
+
+        if !self.gzip {
+            return Ok(content);
+        }
+
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(&content)?;
+
+        return encoder.finish();
+    }
+
+    /// Writes a single document in the syntax of the file.
+    fn encode_document(&self, json: &Value, comments: &Comments) -> io::Result<Vec<u8>> {
+        return Ok(match self.syntax {
+            Syntax::Json | Syntax::Xml if self.compact => serde_json::to_string(json)?.into_bytes(),
+            Syntax::Json | Syntax::Xml => serde_json::to_string_pretty(json)?.into_bytes(),
+            Syntax::Jsonc => to_string_pretty(json, comments).into_bytes(),
+            Syntax::Json5 => {
+                let mut content = json5::to_string(&PlainNumbers(json)).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                content.push('\n');
+                content.into_bytes()
+            }
+            Syntax::Hjson => hjson::to_string(json).into_bytes(),
+            Syntax::Yaml => serde_yaml_ng::to_string(&PlainNumbers(json)).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?.into_bytes(),
+            Syntax::MessagePack => rmp_serde::to_vec(&PlainNumbers(json)).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+        });
+    }
+}
+
+/// Splits a text made of several JSON documents back to back. Gives back `None` if there's
+/// only one, or if it isn't made of JSON documents.
+fn split_documents(text: &str) -> Option<Vec<Value>> {
+    let documents: Vec<Value> = serde_json::Deserializer::from_str(text)
+        .into_iter::<Value>()
+        .collect::<Result<_, _>>()
+        .ok()?;
+
+    return if documents.len() > 1 { Some(documents) } else { None };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let mut format = FileFormat::detect(Path::new("data.json.gz"), &[]);
+        assert!(format.gzip);
+
+        let json = json!({ "name": "Omar", "tags": [1, 2, 3] });
+        let bytes = format.encode(&json, &Comments::default()).unwrap();
+        assert!(bytes.starts_with(&GZIP_MAGIC));
+
+        // Detected by content even without the extension.
+        assert!(FileFormat::detect(Path::new("data.json"), &bytes).gzip);
+
+        let text = format.decode(bytes).unwrap().text;
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), json);
+    }
+
+    #[test]
+    fn test_plain_json_is_untouched() {
+        let mut format = FileFormat::detect(Path::new("data.json"), b"{}");
+        assert!(!format.gzip);
+        assert_eq!(format.decode(b"{}".to_vec()).unwrap().text, "{}");
+    }
+
+    #[test]
+    fn test_precise_numbers_survive_a_round_trip() {
+        let mut format = FileFormat::default();
+        let content = "{\n  \"id\": 123456789012345678901234567890,\n  \"price\": 0.1000000000000000055511151231257827\n}";
+
+        let Decoded { text, comments, .. } = format.decode(content.as_bytes().to_vec()).unwrap();
+        let json: Value = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(format.encode(&json, &comments).unwrap(), content.as_bytes());
+    }
+
+    #[test]
+    fn test_precise_numbers_are_not_rounded_in_other_formats() {
+        for path in ["data.yaml", "data.json5", "data.msgpack"] {
+            let mut format = FileFormat::detect(Path::new(path), &[]);
+
+            let json: Value = serde_json::from_str(r#"{ "ids": [12345678901234567890123] }"#).unwrap();
+            let err = format.encode(&json, &Comments::default()).unwrap_err();
+            assert!(err.to_string().contains("12345678901234567890123 at ids[0] can't be written without losing precision"), "{}: {}", path, err);
+
+            let json: Value = serde_json::from_str(r#"{ "price": 0.1000000000000000055511151231257827 }"#).unwrap();
+            let err = format.encode(&json, &Comments::default()).unwrap_err();
+            assert!(err.to_string().contains("0.1000000000000000055511151231257827 at price"), "{}: {}", path, err);
+
+            // Decimals a float holds exactly, whatever way they're written, are kept.
+            let json: Value = serde_json::from_str("[0.1, 1.50, 1e3, -2.5E-3, 0.0, 100000000000000000000]").unwrap();
+            let bytes = format.encode(&json, &Comments::default()).unwrap();
+            let text = format.decode(bytes).unwrap().text;
+            let numbers: Vec<f64> = serde_json::from_str(&text).unwrap();
+            assert_eq!(numbers, [0.1, 1.5, 1000.0, -0.0025, 0.0, 1e20], "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_number_formatting_is_kept() {
+        let content = "[\n  1.0,\n  1e6,\n  1E+06,\n  -0.0,\n  0.50\n]";
+
+        for path in ["data.json", "data.jsonc"] {
+            let mut format = FileFormat::detect(Path::new(path), &[]);
+            let Decoded { text, comments, .. } = format.decode(content.as_bytes().to_vec()).unwrap();
+            let json: Value = serde_json::from_str(&text).unwrap();
+
+            assert_eq!(String::from_utf8(format.encode(&json, &comments).unwrap()).unwrap(), content);
+        }
+    }
+
+    #[test]
+    fn test_utf16_and_bom() {
+        let json = json!({ "name": "Ømar" });
+
+        for encoding in [TextEncoding::Utf8Bom, TextEncoding::Utf16Le, TextEncoding::Utf16Be] {
+            let format = FileFormat { encoding, ..FileFormat::default() };
+            let bytes = format.encode(&json, &Comments::default()).unwrap();
+
+            let mut detected = FileFormat::default();
+            let text = detected.decode(bytes).unwrap().text;
+            assert_eq!(detected.encoding, encoding);
+            assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), json);
+        }
+
+        // UTF-16 without a byte order mark.
+        let bytes: Vec<u8> = "[1]".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut format = FileFormat::default();
+        assert_eq!(format.decode(bytes).unwrap().text, "[1]");
+        assert_eq!(format.encoding, TextEncoding::Utf16Le);
+    }
+
+    #[test]
+    fn test_lossy_decoding() {
+        let bytes = b"{\n  \"a\": \"\xff\",\n  \"b\": \"ok\",\n  \"c\": \"\xc3\x28\"\n}".to_vec();
+
+        assert!(FileFormat::default().decode(bytes.clone()).is_err());
+
+        let mut format = FileFormat { lossy: true, ..FileFormat::default() };
+        let decoded = format.decode(bytes).unwrap();
+
+        assert_eq!(decoded.replaced_lines, vec![2, 4]);
+        assert_eq!(serde_json::from_str::<Value>(&decoded.text).unwrap(), json!({ "a": "\u{fffd}", "b": "ok", "c": "\u{fffd}(" }));
+    }
+
+    #[test]
+    fn test_lenient() {
+        // A trailing comma, a `#` comment and a missing comma: neither JSONC nor JSON5.
+        let content = b"{\n  # Port.\n  \"port\": 80,\n  \"hosts\": [\"a\", \"b\",]\n  \"debug\": true\n}".to_vec();
+
+        let mut format = FileFormat::detect(Path::new("config.json"), &[]);
+        let text = format.decode(content.clone()).unwrap().text;
+        assert!(serde_json::from_str::<Value>(&text).is_err());
+
+        let mut format = FileFormat { lenient: true, ..FileFormat::detect(Path::new("config.json"), &[]) };
+        let decoded = format.decode(content).unwrap();
+        assert!(decoded.parsed_leniently);
+        assert_eq!(format.syntax, Syntax::Json);
+        assert_eq!(serde_json::from_str::<Value>(&decoded.text).unwrap(), json!({ "port": 80, "hosts": ["a", "b"], "debug": true }));
+
+        let mut format = FileFormat { lenient: true, ..FileFormat::default() };
+        let text = format.decode(b"not json".to_vec()).unwrap().text;
+        assert!(serde_json::from_str::<Value>(&text).is_err());
+    }
+
+    #[test]
+    fn test_json5() {
+        let mut format = FileFormat::detect(Path::new("data.json"), &[]);
+        assert_eq!(format.syntax, Syntax::Json);
+
+        let text = format.decode(b"{ name: 'Omar', tags: [1, 2,], }".to_vec()).unwrap().text;
+        assert_eq!(format.syntax, Syntax::Json5);
+
+        let json: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json, json!({ "name": "Omar", "tags": [1, 2] }));
+
+        let saved = String::from_utf8(format.encode(&json, &Comments::default()).unwrap()).unwrap();
+        assert!(saved.contains("name: \"Omar\""));
+        assert!(saved.ends_with('\n'));
+
+        assert_eq!(FileFormat::detect(Path::new("data.json5.gz"), &[]).syntax, Syntax::Json5);
+    }
+
+    #[test]
+    fn test_concatenated_documents() {
+        let mut format = FileFormat::detect(Path::new("events.log"), &[]);
+
+        let text = format.decode(b"{\"id\":1}\n{\"id\":2}\n\n[3]\n".to_vec()).unwrap().text;
+        assert_eq!(format.concatenated, Some(Concatenation::OnePerLine));
+
+        let json: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json, json!([{ "id": 1 }, { "id": 2 }, [3]]));
+
+        let saved = format.encode(&json, &Comments::default()).unwrap();
+        assert_eq!(saved, b"{\"id\":1}\n{\"id\":2}\n[3]\n");
+
+        let mut format = FileFormat::default();
+        format.decode(b"{\n  \"id\": 1\n}\n{\n  \"id\": 2\n}".to_vec()).unwrap();
+        assert_eq!(format.concatenated, Some(Concatenation::Pretty));
+    }
+
+    #[test]
+    fn test_hjson() {
+        let mut format = FileFormat::detect(Path::new("config.hjson"), &[]);
+        assert_eq!(format.syntax, Syntax::Hjson);
+
+        let text = format.decode(b"# Comment\nname: Omar\nport: 80".to_vec()).unwrap().text;
+        let json: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json, json!({ "name": "Omar", "port": 80 }));
+
+        let saved = String::from_utf8(format.encode(&json, &Comments::default()).unwrap()).unwrap();
+        assert_eq!(saved, "{\n  name: Omar\n  port: 80\n}");
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let mut format = FileFormat::detect(Path::new("compose.yml"), &[]);
+        assert_eq!(format.syntax, Syntax::Yaml);
+
+        let text = format.decode(b"# Comment\nname: Omar\ntags:\n  - 1\n  - two\n".to_vec()).unwrap().text;
+        let json: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json, json!({ "name": "Omar", "tags": [1, "two"] }));
+
+        let saved = String::from_utf8(format.encode(&json, &Comments::default()).unwrap()).unwrap();
+        assert_eq!(saved, "name: Omar\ntags:\n- 1\n- two\n");
+    }
+
+    #[test]
+    fn test_message_pack_round_trip() {
+        let mut format = FileFormat::detect(Path::new("cache.msgpack"), &[]);
+        assert_eq!(format.syntax, Syntax::MessagePack);
+
+        let json = json!({ "name": "Omar", "tags": [1, 2.5, null, true] });
+        let bytes = format.encode(&json, &Comments::default()).unwrap();
+
+        let text = format.decode(bytes).unwrap().text;
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), json);
+    }
+
+    #[test]
+    fn test_jsonc_keeps_comments() {
+        let mut format = FileFormat::detect(Path::new("tsconfig.json"), &[]);
+
+        let Decoded { text, comments, .. } = format.decode(b"{\n  // Strict mode.\n  \"strict\": true,\n}".to_vec()).unwrap();
+        assert_eq!(format.syntax, Syntax::Jsonc);
+
+        let json: Value = serde_json::from_str(&text).unwrap();
+        let saved = String::from_utf8(format.encode(&json, &comments).unwrap()).unwrap();
+        assert_eq!(saved, "{\n  // Strict mode.\n  \"strict\": true\n}");
+    }
+}
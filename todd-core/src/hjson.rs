@@ -0,0 +1,408 @@
+//
+// Hjson ("human JSON", https://hjson.github.io): JSON with comments, optional commas and quotes,
+// multiline strings, and an optional pair of braces around the root object.
+//
+
+use serde_json::{Map, Value};
+
+/// Characters that can't start a quoteless string.
+const PUNCTUATORS: [char; 6] = ['{', '}', '[', ']', ',', ':'];
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        return self.chars.get(self.position).copied();
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        return self.chars.get(self.position + offset).copied();
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.chars[..self.position.min(self.chars.len())].iter().filter(|c| **c == '\n').count() + 1;
+        return format!("Invalid Hjson at line {}: {}", line, message);
+    }
+
+    fn is_at_comment(&self) -> bool {
+        return self.peek() == Some('#')
+            || (self.peek() == Some('/') && matches!(self.peek_at(1), Some('/') | Some('*')));
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.position += 1;
+            } else if c == '/' && self.peek_at(1) == Some('*') {
+                self.position += 2;
+                while self.peek().is_some() && !(self.peek() == Some('*') && self.peek_at(1) == Some('/')) {
+                    self.position += 1;
+                }
+                self.position = (self.position + 2).min(self.chars.len());
+            } else if self.is_at_comment() {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.position += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn parse_root(&mut self) -> Result<Value, String> {
+        self.skip_whitespace_and_comments();
+
+        let value = match self.peek() {
+            Some('{') | Some('[') => self.parse_value()?,
+            _ => {
+                // The braces around the root object can be left out. If it isn't an object,
+                // it's a single value.
+                let start = self.position;
+                match self.parse_members(None) {
+                    Ok(members) => Value::Object(members),
+                    Err(_) => {
+                        self.position = start;
+                        self.parse_value()?
+                    }
+                }
+            }
+        };
+
+        self.skip_whitespace_and_comments();
+        if self.peek().is_some() {
+            return Err(self.error("unexpected content after the end of the document"));
+        }
+
+        return Ok(value);
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace_and_comments();
+
+        return match self.peek() {
+            Some('{') => {
+                self.position += 1;
+                Ok(Value::Object(self.parse_members(Some('}'))?))
+            }
+            Some('[') => {
+                self.position += 1;
+                self.parse_elements()
+            }
+            Some('\'') if self.peek_at(1) == Some('\'') && self.peek_at(2) == Some('\'') => {
+                Ok(Value::String(self.parse_multiline_string()))
+            }
+            Some(quote @ ('"' | '\'')) => Ok(Value::String(self.parse_quoted_string(quote)?)),
+            Some(_) => self.parse_quoteless_value(),
+            None => Err(self.error("expected a value")),
+        };
+    }
+
+    /// Parses the members of an object up to `closing`, or up to the end of the text for a root
+    /// object without braces.
+    fn parse_members(&mut self, closing: Option<char>) -> Result<Map<String, Value>, String> {
+        let mut members = Map::new();
+
+        loop {
+            self.skip_whitespace_and_comments();
+
+            match (self.peek(), closing) {
+                (None, None) => return Ok(members),
+                (None, Some(_)) => return Err(self.error("unclosed object")),
+                (Some(c), Some(closing)) if c == closing => {
+                    self.position += 1;
+                    return Ok(members);
+                }
+                _ => {}
+            }
+
+            let key = match self.peek() {
+                Some(quote @ ('"' | '\'')) => self.parse_quoted_string(quote)?,
+                _ => {
+                    let start = self.position;
+                    while self.peek().is_some_and(|c| c != ':' && !c.is_whitespace() && !PUNCTUATORS.contains(&c)) {
+                        self.position += 1;
+                    }
+                    if start == self.position {
+                        return Err(self.error("expected a key"));
+                    }
+                    self.chars[start..self.position].iter().collect()
+                }
+            };
+
+            self.skip_whitespace_and_comments();
+            if self.peek() != Some(':') {
+                return Err(self.error(&format!("expected ':' after the key \"{}\"", key)));
+            }
+            self.position += 1;
+
+            let value = self.parse_value()?;
+            members.insert(key, value);
+
+            self.skip_whitespace_and_comments();
+            if self.peek() == Some(',') {
+                self.position += 1;
+            }
+        }
+    }
+
+    fn parse_elements(&mut self) -> Result<Value, String> {
+        let mut elements = vec![];
+
+        loop {
+            self.skip_whitespace_and_comments();
+
+            match self.peek() {
+                None => return Err(self.error("unclosed array")),
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Value::Array(elements));
+                }
+                _ => {}
+            }
+
+            elements.push(self.parse_value()?);
+
+            self.skip_whitespace_and_comments();
+            if self.peek() == Some(',') {
+                self.position += 1;
+            }
+        }
+    }
+
+    fn parse_quoted_string(&mut self, quote: char) -> Result<String, String> {
+        self.position += 1;
+        let mut string = String::new();
+
+        loop {
+            match self.peek() {
+                None | Some('\n') => return Err(self.error("unclosed string")),
+                Some(c) if c == quote => {
+                    self.position += 1;
+                    return Ok(string);
+                }
+                Some('\\') => {
+                    let escaped = self.peek_at(1).ok_or_else(|| self.error("unclosed string"))?;
+                    self.position += 2;
+                    match escaped {
+                        'n' => string.push('\n'),
+                        't' => string.push('\t'),
+                        'r' => string.push('\r'),
+                        'b' => string.push('\u{8}'),
+                        'f' => string.push('\u{c}'),
+                        'u' => {
+                            let hex: String = self.chars[self.position..(self.position + 4).min(self.chars.len())].iter().collect();
+                            let code = u32::from_str_radix(&hex, 16).map_err(|_| self.error("invalid unicode escape"))?;
+                            string.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                            self.position += 4;
+                        }
+                        c => string.push(c),
+                    }
+                }
+                Some(c) => {
+                    string.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    /// Parses a `'''` string. The indentation of the opening quotes is removed from every line.
+    fn parse_multiline_string(&mut self) -> String {
+        let line_start = self.chars[..self.position].iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1);
+        let indentation = self.position - line_start;
+
+        self.position += 3;
+        let start = self.position;
+        while self.peek().is_some() && !(self.peek() == Some('\'') && self.peek_at(1) == Some('\'') && self.peek_at(2) == Some('\'')) {
+            self.position += 1;
+        }
+        let raw: String = self.chars[start..self.position].iter().collect();
+        self.position = (self.position + 3).min(self.chars.len());
+
+        let mut lines: Vec<&str> = raw.split('\n').collect();
+        // The first and last lines are only there to hold the quotes.
+        if lines.len() > 1 && lines[0].trim().is_empty() {
+            lines.remove(0);
+        }
+        if lines.len() > 1 && lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+
+        return lines
+            .iter()
+            .map(|line| {
+                let removable = line.chars().take(indentation).take_while(|c| c.is_whitespace()).count();
+                &line[line.char_indices().nth(removable).map_or(line.len(), |(i, _)| i)..]
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+    }
+
+    /// Parses `true`, `false`, `null`, a number, or else a quoteless string that runs to the end
+    /// of the line.
+    fn parse_quoteless_value(&mut self) -> Result<Value, String> {
+        if self.peek().is_some_and(|c| PUNCTUATORS.contains(&c)) {
+            return Err(self.error("expected a value"));
+        }
+
+        let start = self.position;
+        let line_end = self.chars[start..].iter().position(|c| *c == '\n').map_or(self.chars.len(), |i| start + i);
+
+        // A literal only counts if nothing but punctuation or a comment follows it on the line.
+        let token_end = self.chars[start..line_end]
+            .iter()
+            .position(|c| c.is_whitespace() || matches!(c, ',' | '}' | ']' | '#' | '/'))
+            .map_or(line_end, |i| start + i);
+        let token: String = self.chars[start..token_end].iter().collect();
+
+        if let Ok(literal @ (Value::Bool(_) | Value::Null | Value::Number(_))) = serde_json::from_str::<Value>(&token) {
+            self.position = token_end;
+            while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+                self.position += 1;
+            }
+            if self.peek().is_none_or(|c| matches!(c, '\n' | '\r' | ',' | '}' | ']')) || self.is_at_comment() {
+                return Ok(literal);
+            }
+        }
+
+        self.position = line_end;
+        let string: String = self.chars[start..line_end].iter().collect();
+
+        return Ok(Value::String(string.trim_end().to_string()));
+    }
+}
+
+/// Parses an Hjson document.
+pub fn from_str(text: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        position: 0,
+    };
+
+    return parser.parse_root();
+}
+
+/// Writes JSON data as Hjson, leaving out the quotes wherever they aren't needed.
+pub fn to_string(json: &Value) -> String {
+    let mut output = String::new();
+    write_value(&mut output, json, 0);
+
+    return output;
+}
+
+fn write_value(output: &mut String, value: &Value, depth: usize) {
+    let inner_indentation = "  ".repeat(depth + 1);
+
+    match value {
+        Value::Object(members) if !members.is_empty() => {
+            output.push_str("{\n");
+            for (key, member) in members {
+                output.push_str(&inner_indentation);
+                output.push_str(&format_key(key));
+                output.push_str(": ");
+                write_value(output, member, depth + 1);
+                output.push('\n');
+            }
+            output.push_str(&"  ".repeat(depth));
+            output.push('}');
+        }
+        Value::Array(elements) if !elements.is_empty() => {
+            output.push_str("[\n");
+            for element in elements {
+                output.push_str(&inner_indentation);
+                write_value(output, element, depth + 1);
+                output.push('\n');
+            }
+            output.push_str(&"  ".repeat(depth));
+            output.push(']');
+        }
+        Value::String(string) => output.push_str(&format_string(string)),
+        _ => output.push_str(&value.to_string()),
+    }
+}
+
+fn format_key(key: &str) -> String {
+    let needs_quotes = key.is_empty()
+        || key.chars().any(|c| c.is_whitespace() || PUNCTUATORS.contains(&c) || matches!(c, '"' | '\'' | '#' | '/'));
+
+    return if needs_quotes { Value::String(key.to_string()).to_string() } else { key.to_string() };
+}
+
+/// Strings are written without quotes when reading them back gives the same string.
+fn format_string(string: &str) -> String {
+    let can_be_quoteless = !string.is_empty()
+        && string.trim() == string
+        && !string.contains(['\n', '\r'])
+        && !string.starts_with(['"', '\''])
+        && from_str(&format!("[\n{}\n]", string)).is_ok_and(|value| value == Value::Array(vec![Value::String(string.to_string())]));
+
+    return if can_be_quoteless { string.to_string() } else { Value::String(string.to_string()).to_string() };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_str() {
+        let text = r#"
+# A config without the root braces.
+name: Omar Rafat, esq.
+port: 8080 // The default.
+debug: true
+"quoted key": 'single quoted'
+tags: [ 1, 2, three
+  "four" ]
+nested: {
+  empty: {}
+  /* Block comment. */
+  note:
+    '''
+    First line
+      Indented line
+    '''
+}
+"#;
+
+        assert_eq!(
+            from_str(text).unwrap(),
+            json!({
+                "name": "Omar Rafat, esq.",
+                "port": 8080,
+                "debug": true,
+                "quoted key": "single quoted",
+                "tags": [1, 2, "three", "four"],
+                "nested": {
+                    "empty": {},
+                    "note": "First line\n  Indented line"
+                }
+            })
+        );
+
+        assert!(from_str("{ a: 1").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let json = json!({
+            "name": "Omar",
+            "with space": "3 apples",
+            "number_like": "42",
+            "padded": " x ",
+            "comment_like": "# not a comment",
+            "lines": "a\nb",
+            "list": [true, null, 1.5, [], {}]
+        });
+
+        let text = to_string(&json);
+        assert!(text.contains("name: Omar\n"));
+        assert!(text.contains("\"with space\": 3 apples\n"));
+        assert!(text.contains("number_like: \"42\"\n"));
+
+        assert_eq!(from_str(&text).unwrap(), json);
+    }
+}
@@ -0,0 +1,951 @@
+use serde::{ser::Error, Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::patch::parse_pointer;
+
+/// One step of a path into a JSON document: a key into an object or an index into an array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    /// The key of a pair in an object.
+    Key(String),
+    /// The index of an element in an array.
+    Index(usize),
+}
+
+/// Serializes JSON data with plain numbers, for formats other than JSON.
+///
+/// Numbers keep their exact source text (serde_json's `arbitrary_precision`), which serde_json
+/// serializes as a special struct that only its own serializer understands. Other serializers
+/// get them as regular integers or floats instead. A number that neither holds exactly, like a
+/// big integer or a decimal with too many digits, fails the serialization with its path rather
+/// than being rounded.
+pub struct PlainNumbers<'a>(pub &'a Value);
+
+impl Serialize for PlainNumbers<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return PlainNumbersAt { value: self.0, parent: None }.serialize(serializer);
+    }
+}
+
+/// A value being serialized by `PlainNumbers`, with the way back to the root to name its path.
+struct PlainNumbersAt<'a> {
+    value: &'a Value,
+    parent: Option<(&'a PlainNumbersAt<'a>, PathStep<'a>)>,
+}
+
+enum PathStep<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+impl PlainNumbersAt<'_> {
+    fn path(&self) -> Vec<PathSegment> {
+        let mut path = vec![];
+        let mut node = self;
+        while let Some((parent, step)) = &node.parent {
+            path.push(match step {
+                PathStep::Key(key) => PathSegment::Key(key.to_string()),
+                PathStep::Index(index) => PathSegment::Index(*index),
+            });
+            node = parent;
+        }
+        path.reverse();
+
+        return path;
+    }
+}
+
+impl Serialize for PlainNumbersAt<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return match self.value {
+            Value::Number(number) => {
+                if let Some(integer) = number.as_i64() {
+                    serializer.serialize_i64(integer)
+                } else if let Some(integer) = number.as_u64() {
+                    serializer.serialize_u64(integer)
+                } else if let Some(float) = number.as_f64().filter(|float| float.is_finite() && same_decimal(&float.to_string(), &number.to_string())) {
+                    serializer.serialize_f64(float)
+                } else {
+                    let path = self.path();
+                    Err(S::Error::custom(if path.is_empty() {
+                        format!("{} can't be written without losing precision", number)
+                    } else {
+                        format!("{} at {} can't be written without losing precision", number, path_to_string(&path))
+                    }))
+                }
+            }
+            Value::Array(values) => serializer.collect_seq(values.iter().enumerate().map(|(index, value)| {
+                PlainNumbersAt { value, parent: Some((self, PathStep::Index(index))) }
+            })),
+            Value::Object(map) => serializer.collect_map(map.iter().map(|(key, value)| {
+                (key, PlainNumbersAt { value, parent: Some((self, PathStep::Key(key))) })
+            })),
+            _ => self.value.serialize(serializer),
+        };
+    }
+}
+
+/// Whether two numbers written in decimal, with or without an exponent, are the same number.
+fn same_decimal(first: &str, second: &str) -> bool {
+    // The significant digits, and the power of ten of the point before them.
+    let normalize = |text: &str| {
+        let (negative, text) = text.strip_prefix('-').map_or((false, text), |text| (true, text));
+        let (mantissa, exponent) = text.split_once(['e', 'E']).unwrap_or((text, "0"));
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+        let digits = format!("{}{}", integer, fraction);
+        let significant = digits.trim_start_matches('0');
+        let exponent = exponent.parse::<i64>().ok()? + integer.len() as i64 - (digits.len() - significant.len()) as i64;
+        let significant = significant.trim_end_matches('0');
+        if significant.is_empty() {
+            return Some((false, String::new(), 0));
+        }
+
+        return Some((negative, significant.to_string(), exponent));
+    };
+
+    return normalize(first).is_some_and(|first| normalize(second) == Some(first));
+}
+
+/// Gets the current value and key at the specified position in a JSON structure.
+///
+/// This function is used for editing existing values. It returns the parent container,
+/// the key (if in an object), the current value, and the index within the parent.
+///
+/// # Arguments
+///
+/// * `steps` - The position (zero-based) to locate in the JSON structure.
+/// * `obj` - The JSON value to traverse.
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * `Option<&Value>` - The parent object/array containing the element at the specified position.
+/// * `Option<String>` - The key if the element is in an object, None if in an array.
+/// * `Option<&Value>` - The current value at the specified position.
+/// * `usize` - The index of the element within its parent.
+pub fn get_current_value_at_position(steps: usize, obj: &Value) -> (Option<&Value>, Option<String>, Option<&Value>, usize) {
+    fn find_value_path<'a>(current: &mut usize, target: usize, value: &'a Value) -> Option<(Vec<usize>, Option<String>, &'a Value)> {
+        return match value {
+            Value::Object(map) => {
+                for (i, (key, v)) in map.iter().enumerate() {
+                    if *current == target {
+                        return Some((vec![i], Some(key.clone()), v));
+                    }
+
+                    *current += 1;
+                    
+                    if v.is_object() || v.is_array() {
+                        if let Some((mut path, found_key, found_value)) = find_value_path(current, target, v) {
+                            path.insert(0, i);
+                            return Some((path, found_key, found_value));
+                        }
+                    }
+                }
+                None
+            },
+            Value::Array(arr) => {
+                for (i, v) in arr.iter().enumerate() {
+                    if *current == target && !v.is_object() && !v.is_array() {
+                        return Some((vec![i], None, v));
+                    }
+                    
+                    if v.is_object() || v.is_array() {
+                        if let Some((mut path, found_key, found_value)) = find_value_path(current, target, v) {
+                            path.insert(0, i);
+                            return Some((path, found_key, found_value));
+                        }
+                    } else {
+                        *current += 1;
+                    }
+                }
+                None
+            },
+            _ => None
+        };
+    }
+    
+    fn follow_path_to_parent<'a>(obj: &'a Value, path: &[usize]) -> Option<&'a Value> {
+        if path.is_empty() || path.len() == 1 {
+            return Some(obj);
+        }
+        
+        let index_at_the_root = path[0];
+        
+        match obj {
+            Value::Object(map) => {
+                let key = map.keys().nth(index_at_the_root);
+                if let Some(key) = key {
+                    if let Some(value) = map.get(key) {
+                        return follow_path_to_parent(value, &path[1..]);
+                    }
+                }
+            },
+            Value::Array(arr) => {
+                if index_at_the_root < arr.len() {
+                    if let Some(value) = arr.get(index_at_the_root) {
+                        return follow_path_to_parent(value, &path[1..]);
+                    }
+                }
+            },
+            _ => {}
+        }
+        
+        None
+    }
+    
+    let mut current = 0;
+    if let Some((path, key, value)) = find_value_path(&mut current, steps, obj) {
+        let parent = follow_path_to_parent(obj, &path);
+        let index = if path.is_empty() { 0 } else { path[path.len() - 1] };
+        return (parent, key, Some(value), index);
+    }
+    
+    (None, None, None, 0)
+}
+
+/// Finds a JSON element at a specific position within a nested JSON structure.
+///
+/// This function traverses a JSON structure (containing objects and arrays) in a depth-first manner,
+/// counting elements as it goes, until it reaches the element at the specified position.
+///
+/// # Arguments
+///
+/// * `steps` - The position (zero-based) to locate in the JSON structure.
+/// * `obj` - The JSON value to traverse, typically the root of the JSON structure.
+///
+/// # Returns
+///
+/// A tuple containing:
+/// * `Option<&Value>` - The parent object/array containing the element at the specified position,
+///   or `None` if the position is out of bounds.
+/// * `usize` - The index of the element within its parent.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use todd_core::json::get_nested_object_to_insert_into;
+///
+/// let mut value = json!({
+///     "name": "Jane Doe",         // position 0
+///     "address": {                // position 1
+///         "street": "123 Main St", // position 2
+///         "city": "Anytown"        // position 3
+///     },
+///     "hobbies": [                // position 4
+///         "reading",              // position 5
+///         "hiking"                // position 6
+///     ]
+/// });
+///
+/// // Find the "address" object
+/// let (obj_ref, index) = get_nested_object_to_insert_into(1, &mut value);
+/// assert!(obj_ref.is_some());
+/// assert_eq!(index, 1); // "address" is the second key in the root object
+///
+/// // Find the "hobbies" array
+/// let (obj_ref, index) = get_nested_object_to_insert_into(4, &mut value);
+/// assert!(obj_ref.is_some());
+/// assert_eq!(index, 2); // "hobbies" is the third key in the root object
+///
+/// // Find an element in the array
+/// let (obj_ref, index) = get_nested_object_to_insert_into(6, &mut value);
+/// assert!(obj_ref.is_some());
+/// let array = obj_ref.unwrap().as_array().unwrap();
+/// assert_eq!(array[index].as_str().unwrap(), "hiking");
+///
+/// // Position beyond the structure returns None
+/// let (obj_ref, _) = get_nested_object_to_insert_into(10, &mut value);
+/// assert!(obj_ref.is_none());
+/// ```
+///
+/// # Note
+///
+/// - It returns a mutable reference of the nested object, and doesn't actually
+///   anything itself.
+/// - Positions are counted in a depth-first traversal order.
+/// - The function returns the parent container, not the element itself.
+/// - For performance reasons, no validation is performed on the input JSON structure.
+pub fn get_nested_object_to_insert_into(steps: usize, obj: &mut Value) -> (Option<&mut Value>, usize) {
+
+    /// Follows the path returned from `find_path`.
+    fn follow_path<'a>(obj: &'a mut Value, path: &[usize]) -> (Option<&'a mut Value>, usize) {
+        if path.is_empty() {
+            return (None, 0);
+        }
+        
+        let index_at_the_root = path[0];
+        
+        // @Enhancement: Make sure that's the value is not an object or an array when we 
+        // reach it here. If it's not so, return the the obj/arr value with 0 as an index.
+        if path.len() == 1 {
+            return (Some(obj), index_at_the_root);
+        }
+        
+        match obj {
+            Value::Object(map) => {
+                let key = map.keys().nth(index_at_the_root).cloned();
+                if let Some(key) = key {
+                    if let Some(value) = map.get_mut(&key) {
+                        return follow_path(value, &path[1..]);
+                    }
+                }
+            },
+            Value::Array(arr) => {
+                if index_at_the_root < arr.len() {
+                    if let Some(value) = arr.get_mut(index_at_the_root) {
+                        return follow_path(value, &path[1..]);
+                    }
+                }
+            },
+            _ => {}
+        }
+        
+        return (None, 0);
+    }
+    
+    let mut current = 0;
+    let path = find_path(&mut current, steps, obj);
+    
+    return if let Some(path) = path {
+        follow_path(obj, &path)
+    } else {
+        (None, 0)
+    };
+}
+
+/// Gives back a vector of indexes to follow like: [4, 2, 0] meaning: the fifth element
+/// at the root is an object and at that object's third index, there's an object that 
+/// contains our target it its first index.
+/// # Implementation
+/// Runs down the objet, returns a vec with the current index the element is found at,
+/// then, recursively, the call above us in the chain inserts the current index before
+/// the value returned.
+fn find_path(current: &mut usize, target: usize, value: &Value) -> Option<Vec<usize>> {
+    return match value {
+        Value::Object(map) => {
+            for (i, (_, v)) in map.iter().enumerate() {
+                if *current == target {
+                    return Some(vec![i]);
+                }
+
+                *current += 1;
+                
+                if v.is_object() || v.is_array() {
+                    if let Some(mut path) = find_path(current, target, v) {
+                        path.insert(0, i);
+                        return Some(path);
+                    }
+                }
+            }
+            None
+        },
+        Value::Array(arr) => {
+            for (i, v) in arr.iter().enumerate() {
+                if *current == target && !v.is_object() && !v.is_array() {
+                    return Some(vec![i]);
+                }
+                
+                if v.is_object() || v.is_array() {
+                    if let Some(mut path) = find_path(current, target, v) {
+                        path.insert(0, i); // Insert at 0 in the path vec the current index.
+                        return Some(path);
+                    }
+                } else {
+                    *current += 1;
+                }
+            }
+            None
+        },
+        _ => None
+    };
+}
+
+/// The path to the container `get_nested_object_to_insert_into` gives for the same steps, and
+/// the index in it.
+pub fn get_path_to_insert_into(steps: usize, obj: &Value) -> (Option<Vec<PathSegment>>, usize) {
+    let mut current = 0;
+    let Some(indexes) = find_path(&mut current, steps, obj) else {
+        return (None, 0);
+    };
+
+    let (last, parents) = indexes.split_last().unwrap();
+    let mut path = vec![];
+    let mut value = obj;
+    for index in parents {
+        let (segment, child) = match value {
+            Value::Object(map) => match map.iter().nth(*index) {
+                Some((key, child)) => (PathSegment::Key(key.clone()), child),
+                None => return (None, 0),
+            },
+            Value::Array(arr) => match arr.get(*index) {
+                Some(child) => (PathSegment::Index(*index), child),
+                None => return (None, 0),
+            },
+            _ => return (None, 0),
+        };
+        path.push(segment);
+        value = child;
+    }
+
+    return (Some(path), *last);
+}
+
+/// Gets the value rendered at the given line of the tree view.
+///
+/// Every value below the root takes exactly one line in the view (object and array
+/// headers included), so the line number is the position of the value in a depth-first
+/// walk of the document. Unlike `get_current_value_at_position`, this also returns
+/// objects and arrays.
+pub fn get_value_at_line(line: usize, obj: &Value) -> Option<&Value> {
+    fn walk<'a>(current: &mut usize, target: usize, value: &'a Value) -> Option<&'a Value> {
+        let children: Vec<&Value> = match value {
+            Value::Object(map) => map.values().collect(),
+            Value::Array(arr) => arr.iter().collect(),
+            _ => return None,
+        };
+
+        for child in children {
+            if *current == target {
+                return Some(child);
+            }
+
+            *current += 1;
+
+            if let Some(found) = walk(current, target, child) {
+                return Some(found);
+            }
+        }
+
+        return None;
+    }
+
+    let mut current = 0;
+
+    return walk(&mut current, line, obj);
+}
+
+/// The key or index and the value rendered at every line of the tree view, in order.
+/// See `get_value_at_line` for how lines map to values.
+pub fn entries_by_line(obj: &Value) -> Vec<(PathSegment, &Value)> {
+    fn walk<'a>(entries: &mut Vec<(PathSegment, &'a Value)>, value: &'a Value) {
+        let children: Vec<(PathSegment, &Value)> = match value {
+            Value::Object(map) => map.iter().map(|(key, child)| (PathSegment::Key(key.clone()), child)).collect(),
+            Value::Array(arr) => arr.iter().enumerate().map(|(index, child)| (PathSegment::Index(index), child)).collect(),
+            _ => return,
+        };
+
+        for (segment, child) in children {
+            entries.push((segment, child));
+            walk(entries, child);
+        }
+    }
+
+    let mut entries = vec![];
+    walk(&mut entries, obj);
+
+    return entries;
+}
+
+/// Gets the path to the value rendered at the given line of the tree view.
+/// See `get_value_at_line` for how lines map to values.
+pub fn get_path_at_line(line: usize, obj: &Value) -> Option<Vec<PathSegment>> {
+    fn walk(current: &mut usize, target: usize, value: &Value) -> Option<Vec<PathSegment>> {
+        let children: Vec<(PathSegment, &Value)> = match value {
+            Value::Object(map) => map.iter().map(|(k, v)| (PathSegment::Key(k.clone()), v)).collect(),
+            Value::Array(arr) => arr.iter().enumerate().map(|(i, v)| (PathSegment::Index(i), v)).collect(),
+            _ => return None,
+        };
+
+        for (segment, child) in children {
+            if *current == target {
+                return Some(vec![segment]);
+            }
+
+            *current += 1;
+
+            if let Some(mut path) = walk(current, target, child) {
+                path.insert(0, segment);
+                return Some(path);
+            }
+        }
+
+        return None;
+    }
+
+    let mut current = 0;
+
+    return walk(&mut current, line, obj);
+}
+
+/// Gets the value at the given path, to change it.
+pub fn get_value_at_path_mut<'a>(path: &[PathSegment], obj: &'a mut Value) -> Option<&'a mut Value> {
+    let mut value = obj;
+
+    for segment in path {
+        value = match (segment, value) {
+            (PathSegment::Key(key), Value::Object(map)) => map.get_mut(key)?,
+            (PathSegment::Index(index), Value::Array(arr)) => arr.get_mut(*index)?,
+            _ => return None,
+        };
+    }
+
+    return Some(value);
+}
+
+/// The inverse of `get_path_at_line`. Gets the line the value at the given path is rendered at.
+pub fn get_line_at_path(path: &[PathSegment], obj: &Value) -> Option<usize> {
+    /// Counts the lines taken by all the values nested under the given one.
+    fn count_nested_lines(value: &Value) -> usize {
+        return match value {
+            Value::Object(map) => map.values().map(|v| 1 + count_nested_lines(v)).sum(),
+            Value::Array(arr) => arr.iter().map(|v| 1 + count_nested_lines(v)).sum(),
+            _ => 0,
+        };
+    }
+
+    let (segment, rest) = path.split_first()?;
+
+    let children: Vec<&Value> = match obj {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(arr) => arr.iter().collect(),
+        _ => return None,
+    };
+
+    let position = match (segment, obj) {
+        (PathSegment::Key(key), Value::Object(map)) => map.keys().position(|k| k == key)?,
+        (PathSegment::Index(index), Value::Array(arr)) if *index < arr.len() => *index,
+        _ => return None,
+    };
+
+    // Every sibling before the target takes one line plus the lines of what's nested under it.
+    let line: usize = children[..position].iter().map(|v| 1 + count_nested_lines(v)).sum();
+
+    if rest.is_empty() {
+        return Some(line);
+    }
+
+    return get_line_at_path(rest, children[position]).map(|nested_line| line + 1 + nested_line);
+}
+
+/// Writes a path as a JSON Pointer (`/items/0/name`).
+pub fn path_to_pointer(path: &[PathSegment]) -> String {
+    return path
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+            PathSegment::Index(index) => format!("/{}", index),
+        })
+        .collect();
+}
+
+/// Writes a path the way it's read, keys separated by dots and indexes in brackets (`items[0].name`).
+pub fn path_to_string(path: &[PathSegment]) -> String {
+    let mut string = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) if string.is_empty() => string.push_str(key),
+            PathSegment::Key(key) => string.push_str(&format!(".{}", key)),
+            PathSegment::Index(index) => string.push_str(&format!("[{}]", index)),
+        }
+    }
+
+    return string;
+}
+
+/// Reads a path written the way `path_to_string` writes it, with or without a leading dot, like
+/// `.items[0].name`. Keys with dots or brackets in them are quoted in brackets (`["a.b"]`), and
+/// `.` alone is the document itself.
+pub fn parse_path(text: &str) -> Result<Vec<PathSegment>, String> {
+    let mut path = vec![];
+    let mut rest = text.strip_prefix('.').unwrap_or(text);
+
+    while !rest.is_empty() {
+        if let Some(bracketed) = rest.strip_prefix('[') {
+            if bracketed.starts_with('"') {
+                let mut keys = serde_json::Deserializer::from_str(bracketed).into_iter::<String>();
+                let Some(Ok(key)) = keys.next() else {
+                    return Err(format!("Invalid quoted key in {}", text));
+                };
+                rest = bracketed[keys.byte_offset()..].strip_prefix(']').ok_or_else(|| format!("Missing ] in {}", text))?;
+                path.push(PathSegment::Key(key));
+            } else {
+                let (index, after) = bracketed.split_once(']').ok_or_else(|| format!("Missing ] in {}", text))?;
+                let index = index.trim().parse().map_err(|_| format!("Invalid index [{}] in {}", index, text))?;
+                path.push(PathSegment::Index(index));
+                rest = after;
+            }
+            continue;
+        }
+
+        // The first key comes without a dot, the next ones after one.
+        let key_start = if path.is_empty() { rest } else { rest.strip_prefix('.').ok_or_else(|| format!("Expected . or [ at {} in {}", rest, text))? };
+        let end = key_start.find(['.', '[']).unwrap_or(key_start.len());
+        if end == 0 {
+            return Err(format!("Missing key in {}", text));
+        }
+        path.push(PathSegment::Key(key_start[..end].to_string()));
+        rest = &key_start[end..];
+    }
+
+    return Ok(path);
+}
+
+/// A copy of the value with the keys of all its objects sorted alphabetically and, given a
+/// field, its arrays of objects sorted by their value at the field. Elements without the field
+/// go last, and equal elements keep their order.
+pub fn sorted(value: &Value, field: Option<&str>) -> Value {
+    return match value {
+        Value::Object(map) => {
+            let mut map: serde_json::Map<String, Value> = map.iter().map(|(key, value)| (key.clone(), sorted(value, field))).collect();
+            map.sort_keys();
+            Value::Object(map)
+        }
+        Value::Array(values) => {
+            let mut values: Vec<Value> = values.iter().map(|value| sorted(value, field)).collect();
+            if let Some(field) = field {
+                values.sort_by(|a, b| compare_for_sorting(a.get(field), b.get(field)));
+            }
+            Value::Array(values)
+        }
+        _ => value.clone(),
+    };
+}
+
+/// Orders values by type (numbers, strings, booleans, null, others, then missing values), and
+/// numbers and strings by themselves.
+fn compare_for_sorting(a: Option<&Value>, b: Option<&Value>) -> std::cmp::Ordering {
+    let rank = |value: Option<&Value>| match value {
+        Some(Value::Number(_)) => 0,
+        Some(Value::String(_)) => 1,
+        Some(Value::Bool(_)) => 2,
+        Some(Value::Null) => 3,
+        Some(_) => 4,
+        None => 5,
+    };
+
+    return match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a.as_f64().partial_cmp(&b.as_f64()).unwrap_or(std::cmp::Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        (Some(Value::Bool(a)), Some(Value::Bool(b))) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    };
+}
+
+/// Reads a JSON Pointer back into a path of the document. Tokens are indices where the value
+/// they lead into is an array. `None` if the pointer leads nowhere in the document.
+pub fn pointer_to_path(pointer: &str, json: &Value) -> Option<Vec<PathSegment>> {
+    let mut path = vec![];
+    let mut current = json;
+
+    for token in parse_pointer(pointer).ok()? {
+        match current {
+            Value::Object(map) => {
+                current = map.get(&token)?;
+                path.push(PathSegment::Key(token));
+            }
+            Value::Array(values) => {
+                let index: usize = token.parse().ok()?;
+                current = values.get(index)?;
+                path.push(PathSegment::Index(index));
+            }
+            _ => return None,
+        }
+    }
+
+    return Some(path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_get_nested_object_to_insert_into_basic() {
+        let mut value = json!({
+            "name": "Jane Doe", // 0
+            "age": 9, // 1
+            "address": { // 2
+                "street": "123 Main St", // 3
+                "city": "Anytown", // 4
+                "state": "CA", // 5
+                "zip": "12345" // 6
+            },
+            "salary": "5", // 7
+            "billing_info": { // 8
+                "card_number": "1234567890123456", // 9
+                "expiry_date": "12/25", // 10
+                "invoices": [ // 11
+                    {
+                        "amount": 100.0, // 12
+                        "due_date": "2023-06-30" // 13
+                    },
+                    {
+                        "amount": 100.0, // 14
+                        "due_date": "2023-06-30" // 15
+                    },
+                ],
+                "cvv": "123" // 16
+            },
+            "currency": "USD", // 17
+            "currency_symbol": "$", // 18
+        });
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(0, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 0);
+            assert!(obj.as_object().unwrap().contains_key("name"));
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(2, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 2);
+            assert!(obj.as_object().unwrap().contains_key("address"));
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(8, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 4);
+            assert!(obj.as_object().unwrap().contains_key("billing_info"));
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(11, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 2);
+            assert!(obj.as_object().unwrap().contains_key("invoices"));
+            assert!(obj.as_object().unwrap()["invoices"].is_array());
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(12, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 0);
+            assert!(obj.as_object().unwrap()["amount"].is_number());
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(20, &mut value);
+            assert!(obj_ref.is_none());
+            assert_eq!(index, 0);
+        }
+    }
+
+    #[test]
+    fn test_get_nested_object_to_insert_into_array_root() {
+        let mut value = json!([
+            "item1", // 0
+            "item2", // 1
+            { 
+                "key": "value", // 2
+                "nested": { // 3
+                    "deep": "value" // 4
+                }
+            },
+            [
+                "nested1", // 5
+                "nested2"  // 6
+            ]
+        ]);
+
+        // Test case 1: Get root array (line 0)
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(0, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 0);
+            assert!(obj.is_array());
+        }
+
+        // Test case 2: Get object in array (line 2)
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(2, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 0);
+            assert!(obj.as_object().unwrap()["key"].is_string());
+        }
+
+        // test case 3: get nested array in array (line 4)
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(4, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 0);
+            assert!(obj.as_object().unwrap()["deep"].is_string());
+        }
+    }
+
+    #[test]
+    fn test_get_nested_object_to_insert_into_mixed_types() {
+        let mut value = json!({
+            "primitives": [ // 0
+                123, // 1
+                true, // 2
+                null, // 3
+                "string" // 4
+            ],
+            "empty": {}, // 5
+            "nested_empty": { // 6
+                "inner_empty": {} // 7
+            }
+        });
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(0, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 0);
+            assert!(obj.as_object().unwrap().contains_key("primitives"));
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(4, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 3);
+            assert!(obj.is_array());
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(6, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 2);
+            assert!(obj.as_object().unwrap().contains_key("nested_empty"));
+        }
+
+        {
+            let (obj_ref, index) = get_nested_object_to_insert_into(7, &mut value);
+            assert!(obj_ref.is_some());
+            let obj = obj_ref.unwrap();
+            assert_eq!(index, 0);
+            assert!(obj.as_object().unwrap().contains_key("inner_empty"));
+        }
+    }
+
+    #[test]
+    fn test_get_value_at_line() {
+        let value = json!({
+            "name": "Jane Doe", // 0
+            "hobbies": [ // 1
+                "reading", // 2
+                { // 3
+                    "title": "hiking" // 4
+                }
+            ],
+            "empty": {} // 5
+        });
+
+        assert_eq!(get_value_at_line(0, &value), Some(&json!("Jane Doe")));
+        assert_eq!(get_value_at_line(1, &value), Some(&json!(["reading", { "title": "hiking" }])));
+        assert_eq!(get_value_at_line(2, &value), Some(&json!("reading")));
+        assert_eq!(get_value_at_line(3, &value), Some(&json!({ "title": "hiking" })));
+        assert_eq!(get_value_at_line(4, &value), Some(&json!("hiking")));
+        assert_eq!(get_value_at_line(5, &value), Some(&json!({})));
+        assert_eq!(get_value_at_line(6, &value), None);
+
+        let entries = entries_by_line(&value);
+        assert_eq!(entries.len(), 6);
+        assert!((0..6).all(|line| Some(entries[line].1) == get_value_at_line(line, &value)));
+        assert_eq!(entries[3].0, PathSegment::Index(1));
+        assert_eq!(entries[4].0, PathSegment::Key("title".to_string()));
+    }
+
+    #[test]
+    fn test_path_and_line_conversions() {
+        let value = json!({
+            "name": "Jane Doe", // 0
+            "hobbies": [ // 1
+                "reading", // 2
+                { // 3
+                    "title": "hiking" // 4
+                }
+            ],
+            "empty": {} // 5
+        });
+
+        let key = |k: &str| PathSegment::Key(k.to_string());
+
+        assert_eq!(get_path_at_line(0, &value), Some(vec![key("name")]));
+        assert_eq!(get_path_at_line(3, &value), Some(vec![key("hobbies"), PathSegment::Index(1)]));
+        assert_eq!(get_path_at_line(4, &value), Some(vec![key("hobbies"), PathSegment::Index(1), key("title")]));
+        assert_eq!(get_path_at_line(5, &value), Some(vec![key("empty")]));
+        assert_eq!(get_path_at_line(6, &value), None);
+
+        for line in 0..6 {
+            let path = get_path_at_line(line, &value).unwrap();
+            assert_eq!(get_line_at_path(&path, &value), Some(line));
+        }
+
+        assert_eq!(get_line_at_path(&[key("missing")], &value), None);
+        assert_eq!(get_line_at_path(&[key("hobbies"), PathSegment::Index(2)], &value), None);
+        assert_eq!(get_line_at_path(&[], &value), None);
+
+        for line in 0..6 {
+            let path = get_path_at_line(line, &value).unwrap();
+            assert_eq!(pointer_to_path(&path_to_pointer(&path), &value), Some(path));
+        }
+
+        assert_eq!(pointer_to_path("", &value), Some(vec![]));
+        assert_eq!(pointer_to_path("/hobbies/first", &value), None);
+        assert_eq!(pointer_to_path("/name/0", &value), None);
+
+        let path = vec![PathSegment::Key("billing_info".to_string()), PathSegment::Key("invoices".to_string()), PathSegment::Index(1)];
+        assert_eq!(path_to_string(&path), "billing_info.invoices[1]");
+        assert_eq!(path_to_string(&[PathSegment::Index(0), PathSegment::Key("id".to_string())]), "[0].id");
+
+        // Read back, with or without the leading dot.
+        assert_eq!(parse_path("billing_info.invoices[1]"), Ok(path.clone()));
+        assert_eq!(parse_path(".billing_info.invoices[1]"), Ok(path));
+        assert_eq!(parse_path("[0][\"a.b\"].c"), Ok(vec![PathSegment::Index(0), key("a.b"), key("c")]));
+        assert_eq!(parse_path("."), Ok(vec![]));
+        assert!(parse_path("a..b").is_err());
+        assert!(parse_path("a[x]").is_err());
+        assert!(parse_path("a[0]b").is_err());
+    }
+
+    #[test]
+    fn test_get_path_to_insert_into() {
+        let value = json!({
+            "name": "Jane Doe", // 0
+            "hobbies": [ // 1
+                { // Not a step.
+                    "title": "hiking" // 2
+                },
+                "reading" // 3
+            ]
+        });
+
+        assert_eq!(get_path_to_insert_into(0, &value), (Some(vec![]), 0));
+        assert_eq!(get_path_to_insert_into(2, &value), (Some(vec![PathSegment::Key("hobbies".to_string()), PathSegment::Index(0)]), 0));
+        assert_eq!(get_path_to_insert_into(3, &value), (Some(vec![PathSegment::Key("hobbies".to_string())]), 1));
+        assert_eq!(get_path_to_insert_into(4, &value), (None, 0));
+    }
+
+    #[test]
+    fn test_sorted() {
+        let value = json!({
+            "b": 1,
+            "a": { "d": 2, "c": 3 },
+            "items": [{ "id": 3 }, { "name": "x" }, { "id": 1, "z": 0, "y": 0 }, { "id": "2" }]
+        });
+
+        let by_keys = sorted(&value, None);
+        assert_eq!(serde_json::to_string(&by_keys).unwrap(), r#"{"a":{"c":3,"d":2},"b":1,"items":[{"id":3},{"name":"x"},{"id":1,"y":0,"z":0},{"id":"2"}]}"#);
+
+        let by_id = sorted(&value, Some("id"));
+        assert_eq!(by_id["items"], json!([{ "id": 1, "y": 0, "z": 0 }, { "id": 3 }, { "id": "2" }, { "name": "x" }]));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,337 @@
+//
+// JSON with comments (JSONC), like `tsconfig.json` or VS Code's settings. The comments are lifted
+// out of the text so that it parses as plain JSON, and are kept by the path of the value they
+// belong to so they can be shown and written back.
+//
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::json::PathSegment;
+
+/// The comments of a document, by the path of the value they belong to. Comments are kept as
+/// written, `//` and `/* */` included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comments {
+    /// Comments on the lines right before a value.
+    pub leading: HashMap<Vec<PathSegment>, Vec<String>>,
+    /// A comment at the end of the line a value ends on.
+    pub trailing: HashMap<Vec<PathSegment>, String>,
+    /// Comments after the last member of an object/array, before its closing bracket. Comments
+    /// after the whole document are kept under the root (the empty path) too.
+    pub closing: HashMap<Vec<PathSegment>, Vec<String>>,
+}
+
+impl Comments {
+    /// Whether the document has no comments to write back.
+    pub fn is_empty(&self) -> bool {
+        return self.leading.is_empty() && self.trailing.is_empty() && self.closing.is_empty();
+    }
+}
+
+/// An object or array the scanner is inside of.
+struct Container {
+    is_object: bool,
+    /// The key of the member being read, in objects.
+    key: String,
+    /// The index of the member being read, in arrays.
+    index: usize,
+    expecting_key: bool,
+}
+
+fn path_of(containers: &[Container]) -> Vec<PathSegment> {
+    return containers
+        .iter()
+        .map(|container| match container.is_object {
+            true => PathSegment::Key(container.key.clone()),
+            false => PathSegment::Index(container.index),
+        })
+        .collect();
+}
+
+/// Takes the comments and trailing commas out of a JSONC text, giving back plain JSON text and
+/// the comments that were in it.
+///
+/// The text isn't validated. Whatever isn't valid JSON once the comments are gone is left for the
+/// JSON parser to report.
+pub fn strip_comments(text: &str) -> (String, Comments) {
+    let mut output = String::with_capacity(text.len());
+    let mut comments = Comments::default();
+
+    let mut containers: Vec<Container> = vec![];
+    // Comments waiting for the value they come before.
+    let mut pending: Vec<String> = vec![];
+    // The last value that ended, as long as we are still on the line it ended on.
+    let mut value_ending_on_this_line: Option<Vec<PathSegment>> = None;
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+
+    // Where a value starts, the comments before it are attached to it. In objects, that's done
+    // at the key already.
+    let start_value = |containers: &[Container], pending: &mut Vec<String>, comments: &mut Comments| {
+        if containers.last().is_none_or(|container| !container.is_object) && !pending.is_empty() {
+            comments.leading.insert(path_of(containers), std::mem::take(pending));
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        match c {
+            '/' if matches!(chars.get(i + 1), Some('/') | Some('*')) => {
+                let start = i;
+                if chars[i + 1] == '/' {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                } else {
+                    i += 2;
+                    while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                        i += 1;
+                    }
+                    i = (i + 2).min(chars.len());
+                }
+
+                let comment: String = chars[start..i].iter().collect::<String>().trim_end().to_string();
+
+                match value_ending_on_this_line.take() {
+                    Some(path) if !comments.trailing.contains_key(&path) => {
+                        comments.trailing.insert(path, comment);
+                    }
+                    _ => pending.push(comment),
+                }
+                continue;
+            }
+            '"' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+
+                let literal: String = chars[start..i].iter().collect();
+                output.push_str(&literal);
+
+                match containers.last_mut() {
+                    Some(container) if container.is_object && container.expecting_key => {
+                        container.key = serde_json::from_str(&literal).unwrap_or_default();
+                        container.expecting_key = false;
+
+                        if !pending.is_empty() {
+                            comments.leading.insert(path_of(&containers), std::mem::take(&mut pending));
+                        }
+                    }
+                    _ => {
+                        start_value(&containers, &mut pending, &mut comments);
+                        value_ending_on_this_line = Some(path_of(&containers));
+                    }
+                }
+                continue;
+            }
+            '{' | '[' => {
+                start_value(&containers, &mut pending, &mut comments);
+                containers.push(Container {
+                    is_object: c == '{',
+                    key: String::new(),
+                    index: 0,
+                    expecting_key: c == '{',
+                });
+                value_ending_on_this_line = None;
+            }
+            '}' | ']' => {
+                // Trailing commas aren't valid JSON.
+                let kept_length = output.trim_end().len();
+                if output[..kept_length].ends_with(',') {
+                    output.replace_range(kept_length - 1..kept_length, "");
+                }
+
+                containers.pop();
+                if !pending.is_empty() {
+                    comments.closing.entry(path_of(&containers)).or_default().append(&mut pending);
+                }
+                value_ending_on_this_line = Some(path_of(&containers));
+            }
+            ',' => {
+                if let Some(container) = containers.last_mut() {
+                    container.index += 1;
+                    container.expecting_key = container.is_object;
+                }
+            }
+            '\n' => {
+                value_ending_on_this_line = None;
+            }
+            c if c.is_whitespace() || c == ':' => {}
+            _ => {
+                // A number, `true`, `false` or `null`.
+                start_value(&containers, &mut pending, &mut comments);
+                let start = i;
+                i += 1;
+                while i < chars.len() && !matches!(chars[i], ',' | '}' | ']' | '/' | '"') && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                output.extend(&chars[start..i]);
+                value_ending_on_this_line = Some(path_of(&containers));
+                continue;
+            }
+        }
+
+        output.push(c);
+        i += 1;
+    }
+
+    if !pending.is_empty() {
+        comments.closing.entry(vec![]).or_default().append(&mut pending);
+    }
+
+    return (output, comments);
+}
+
+/// Pretty-prints JSON the way `serde_json::to_string_pretty` does, with the comments put back
+/// where they belong.
+pub fn to_string_pretty(json: &Value, comments: &Comments) -> String {
+    let mut output = String::new();
+    let mut path = vec![];
+
+    for comment in comments.leading.get(&path).into_iter().flatten() {
+        output.push_str(comment);
+        output.push('\n');
+    }
+
+    write_value(&mut output, json, &mut path, 0, comments);
+
+    if let Some(comment) = comments.trailing.get(&path) {
+        output.push(' ');
+        output.push_str(comment);
+    }
+
+    return output;
+}
+
+fn write_value(output: &mut String, value: &Value, path: &mut Vec<PathSegment>, depth: usize, comments: &Comments) {
+    let members: Vec<(PathSegment, &Value)> = match value {
+        Value::Object(map) => map.iter().map(|(key, value)| (PathSegment::Key(key.clone()), value)).collect(),
+        Value::Array(values) => values.iter().enumerate().map(|(index, value)| (PathSegment::Index(index), value)).collect(),
+        _ => {
+            output.push_str(&value.to_string());
+            return;
+        }
+    };
+
+    let (opening, closing) = if value.is_object() { ('{', '}') } else { ('[', ']') };
+    let closing_comments = comments.closing.get(path.as_slice());
+    let inner_indentation = "  ".repeat(depth + 1);
+
+    output.push(opening);
+
+    if members.is_empty() && closing_comments.is_none() {
+        output.push(closing);
+        return;
+    }
+
+    output.push('\n');
+
+    let members_count = members.len();
+    for (position, (segment, member)) in members.into_iter().enumerate() {
+        path.push(segment);
+
+        for comment in comments.leading.get(path.as_slice()).into_iter().flatten() {
+            output.push_str(&inner_indentation);
+            output.push_str(comment);
+            output.push('\n');
+        }
+
+        output.push_str(&inner_indentation);
+        if let Some(PathSegment::Key(key)) = path.last() {
+            output.push_str(&Value::String(key.clone()).to_string());
+            output.push_str(": ");
+        }
+
+        write_value(output, member, path, depth + 1, comments);
+
+        if position + 1 < members_count {
+            output.push(',');
+        }
+
+        if let Some(comment) = comments.trailing.get(path.as_slice()) {
+            output.push(' ');
+            output.push_str(comment);
+        }
+
+        output.push('\n');
+        path.pop();
+    }
+
+    for comment in closing_comments.into_iter().flatten() {
+        output.push_str(&inner_indentation);
+        output.push_str(comment);
+        output.push('\n');
+    }
+
+    output.push_str(&"  ".repeat(depth));
+    output.push(closing);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TSCONFIG: &str = r#"// Base config.
+{
+  "compilerOptions": {
+    /* Language */
+    "target": "es2020", // Keep in sync with the runtime.
+    "lib": [
+      // The browser.
+      "dom",
+      "es2020",
+    ],
+    // Leftover comment.
+  },
+}"#;
+
+    #[test]
+    fn test_strip_comments() {
+        let (text, comments) = strip_comments(TSCONFIG);
+        let json: Value = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(json, serde_json::json!({ "compilerOptions": { "target": "es2020", "lib": ["dom", "es2020"] } }));
+
+        let key = |key: &str| PathSegment::Key(key.to_string());
+        assert_eq!(comments.leading[&vec![]], vec!["// Base config."]);
+        assert_eq!(comments.leading[&vec![key("compilerOptions"), key("target")]], vec!["/* Language */"]);
+        assert_eq!(comments.trailing[&vec![key("compilerOptions"), key("target")]], "// Keep in sync with the runtime.");
+        assert_eq!(comments.leading[&vec![key("compilerOptions"), key("lib"), PathSegment::Index(0)]], vec!["// The browser."]);
+        assert_eq!(comments.closing[&vec![key("compilerOptions")]], vec!["// Leftover comment."]);
+    }
+
+    #[test]
+    fn test_comments_round_trip() {
+        let (text, comments) = strip_comments(TSCONFIG);
+        let json: Value = serde_json::from_str(&text).unwrap();
+
+        let written = to_string_pretty(&json, &comments);
+        let expected = r#"// Base config.
+{
+  "compilerOptions": {
+    /* Language */
+    "target": "es2020", // Keep in sync with the runtime.
+    "lib": [
+      // The browser.
+      "dom",
+      "es2020"
+    ]
+    // Leftover comment.
+  }
+}"#;
+        assert_eq!(written, expected);
+
+        // Without comments, it's the same as plain pretty-printing.
+        assert_eq!(to_string_pretty(&json, &Comments::default()), serde_json::to_string_pretty(&json).unwrap());
+    }
+}
@@ -0,0 +1,47 @@
+//! The JSON document model of todd, without the terminal interface: the paths into a document
+//! and the lines of its tree, the edits made to it, and how it's read from and written back to
+//! the formats todd opens.
+//!
+//! Documents are `serde_json` values built with the `preserve_order` and `arbitrary_precision`
+//! features: keys keep their order and numbers their exact text.
+//!
+//! ```
+//! use serde_json::json;
+//! use todd_core::{json::{get_line_at_path, get_path_at_line, path_to_pointer}, patch::remove_by_pointer};
+//!
+//! let mut document = json!({ "name": "todd", "tags": ["json", "tui"] });
+//!
+//! let path = get_path_at_line(3, &document).unwrap();
+//! assert_eq!(path_to_pointer(&path), "/tags/1");
+//! assert_eq!(get_line_at_path(&path, &document), Some(3));
+//!
+//! assert_eq!(remove_by_pointer(&mut document, "/tags/0"), Ok(json!("json")));
+//! assert_eq!(document, json!({ "name": "todd", "tags": ["tui"] }));
+//! ```
+
+// Explicit `return`s and un-collapsed `if`s are the house style of this codebase.
+#![allow(
+    clippy::needless_return,
+    clippy::collapsible_if,
+    clippy::collapsible_match,
+    clippy::comparison_to_empty,
+    clippy::len_zero,
+)]
+#![warn(missing_docs)]
+
+/// Reordering, moving and inserting the children of objects and arrays.
+pub mod edit;
+/// How documents are stored on disk: gzip, text encodings, and the syntaxes read into JSON and
+/// written back (JSON, JSONC, JSON5, Hjson, YAML, MessagePack, and XML only read).
+pub mod formats;
+/// Hjson ("human JSON"), read and written.
+pub mod hjson;
+/// Paths into a document and the lines of the tree view. Every key and every element of an array
+/// takes one line, depth first, so a line and a path lead to each other.
+pub mod json;
+/// The comments of JSONC documents, kept by path and written back on save.
+pub mod jsonc;
+/// Edits addressed with JSON Pointers (RFC 6901), JSON Patch (RFC 6902) and JSON Merge Patch (RFC 7386).
+pub mod patch;
+/// XML documents mapped to JSON, to browse them.
+pub mod xml;
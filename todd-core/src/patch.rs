@@ -0,0 +1,469 @@
+//
+// JSON Patch (RFC 6902) and the JSON Pointers (RFC 6901) it uses to address values, as well as
+// JSON Merge Patch (RFC 7386).
+//
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// Why a patch wasn't applied.
+#[derive(Debug, PartialEq)]
+pub enum PatchError {
+    /// The patch isn't an array of operations.
+    NotAnArray,
+    /// An operation failed, so none of them were applied.
+    Failed {
+        /// The index of the operation in the patch, from 0.
+        index: usize,
+        /// A short description of the operation, like `replace /a/b`.
+        description: String,
+        /// Why it failed.
+        error: String,
+    },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            PatchError::NotAnArray => write!(f, "A JSON patch must be an array of operations"),
+            PatchError::Failed { index, description, error } => write!(f, "#{} {}: {}", index + 1, description, error),
+        };
+    }
+}
+
+/// Splits a JSON Pointer like `/a/b~1c/0` into its unescaped reference tokens (`a`, `b/c`, `0`).
+pub fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if !pointer.starts_with('/') {
+        return Err(format!("Invalid JSON pointer \"{}\": it must start with /", pointer));
+    }
+
+    return Ok(
+        pointer[1..]
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect()
+    );
+}
+
+/// Gets the value a JSON Pointer points to.
+pub fn get_by_pointer<'a>(doc: &'a Value, pointer: &str) -> Result<&'a Value, String> {
+    let mut current = doc;
+
+    for token in parse_pointer(pointer)? {
+        current = match current {
+            Value::Object(map) => map.get(&token),
+            Value::Array(values) => parse_array_index(&token).and_then(|index| values.get(index)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("No value at {}", pointer))?;
+    }
+
+    return Ok(current);
+}
+
+fn get_by_tokens_mut<'a>(doc: &'a mut Value, tokens: &[String], pointer: &str) -> Result<&'a mut Value, String> {
+    let mut current = doc;
+
+    for token in tokens {
+        current = match current {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(values) => parse_array_index(token).and_then(|index| values.get_mut(index)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("No value at {}", pointer))?;
+    }
+
+    return Ok(current);
+}
+
+/// Array indexes in pointers are plain decimal numbers without leading zeros.
+fn parse_array_index(token: &str) -> Option<usize> {
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+
+    return token.parse().ok();
+}
+
+/// Adds a value at the pointer. Existing object members are replaced, and array elements
+/// are shifted to make room. `-` as the last token appends to an array.
+pub fn add_by_pointer(doc: &mut Value, pointer: &str, value: Value) -> Result<(), String> {
+    let tokens = parse_pointer(pointer)?;
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+
+    match get_by_tokens_mut(doc, parent_tokens, pointer)? {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        Value::Array(values) => {
+            if last == "-" {
+                values.push(value);
+            } else {
+                match parse_array_index(last) {
+                    Some(index) if index <= values.len() => values.insert(index, value),
+                    _ => return Err(format!("Invalid array index in {}", pointer)),
+                }
+            }
+        }
+        _ => return Err(format!("The parent of {} is not an object or an array", pointer)),
+    }
+
+    return Ok(());
+}
+
+/// Sets the value at the pointer: the one there is replaced, or the value is added as a new
+/// member of the object or a new last element of the array.
+pub fn set_by_pointer(doc: &mut Value, pointer: &str, value: Value) -> Result<(), String> {
+    let tokens = parse_pointer(pointer)?;
+    if let Ok(target) = get_by_tokens_mut(doc, &tokens, pointer) {
+        *target = value;
+        return Ok(());
+    }
+
+    return add_by_pointer(doc, pointer, value);
+}
+
+/// Removes the value at the pointer and gives it back.
+pub fn remove_by_pointer(doc: &mut Value, pointer: &str) -> Result<Value, String> {
+    return remove_with_position(doc, pointer).map(|(removed, _)| removed);
+}
+
+/// Removes the value at the pointer and gives it back, along with the position it had in its parent.
+fn remove_with_position(doc: &mut Value, pointer: &str) -> Result<(Value, usize), String> {
+    let tokens = parse_pointer(pointer)?;
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        return Err("Can't remove the whole document".to_string());
+    };
+
+    let removed = match get_by_tokens_mut(doc, parent_tokens, pointer)? {
+        Value::Object(map) => map
+            .keys()
+            .position(|key| key == last)
+            .and_then(|position| map.shift_remove(last).map(|removed| (removed, position))),
+        Value::Array(values) => match parse_array_index(last) {
+            Some(index) if index < values.len() => Some((values.remove(index), index)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    return removed.ok_or_else(|| format!("No value at {}", pointer));
+}
+
+/// Puts a removed value back at the position it was removed from.
+fn restore_at_position(doc: &mut Value, pointer: &str, value: Value, position: usize) -> Result<(), String> {
+    let tokens = parse_pointer(pointer)?;
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        return Ok(());
+    };
+
+    match get_by_tokens_mut(doc, parent_tokens, pointer)? {
+        Value::Object(map) => {
+            map.shift_insert(position, last.clone(), value);
+        }
+        Value::Array(values) => {
+            values.insert(position, value);
+        }
+        _ => {}
+    }
+
+    return Ok(());
+}
+
+/// Applies a JSON Patch document (an array of operations) to `doc`, and gives back how many
+/// operations it had.
+///
+/// Like RFC 6902 prescribes, the patch is applied as a whole or not at all: the first operation
+/// that fails, a `test` included, leaves `doc` as it was.
+pub fn apply_patch(doc: &mut Value, patch: &Value) -> Result<usize, PatchError> {
+    let Value::Array(operations) = patch else {
+        return Err(PatchError::NotAnArray);
+    };
+
+    let mut patched = doc.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        let op = operation.get("op").and_then(Value::as_str).unwrap_or("?");
+        let path = operation.get("path").and_then(Value::as_str);
+
+        let result = match path {
+            Some(path) => apply_operation(&mut patched, op, path, operation),
+            None => Err("Missing \"path\"".to_string()),
+        };
+        if let Err(error) = result {
+            return Err(PatchError::Failed { index, description: format!("{} {}", op, path.unwrap_or("?")), error });
+        }
+    }
+    *doc = patched;
+
+    return Ok(operations.len());
+}
+
+fn apply_operation(doc: &mut Value, op: &str, path: &str, operation: &Value) -> Result<(), String> {
+    let value = || operation.get("value").cloned().ok_or_else(|| "Missing \"value\"".to_string());
+    let from = || operation.get("from").and_then(Value::as_str).ok_or_else(|| "Missing \"from\"".to_string());
+
+    return match op {
+        "add" => add_by_pointer(doc, path, value()?),
+        "remove" => remove_by_pointer(doc, path).map(|_| ()),
+        "replace" => {
+            let value = value()?;
+            let target = get_by_tokens_mut(doc, &parse_pointer(path)?, path)?;
+            *target = value;
+            Ok(())
+        }
+        "move" => {
+            let from = from()?;
+            if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                return Err(format!("Can't move {} into one of its own children", from));
+            }
+
+            let (moved, position) = remove_with_position(doc, from)?;
+            if let Err(err) = add_by_pointer(doc, path, moved.clone()) {
+                // Put the value back so the operation doesn't half-apply.
+                let _ = restore_at_position(doc, from, moved, position);
+                return Err(err);
+            }
+            Ok(())
+        }
+        "copy" => {
+            let copied = get_by_pointer(doc, from()?)?.clone();
+            add_by_pointer(doc, path, copied)
+        }
+        "test" => {
+            let expected = value()?;
+            if numerically_equal(get_by_pointer(doc, path)?, &expected) {
+                Ok(())
+            } else {
+                Err(format!("The value at {} is not {}", path, expected))
+            }
+        }
+        _ => Err(format!("Unknown operation \"{}\"", op)),
+    };
+}
+
+/// Compares JSON values the way RFC 6902 wants for `test`: numbers are equal when their values
+/// are, however they are written (`1.0` and `1`, `1e3` and `1000`).
+fn numerically_equal(a: &Value, b: &Value) -> bool {
+    return match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b || a.as_f64() == b.as_f64(),
+        },
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| numerically_equal(a, b)),
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| numerically_equal(a, b)))
+        }
+        _ => a == b,
+    };
+}
+
+/// Applies a JSON Merge Patch to `doc`: objects are merged recursively, `null` members are
+/// removed, and anything else replaces the value at the same place.
+pub fn apply_merge_patch(doc: &mut Value, patch: &Value) {
+    let Value::Object(patch_members) = patch else {
+        *doc = patch.clone();
+        return;
+    };
+
+    if !doc.is_object() {
+        *doc = Value::Object(Map::new());
+    }
+
+    let Value::Object(members) = doc else {
+        return;
+    };
+
+    for (key, patch_value) in patch_members {
+        if patch_value.is_null() {
+            members.shift_remove(key);
+        } else {
+            apply_merge_patch(members.entry(key.clone()).or_insert(Value::Null), patch_value);
+        }
+    }
+}
+
+/// Creates the JSON Merge Patch that turns `from` into `to`.
+///
+/// Merge patches can't set a member to `null` (it means "remove"), so those members are
+/// removed instead. `has_lost_nulls` tells whether that happened.
+pub fn create_merge_patch(from: &Value, to: &Value) -> (Value, bool) {
+    let (Value::Object(from_members), Value::Object(to_members)) = (from, to) else {
+        return (to.clone(), contains_null_member(to));
+    };
+
+    let mut patch = Map::new();
+    let mut has_lost_nulls = false;
+
+    for key in from_members.keys() {
+        if !to_members.contains_key(key) {
+            patch.insert(key.clone(), Value::Null);
+        }
+    }
+
+    for (key, to_value) in to_members {
+        match from_members.get(key) {
+            Some(from_value) if from_value == to_value => {}
+            _ if to_value.is_null() => {
+                has_lost_nulls = true;
+                if from_members.contains_key(key) {
+                    patch.insert(key.clone(), Value::Null);
+                }
+            }
+            Some(from_value) => {
+                let (member_patch, member_lost_nulls) = create_merge_patch(from_value, to_value);
+                has_lost_nulls |= member_lost_nulls;
+                patch.insert(key.clone(), member_patch);
+            }
+            None => {
+                has_lost_nulls |= contains_null_member(to_value);
+                patch.insert(key.clone(), to_value.clone());
+            }
+        }
+    }
+
+    return (Value::Object(patch), has_lost_nulls);
+}
+
+/// Whether an object, or one nested in it, has a `null` member. Those get dropped when the
+/// object is applied as part of a merge patch.
+fn contains_null_member(value: &Value) -> bool {
+    let Value::Object(members) = value else {
+        return false;
+    };
+
+    return members.values().any(|member| member.is_null() || contains_null_member(member));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_pointer() {
+        assert_eq!(parse_pointer("").unwrap(), Vec::<String>::new());
+        assert_eq!(parse_pointer("/a/b~1c/~01").unwrap(), vec!["a", "b/c", "~1"]);
+        assert!(parse_pointer("a/b").is_err());
+    }
+
+    #[test]
+    fn test_set_by_pointer() {
+        let mut doc = json!({ "a": { "b": [1, 2] } });
+
+        set_by_pointer(&mut doc, "/a/b/0", json!(42)).unwrap();
+        set_by_pointer(&mut doc, "/a/b/2", json!(3)).unwrap();
+        set_by_pointer(&mut doc, "/a/c", json!("new")).unwrap();
+        assert_eq!(doc, json!({ "a": { "b": [42, 2, 3], "c": "new" } }));
+
+        assert!(set_by_pointer(&mut doc, "/a/b/5", json!(0)).is_err());
+        assert!(set_by_pointer(&mut doc, "/x/y", json!(0)).is_err());
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let mut doc = json!({
+            "name": "Omar",
+            "tags": ["a", "b"],
+            "address": { "city": "Amman", "count": 2 }
+        });
+
+        let patch = json!([
+            { "op": "replace", "path": "/name", "value": "Jane" },
+            { "op": "add", "path": "/tags/1", "value": "x" },
+            { "op": "add", "path": "/tags/-", "value": "z" },
+            { "op": "remove", "path": "/tags/0" },
+            { "op": "copy", "from": "/address/city", "path": "/city" },
+            { "op": "move", "from": "/address", "path": "/location" },
+            { "op": "test", "path": "/name", "value": "Jane" },
+            { "op": "test", "path": "/location/count", "value": 2.0 }
+        ]);
+
+        assert_eq!(apply_patch(&mut doc, &patch), Ok(8));
+        assert_eq!(doc, json!({
+            "name": "Jane",
+            "tags": ["x", "b", "z"],
+            "city": "Amman",
+            "location": { "city": "Amman", "count": 2 }
+        }));
+
+        assert_eq!(apply_patch(&mut doc, &json!({ "op": "remove", "path": "/name" })), Err(PatchError::NotAnArray));
+    }
+
+    #[test]
+    fn test_failing_test_leaves_the_document_alone() {
+        let mut doc = json!({ "version": 2, "name": "Omar" });
+        let patch = json!([
+            { "op": "test", "path": "/version", "value": 1 },
+            { "op": "replace", "path": "/name", "value": "Jane" }
+        ]);
+
+        let error = apply_patch(&mut doc, &patch).unwrap_err();
+
+        assert_eq!(error.to_string(), "#1 test /version: The value at /version is not 1");
+        assert_eq!(doc, json!({ "version": 2, "name": "Omar" }));
+    }
+
+    #[test]
+    fn test_failing_operation_leaves_the_document_alone() {
+        let mut doc = json!({ "a": { "b": 1 }, "list": [] });
+        let patch = json!([
+            { "op": "add", "path": "/list/-", "value": 1 },
+            { "op": "move", "from": "/a", "path": "/list/5" },
+            { "op": "remove", "path": "/a" }
+        ]);
+
+        let error = apply_patch(&mut doc, &patch).unwrap_err();
+
+        assert!(matches!(error, PatchError::Failed { index: 1, .. }));
+        assert_eq!(doc, json!({ "a": { "b": 1 }, "list": [] }));
+        assert_eq!(doc.as_object().unwrap().keys().next().unwrap(), "a");
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        // The example from RFC 7386.
+        let mut doc = json!({
+            "title": "Goodbye!",
+            "author": { "givenName": "John", "familyName": "Doe" },
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+        let patch = json!({
+            "title": "Hello!",
+            "phoneNumber": "+01-123-456-7890",
+            "author": { "familyName": null },
+            "tags": ["example"]
+        });
+        let expected = json!({
+            "title": "Hello!",
+            "author": { "givenName": "John" },
+            "tags": ["example"],
+            "content": "This will be unchanged",
+            "phoneNumber": "+01-123-456-7890"
+        });
+
+        let original = doc.clone();
+        apply_merge_patch(&mut doc, &patch);
+        assert_eq!(doc, expected);
+
+        let (created, has_lost_nulls) = create_merge_patch(&original, &expected);
+        assert!(!has_lost_nulls);
+        assert_eq!(created, patch);
+    }
+
+    #[test]
+    fn test_merge_patch_can_not_set_nulls() {
+        let (patch, has_lost_nulls) = create_merge_patch(&json!({ "a": 1 }), &json!({ "a": null, "b": null }));
+
+        assert!(has_lost_nulls);
+        assert_eq!(patch, json!({ "a": null }));
+    }
+}
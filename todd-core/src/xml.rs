@@ -0,0 +1,172 @@
+//
+// Turning XML documents into JSON so they can be browsed like any other document.
+//
+
+use quick_xml::{escape::resolve_predefined_entity, events::{BytesStart, Event}, Reader, XmlVersion};
+use serde_json::{Map, Value};
+
+/// An element that is still being read.
+struct Element {
+    name: String,
+    members: Map<String, Value>,
+    text: String,
+}
+
+impl Element {
+    fn new(start: &BytesStart) -> Result<Element, String> {
+        let mut members = Map::new();
+
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|err| format!("Invalid XML attribute: {}", err))?;
+            let value = attribute.normalized_value(XmlVersion::Implicit1_0).map_err(|err| format!("Invalid XML attribute: {}", err))?;
+            members.insert(format!("@{}", attribute.key.as_ref()), Value::String(value.to_string()));
+        }
+
+        return Ok(Element {
+            name: start.name().as_ref().to_string(),
+            members,
+            text: String::new(),
+        });
+    }
+
+    /// Elements with only text become strings. Otherwise they're objects with their attributes
+    /// (`@name`), child elements, and text (`#text`).
+    fn into_value(self) -> Value {
+        let text = self.text.trim();
+
+        if self.members.is_empty() {
+            return Value::String(text.to_string());
+        }
+
+        let mut members = self.members;
+        if !text.is_empty() {
+            members.insert("#text".to_string(), Value::String(text.to_string()));
+        }
+
+        return Value::Object(members);
+    }
+}
+
+/// Adds a child element to its parent. Repeated elements are grouped into an array.
+fn add_child(parent: &mut Map<String, Value>, name: String, value: Value) {
+    match parent.get_mut(&name) {
+        Some(Value::Array(siblings)) => siblings.push(value),
+        Some(sibling) => {
+            let first = sibling.take();
+            *sibling = Value::Array(vec![first, value]);
+        }
+        None => {
+            parent.insert(name, value);
+        }
+    }
+}
+
+/// Maps an XML document to JSON: `<root a="1"><item>x</item><item>y</item></root>` becomes
+/// `{ "root": { "@a": "1", "item": ["x", "y"] } }`. Comments and processing instructions are dropped.
+pub fn xml_to_json(content: &str) -> Result<Value, String> {
+    let mut reader = Reader::from_str(content);
+
+    let mut document = Map::new();
+    let mut elements: Vec<Element> = vec![];
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|err| format!("Invalid XML at position {}: {}", reader.error_position(), err))?;
+
+        match event {
+            Event::Start(start) => elements.push(Element::new(&start)?),
+            Event::Empty(start) => {
+                let element = Element::new(&start)?;
+                match elements.last_mut() {
+                    Some(parent) => add_child(&mut parent.members, element.name.clone(), element.into_value()),
+                    None => add_child(&mut document, element.name.clone(), element.into_value()),
+                }
+            }
+            Event::End(_) => {
+                let Some(element) = elements.pop() else {
+                    return Err("Invalid XML: unexpected closing tag".to_string());
+                };
+                let name = element.name.clone();
+                match elements.last_mut() {
+                    Some(parent) => add_child(&mut parent.members, name, element.into_value()),
+                    None => add_child(&mut document, name, element.into_value()),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = elements.last_mut() {
+                    element.text.push_str(&text.xml10_content());
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = elements.last_mut() {
+                    element.text.push_str(&data.xml10_content());
+                }
+            }
+            Event::GeneralRef(reference) => {
+                if let Some(element) = elements.last_mut() {
+                    let resolved = match reference.resolve_char_ref() {
+                        Ok(Some(c)) => c.to_string(),
+                        _ => resolve_predefined_entity(&reference)
+                            .map(str::to_string)
+                            .unwrap_or_else(|| format!("&{};", &*reference)),
+                    };
+                    element.text.push_str(&resolved);
+                }
+            }
+            Event::Eof => break,
+            Event::Comment(_) | Event::Decl(_) | Event::PI(_) | Event::DocType(_) => {}
+        }
+    }
+
+    if !elements.is_empty() {
+        return Err("Invalid XML: unclosed elements".to_string());
+    }
+
+    if document.is_empty() {
+        return Err("The XML document has no root element".to_string());
+    }
+
+    return Ok(Value::Object(document));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_xml_to_json() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- A Maven project. -->
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <artifactId>todd &amp; co</artifactId>
+  <dependencies>
+    <dependency scope="test"><artifactId>junit</artifactId></dependency>
+    <dependency><artifactId>guava</artifactId></dependency>
+  </dependencies>
+  <description><![CDATA[<b>bold</b>]]></description>
+  <empty/>
+</project>"#;
+
+        assert_eq!(
+            xml_to_json(content).unwrap(),
+            json!({
+                "project": {
+                    "@xmlns": "http://maven.apache.org/POM/4.0.0",
+                    "artifactId": "todd & co",
+                    "dependencies": {
+                        "dependency": [
+                            { "@scope": "test", "artifactId": "junit" },
+                            { "artifactId": "guava" }
+                        ]
+                    },
+                    "description": "<b>bold</b>",
+                    "empty": ""
+                }
+            })
+        );
+
+        assert!(xml_to_json("<a><b></a>").is_err());
+    }
+}